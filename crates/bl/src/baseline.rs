@@ -0,0 +1,242 @@
+//! Baselines of existing violations, used by `check --baseline` so that only
+//! violations introduced after the baseline was recorded are reported.
+//!
+//! Each violation is identified by its [fingerprint], which is derived from
+//! the path, rule code, message and source text of the violation rather than
+//! its position, so that it survives lines being added or removed elsewhere
+//! in the file. Paths are recorded relative to the directory that contains
+//! the baseline file, so that the baseline matches regardless of the
+//! directory that `check` is run from.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use bl_diagnostics::{Diagnostic, Diagnostics};
+use bl_lints::message::fingerprint;
+use bl_utils::fs::{normalize_path, relativize_path_to};
+use serde::{Deserialize, Serialize};
+
+/// The version of the baseline format, which is bumped whenever the format
+/// or the computation of fingerprints changes.
+const VERSION: u32 = 1;
+
+/// A violation that was recorded within a [Baseline].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct BaselineEntry {
+    /// The fingerprint of the violation, as a hexadecimal string.
+    pub fingerprint: String,
+
+    /// The path of the file, relative to the directory of the baseline.
+    pub path: String,
+
+    /// The code of the rule, or `None` for syntax errors.
+    pub code: Option<String>,
+
+    /// The message of the violation, which is only recorded so that the file
+    /// can be reviewed.
+    pub message: String,
+}
+
+/// The set of violations that existed when the baseline was recorded.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Baseline {
+    /// The version of the format that the baseline was written with.
+    version: u32,
+
+    /// The recorded violations, ordered by path and then by fingerprint.
+    entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Get the directory that the paths of the baseline at the given path are
+    /// relative to, which is the directory that contains the baseline.
+    pub fn root_of(path: &Path) -> PathBuf {
+        let path = normalize_path(path);
+        path.parent().map_or(path.clone(), Path::to_path_buf)
+    }
+
+    /// Record a [Baseline] of the given diagnostics, with paths relative to
+    /// the given `root`.
+    pub fn from_diagnostics(diagnostics: &Diagnostics, root: &Path) -> Self {
+        let mut entries: Vec<_> = fingerprints(diagnostics, root)
+            .map(|(fingerprint, path, diagnostic)| BaselineEntry {
+                fingerprint,
+                path,
+                code: diagnostic.code.map(str::to_string),
+                message: diagnostic.message.clone(),
+            })
+            .collect();
+        entries
+            .sort_by(|lhs, rhs| (&lhs.path, &lhs.fingerprint).cmp(&(&rhs.path, &rhs.fingerprint)));
+
+        Self { version: VERSION, entries }
+    }
+
+    /// Read a [Baseline] from the given file. A missing file is an error, so
+    /// that a mistyped path doesn't silently pass, unless the baseline is
+    /// being recorded with `update`, in which case `None` is returned.
+    pub fn read(path: &Path, update: bool) -> Result<Option<Self>> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                if update {
+                    return Ok(None);
+                }
+                anyhow::bail!(
+                    "baseline `{}` doesn't exist, use `--update-baseline` to create it",
+                    path.display()
+                );
+            }
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("failed to read baseline `{}`", path.display()))
+            }
+        };
+
+        let baseline: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse baseline `{}`", path.display()))?;
+        if baseline.version != VERSION {
+            anyhow::bail!(
+                "baseline `{}` was written with an unsupported version ({}), re-create it with \
+                 `--update-baseline`",
+                path.display(),
+                baseline.version
+            );
+        }
+
+        Ok(Some(baseline))
+    }
+
+    /// Write the [Baseline] to the given file.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut contents = serde_json::to_string_pretty(self)?;
+        contents.push('\n');
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write baseline `{}`", path.display()))
+    }
+
+    /// The number of violations within the [Baseline].
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Remove all of the diagnostics that were recorded within the
+    /// [Baseline], leaving only new violations. The `root` is the directory
+    /// that the paths of the baseline are relative to.
+    pub fn filter(&self, diagnostics: Diagnostics, root: &Path) -> Diagnostics {
+        let known: BTreeSet<_> = self.entries.iter().map(|entry| &entry.fingerprint).collect();
+        let new: BTreeSet<_> = fingerprints(&diagnostics, root)
+            .enumerate()
+            .filter(|(_, (fingerprint, ..))| !known.contains(fingerprint))
+            .map(|(index, _)| index)
+            .collect();
+
+        diagnostics
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| new.contains(index))
+            .map(|(_, diagnostic)| diagnostic)
+            .collect()
+    }
+
+    /// Find the entries of the [Baseline] which are no longer within the
+    /// `current` baseline, i.e. violations that have since been fixed.
+    pub fn fixed<'a>(&'a self, current: &Baseline) -> Vec<&'a BaselineEntry> {
+        let current: BTreeSet<_> = current.entries.iter().map(|entry| &entry.fingerprint).collect();
+        self.entries.iter().filter(|entry| !current.contains(&entry.fingerprint)).collect()
+    }
+}
+
+/// Compute the fingerprint and the path relative to `root` of each of the
+/// diagnostics, in order. Identical violations within a file are
+/// distinguished by the number of times that they have been seen, as with the
+/// GitLab emitter.
+fn fingerprints<'d>(
+    diagnostics: &'d Diagnostics,
+    root: &'d Path,
+) -> impl Iterator<Item = (String, String, &'d Diagnostic)> {
+    let mut occurrences: HashMap<u64, usize> = HashMap::new();
+
+    diagnostics.iter().map(move |diagnostic| {
+        let path = diagnostic
+            .path()
+            .map(|path| relativize_path_to(normalize_path(path), root))
+            .unwrap_or_default();
+
+        let occurrence = occurrences.entry(fingerprint(diagnostic, &path, 0)).or_default();
+        let fingerprint = fingerprint(diagnostic, &path, *occurrence);
+        *occurrence += 1;
+
+        (format!("{fingerprint:016x}"), path, diagnostic)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics};
+
+    use super::Baseline;
+
+    /// Create a violation of BL002 at the given offset of the source, which is
+    /// registered at the given path.
+    fn diagnostic(path: &str, source: &'static str, start: usize) -> Diagnostic {
+        let id = SourceMap::add(path, source);
+        let span = Span::new(ByteRange::new(start, start + 10), id);
+        Diagnostic::new(DiagnosticKind::Error, "`break` outside of a loop", span).with_code("BL002")
+    }
+
+    #[test]
+    fn baseline_survives_line_shifts() {
+        let root = Path::new("/project");
+        let diagnostic = |source, start| diagnostic("/project/baseline.html", source, start);
+
+        let before = "{% break %}\n{% break %}";
+        let baseline = Baseline::from_diagnostics(
+            &[diagnostic(before, 0), diagnostic(before, 12)].into_iter().collect(),
+            root,
+        );
+
+        // Shifting the existing violations doesn't report them again, only
+        // the additional occurrence is new.
+        let after = "\n\n{% break %}\n{% break %}\n{% break %}";
+        let current: Diagnostics =
+            [diagnostic(after, 2), diagnostic(after, 14), diagnostic(after, 26)]
+                .into_iter()
+                .collect();
+        let new = baseline.filter(current.clone(), root);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new.iter().next().unwrap().span.range.start(), 26);
+
+        // Removing a violation is reported as fixed.
+        let fixed = Baseline::from_diagnostics(&[diagnostic(after, 2)].into_iter().collect(), root);
+        assert_eq!(baseline.fixed(&fixed).len(), 1);
+        assert!(baseline.fixed(&Baseline::from_diagnostics(&current, root)).is_empty());
+    }
+
+    #[test]
+    fn baseline_paths_are_relative_to_its_directory() {
+        let source = "{% break %}";
+        let diagnostics: Diagnostics =
+            [diagnostic("/project/templates/index.html", source, 0)].into_iter().collect();
+
+        let baseline = Baseline::from_diagnostics(&diagnostics, Path::new("/project"));
+        assert_eq!(baseline.entries[0].path, "templates/index.html");
+
+        // The same file matches, whichever directory the check was run from.
+        let root = Baseline::root_of(Path::new("/project/baseline.json"));
+        assert_eq!(root, Path::new("/project"));
+        assert!(baseline.filter(diagnostics.clone(), &root).is_empty());
+
+        // A missing baseline is an error, unless it is being recorded.
+        let missing = std::env::temp_dir().join("bracketlint-missing-baseline.json");
+        let error = Baseline::read(&missing, false).unwrap_err();
+        assert!(error.to_string().contains("use `--update-baseline` to create it"), "{error}");
+        assert!(Baseline::read(&missing, true).unwrap().is_none());
+    }
+}
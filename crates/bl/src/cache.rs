@@ -0,0 +1,335 @@
+//! The on-disk cache of the diagnostics of each file, which allows unchanged
+//! files to be skipped when re-running the `check` command.
+//!
+//! The cache is stored within the [CACHE_DIR] directory of the current working
+//! directory, there is a single cache file for each version of `bracketlint`
+//! and each unique set of settings, i.e. `.bracketlint_cache/0.1.0/<key>.json`.
+//! Each file within the cache is keyed by its path, and stores the hash and the
+//! modification time of the contents that the diagnostics were computed from.
+
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{Context, Result};
+use bl_ast::{ByteRange, SourceId, Span};
+use bl_diagnostics::{Applicability, Diagnostic, DiagnosticKind, Diagnostics, Edit, Fix};
+use bl_lints::registry::rule_by_code;
+use bl_utils::hash::StableHasher;
+use bl_workspace::{settings::Settings, Member};
+use serde::{Deserialize, Serialize};
+
+/// The name of the directory that the cache is stored in.
+pub(crate) const CACHE_DIR: &str = ".bracketlint_cache";
+
+/// The cached diagnostics of all of the files that have been checked with a
+/// particular set of settings.
+pub(crate) struct Cache {
+    /// The path of the cache file.
+    path: PathBuf,
+
+    /// The cached diagnostics of each file.
+    files: BTreeMap<PathBuf, FileCache>,
+
+    /// Whether the cache has been modified since it was loaded.
+    changed: bool,
+}
+
+impl Cache {
+    /// Open the cache within the given `root` directory for the given settings.
+    /// If the cache doesn't exist or is corrupted, an empty cache is returned.
+    pub(crate) fn open(root: &Path, settings: &Settings) -> Self {
+        let path = root
+            .join(CACHE_DIR)
+            .join(env!("CARGO_PKG_VERSION"))
+            .join(format!("{:016x}.json", settings.cache_key()));
+
+        let files = match std::fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents).unwrap_or_else(|error| {
+                log::debug!("discarding corrupted cache `{}`: {error}", path.display());
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        Self { path, files, changed: false }
+    }
+
+    /// Get the cached diagnostics of the given [Member], if the contents of the
+    /// member haven't changed since they were cached.
+    pub(crate) fn get(&self, member: &Member, key: &FileKey) -> Option<Diagnostics> {
+        let file = self.files.get(&member.path)?;
+        if file.key != *key {
+            return None;
+        }
+
+        file.diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_diagnostic(member.source_id))
+            .collect()
+    }
+
+    /// Store the diagnostics of the given [Member]. Diagnostics that refer to
+    /// other files can't be cached, in which case the member is removed from
+    /// the cache.
+    pub(crate) fn update(&mut self, member: &Member, key: FileKey, diagnostics: &Diagnostics) {
+        let cached: Option<Vec<_>> = diagnostics
+            .iter()
+            .map(|diagnostic| CachedDiagnostic::new(diagnostic, member.source_id))
+            .collect();
+
+        match cached {
+            Some(diagnostics) => {
+                self.files.insert(member.path.clone(), FileCache { key, diagnostics });
+            }
+            None => {
+                self.files.remove(&member.path);
+            }
+        }
+
+        self.changed = true;
+    }
+
+    /// Write the cache back to disk if it has been modified, entries for files
+    /// that no longer exist are removed.
+    pub(crate) fn persist(mut self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+
+        self.files.retain(|path, _| path.exists());
+
+        let directory = self.path.parent().unwrap();
+        std::fs::create_dir_all(directory)
+            .with_context(|| format!("failed to create `{}`", directory.display()))?;
+
+        // Ensure that the cache is never committed.
+        let root = directory.parent().unwrap();
+        let gitignore = root.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(gitignore, "*\n")?;
+        }
+
+        // Write to a temporary file first, so that an interrupted write doesn't
+        // leave a partial cache behind.
+        let temporary = self.path.with_extension("json.tmp");
+        std::fs::write(&temporary, serde_json::to_vec(&self.files)?)?;
+        std::fs::rename(&temporary, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Identifies the contents of a file, and the settings of that file, that
+/// diagnostics were computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FileKey {
+    /// The modification time of the file, as seconds and nanoseconds since the
+    /// epoch.
+    mtime: (u64, u32),
+
+    /// The hash of the contents of the file.
+    hash: u64,
+
+    /// The [Settings::cache_key] of the settings of the file, which differ
+    /// from the root settings when a nested directory has its own
+    /// configuration.
+    settings: u64,
+}
+
+impl FileKey {
+    /// Compute the [FileKey] of the given [Member] that is checked with the
+    /// given [Settings].
+    pub(crate) fn of(member: &Member, settings: &Settings) -> Option<Self> {
+        let modified = std::fs::metadata(&member.path).and_then(|data| data.modified()).ok()?;
+        let since_epoch = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        let mut hasher = StableHasher::new();
+        member.contents.hash(&mut hasher);
+
+        Some(Self {
+            mtime: (since_epoch.as_secs(), since_epoch.subsec_nanos()),
+            hash: hasher.finish(),
+            settings: settings.cache_key(),
+        })
+    }
+}
+
+/// The cached diagnostics of a single file.
+#[derive(Serialize, Deserialize)]
+struct FileCache {
+    #[serde(flatten)]
+    key: FileKey,
+    diagnostics: Vec<CachedDiagnostic>,
+}
+
+/// A [Diagnostic] in a form that is independent of the [SourceId] of the file,
+/// all of the ranges are stored as inclusive byte offsets.
+#[derive(Serialize, Deserialize)]
+struct CachedDiagnostic {
+    kind: DiagnosticKind,
+    code: Option<String>,
+    message: String,
+    range: (usize, usize),
+    labels: Vec<((usize, usize), String)>,
+    notes: Vec<String>,
+    help: Option<String>,
+    fix: Option<CachedFix>,
+    children: Vec<CachedDiagnostic>,
+}
+
+/// A [Fix] where each edit is stored as `(start, end, content)` with an
+/// exclusive `end`.
+#[derive(Serialize, Deserialize)]
+struct CachedFix {
+    applicability: Applicability,
+    edits: Vec<(usize, usize, String)>,
+}
+
+impl CachedDiagnostic {
+    /// Convert a [Diagnostic] from the given source into its cached form, if
+    /// any part of the diagnostic refers to another source then it can't be
+    /// cached.
+    fn new(diagnostic: &Diagnostic, source: SourceId) -> Option<Self> {
+        let range =
+            |span: Span| (span.id == source).then_some((span.range.start(), span.range.end()));
+
+        let labels = diagnostic
+            .labels
+            .iter()
+            .map(|label| Some((range(label.span)?, label.message.clone())))
+            .collect::<Option<_>>()?;
+
+        let children = diagnostic
+            .children
+            .iter()
+            .map(|child| CachedDiagnostic::new(child, source))
+            .collect::<Option<_>>()?;
+
+        let fix = diagnostic.fix.as_ref().map(|fix| CachedFix {
+            applicability: fix.applicability(),
+            edits: fix
+                .edits()
+                .iter()
+                .map(|edit| (edit.start(), edit.end(), edit.content().to_string()))
+                .collect(),
+        });
+
+        Some(Self {
+            kind: diagnostic.kind,
+            code: diagnostic.code.map(str::to_string),
+            message: diagnostic.message.clone(),
+            range: range(diagnostic.span)?,
+            labels,
+            notes: diagnostic.notes.clone(),
+            help: diagnostic.help.clone(),
+            fix,
+            children,
+        })
+    }
+
+    /// Convert the cached form back into a [Diagnostic] for the given source,
+    /// rule codes that no longer exist invalidate the cached diagnostic.
+    fn to_diagnostic(&self, source: SourceId) -> Option<Diagnostic> {
+        let span = |(start, end)| Span::new(ByteRange::new(start, end), source);
+
+        let mut diagnostic = Diagnostic::new(self.kind, &self.message, span(self.range));
+
+        if let Some(code) = &self.code {
+            diagnostic = diagnostic.with_code(rule_by_code(code)?.code());
+        }
+
+        for (range, message) in &self.labels {
+            diagnostic = diagnostic.with_label(span(*range), message);
+        }
+
+        for note in &self.notes {
+            diagnostic = diagnostic.with_note(note);
+        }
+
+        if let Some(help) = &self.help {
+            diagnostic = diagnostic.with_help(help);
+        }
+
+        if let Some(fix) = &self.fix {
+            let edits = fix.edits.iter().map(|(start, end, content)| {
+                if start == end {
+                    Edit::insert(*start, content)
+                } else {
+                    Edit::replace(ByteRange::new(*start, end - 1), content)
+                }
+            });
+            diagnostic = diagnostic.with_fix(Fix::new(edits, fix.applicability));
+        }
+
+        for child in &self.children {
+            diagnostic = diagnostic.with_child(child.to_diagnostic(source)?);
+        }
+
+        Some(diagnostic)
+    }
+}
+
+/// Remove the cache from the given `root` directory.
+pub(crate) fn clean(root: &Path) -> Result<bool> {
+    let path = root.join(CACHE_DIR);
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    std::fs::remove_dir_all(&path)
+        .with_context(|| format!("failed to remove `{}`", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics, Edit, Fix};
+    use bl_workspace::{settings::Settings, Member};
+
+    use super::{Cache, FileKey};
+
+    #[test]
+    fn round_trip_and_discard_corrupted() {
+        let root = std::env::temp_dir().join(format!("bracketlint-cache-{}", std::process::id()));
+        let settings = Settings::default();
+
+        let path = root.join("index.html");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(&path, "{% block a %}{% endblock %}").unwrap();
+
+        let member = Member::new(path, "{% block a %}{% endblock %}".to_string());
+        let key = FileKey::of(&member, &settings).unwrap();
+        let span = |start, end| Span::new(ByteRange::new(start, end), member.source_id);
+
+        let diagnostics: Diagnostics =
+            [Diagnostic::new(DiagnosticKind::Error, "message", span(9, 9))
+                .with_code("BL001")
+                .with_label(span(0, 12), "label")
+                .with_help("help")
+                .with_fix(Fix::safe_edits([
+                    Edit::insert(0, "x"),
+                    Edit::delete(ByteRange::new(3, 4)),
+                ]))]
+            .into_iter()
+            .collect();
+
+        let mut cache = Cache::open(&root, &settings);
+        assert!(cache.get(&member, &key).is_none());
+        cache.update(&member, key.clone(), &diagnostics);
+        cache.persist().unwrap();
+
+        let cache = Cache::open(&root, &settings);
+        assert_eq!(cache.get(&member, &key).unwrap().0, diagnostics.0);
+
+        // A corrupted cache is treated as being empty.
+        std::fs::write(&cache.path, "{ not json").unwrap();
+        assert!(Cache::open(&root, &settings).get(&member, &key).is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
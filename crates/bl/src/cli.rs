@@ -1,7 +1,10 @@
 //! Definitions of the command line interface for the `bl` binary.
 
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
 
+use anyhow::bail;
+use bl_lints::{registry::RuleSelector, settings::UnsafeFixes};
+use bl_utils::highlight::ColourChoice;
 use clap::{command, Parser};
 
 #[derive(Debug, Parser)]
@@ -16,13 +19,41 @@ use clap::{command, Parser};
 pub struct Cli {
     #[command(subcommand)]
     pub(crate) command: Command,
+
+    #[clap(flatten)]
+    pub(crate) global_options: GlobalOptions,
+}
+
+/// Options that are shared between all of the commands.
+#[derive(Debug, Clone, clap::Args)]
+pub struct GlobalOptions {
+    /// Control when coloured output is used, colours are disabled by default
+    /// if the `NO_COLOR` environment variable is set.
+    #[arg(long, global = true, value_enum, default_value_t = ColourChoice::Auto, value_name = "WHEN")]
+    pub color: ColourChoice,
+
+    /// Enable verbose logging.
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
 }
 
 #[derive(Debug, clap::Subcommand)]
 pub enum Command {
     /// The check command checks the given files or directories for linting
     /// errors.
-    Check(CheckCommand),
+    Check(Box<CheckCommand>),
+
+    /// Remove the cache of the current directory.
+    Clean,
+
+    /// Parse a template and print the resulting syntax tree, this is primarily
+    /// useful for debugging the parser.
+    #[command(alias = "dump-ast")]
+    Parse(ParseCommand),
+
+    /// Lex a template and print the resulting tokens, this is primarily useful
+    /// for debugging the lexer.
+    Tokens(TokensCommand),
 
     /// Command to print the version of the `bl` binary.
     Version,
@@ -30,8 +61,8 @@ pub enum Command {
 
 #[derive(Clone, Debug, clap::Parser)]
 pub struct CheckCommand {
-    /// List of files or directories to check.
-    #[clap(help = "List of files or directories to check [default: .]")]
+    /// List of files or directories to check, or `-` to read from stdin.
+    #[clap(help = "List of files or directories to check, or `-` to read from stdin [default: .]")]
     pub files: Vec<PathBuf>,
 
     /// Apply fixes to resolve lint violations.
@@ -42,31 +73,338 @@ pub struct CheckCommand {
     #[clap(long, overrides_with("fix"), hide = true)]
     no_fix: bool,
 
+    /// Include fixes that may not retain the original intent of the template,
+    /// such as those that could change the rendered output.
+    /// Use `--no-unsafe-fixes` to disable.
+    #[arg(long, overrides_with("no_unsafe_fixes"))]
+    unsafe_fixes: bool,
+
+    #[arg(long, overrides_with("unsafe_fixes"), hide = true)]
+    no_unsafe_fixes: bool,
+
+    /// Disable the cache, all files are re-checked and the cache isn't
+    /// updated.
+    #[arg(long)]
+    pub no_cache: bool,
+
     /// Enable preview mode; checks will include unstable rules and fixes.
     /// Use `--no-preview` to disable.
     #[arg(long, overrides_with("no_preview"))]
-    pub preview: bool,
+    preview: bool,
     #[clap(long, overrides_with("preview"), hide = true)]
     no_preview: bool,
 
+    /// Comma-separated list of rule selectors to enable, replacing those of the
+    /// configuration. A selector is a rule code or prefix (`BL0`), a rule
+    /// name, a category (`correctness`, `security`, `style`, `a11y`) or
+    /// `ALL`.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "RULE_SELECTOR",
+        help_heading = "Rule selection"
+    )]
+    pub select: Option<Vec<RuleSelector>>,
+
+    /// Comma-separated list of rule selectors to enable, in addition to those
+    /// that are already enabled.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "RULE_SELECTOR",
+        help_heading = "Rule selection"
+    )]
+    pub extend_select: Option<Vec<RuleSelector>>,
+
+    /// Comma-separated list of rule selectors to disable.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "RULE_SELECTOR",
+        help_heading = "Rule selection"
+    )]
+    pub ignore: Option<Vec<RuleSelector>>,
+
+    /// Comma-separated list of `pattern:selector` pairs, which disable the
+    /// rules for the files that match the pattern, replacing the
+    /// `per-file-ignores` of the configuration.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "PATTERN:RULE_SELECTOR",
+        help_heading = "Rule selection"
+    )]
+    pub per_file_ignores: Option<Vec<PatternSelectorPair>>,
+
     /// Respect file exclusions via `.gitignore` and other standard ignore
     /// files. Use `--no-respect-gitignore` to disable.
     #[arg(long, overrides_with("no_respect_gitignore"), help_heading = "File selection")]
-    pub respect_gitignore: bool,
+    respect_gitignore: bool,
 
     #[clap(long, overrides_with("respect_gitignore"), hide = true)]
     no_respect_gitignore: bool,
 
+    /// List of paths, used to omit files and/or directories from analysis,
+    /// replacing the default exclusions.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "FILE_PATTERN",
+        help_heading = "File selection"
+    )]
+    pub exclude: Option<Vec<String>>,
+
+    /// Like `--exclude`, but adds additional files and directories on top of
+    /// those already excluded.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "FILE_PATTERN",
+        help_heading = "File selection"
+    )]
+    pub extend_exclude: Option<Vec<String>>,
+
+    /// List of file patterns to include when linting, replacing the defaults.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "FILE_PATTERN",
+        help_heading = "File selection"
+    )]
+    pub include: Option<Vec<String>>,
+
+    /// Enforce exclusions, even for paths passed directly on the command line.
+    /// Use `--no-force-exclude` to disable.
+    #[arg(long, overrides_with("no_force_exclude"), help_heading = "File selection")]
+    force_exclude: bool,
+
+    #[clap(long, overrides_with("force_exclude"), hide = true)]
+    no_force_exclude: bool,
+
+    /// List the files that would be checked, along with any paths that were
+    /// excluded, and then exit.
+    #[arg(long, help_heading = "File selection")]
+    pub show_files: bool,
+
+    /// The path of the file that is read from stdin, which is used to find the
+    /// settings of the file and in the reported violations.
+    #[arg(long, value_name = "PATH", help_heading = "Miscellaneous")]
+    pub stdin_filename: Option<PathBuf>,
+
+    /// The configuration file to use, rather than discovering one from the
+    /// current directory.
+    #[arg(long, value_name = "PATH", conflicts_with = "isolated", help_heading = "Configuration")]
+    pub config: Option<PathBuf>,
+
+    /// Ignore all configuration files.
+    #[arg(long, help_heading = "Configuration")]
+    pub isolated: bool,
+
     /// Avoid writing any fixed files back; instead, output a diff for each
-    /// changed file to stdout, and exit 0 if there are no diffs.
-    /// Implies `--fix-only`.
+    /// changed file to stdout, and exit 1 if there are any diffs or 0
+    /// otherwise. Violations themselves aren't reported.
     #[arg(long, conflicts_with = "show_fixes")]
     pub diff: bool,
 
+    /// The format in which violations are printed.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Full)]
+    pub output_format: OutputFormat,
+
     /// Show an enumeration of all fixed lint violations.
     /// Use `--no-show-fixes` to disable.
     #[arg(long, overrides_with("no_show_fixes"))]
     show_fixes: bool,
     #[clap(long, overrides_with("show_fixes"), hide = true)]
     no_show_fixes: bool,
+
+    /// Show counts for every rule with at least one violation, instead of the
+    /// violations themselves.
+    #[arg(long, conflicts_with = "diff")]
+    pub statistics: bool,
+
+    /// Treat warnings as errors, so that any warning results in a non-zero
+    /// exit code.
+    #[arg(long, help_heading = "Exit status")]
+    pub warnings_as_errors: bool,
+
+    /// Exit with status code "0", even upon detecting lint violations.
+    #[arg(long, conflicts_with = "exit_non_zero_on_fix", help_heading = "Exit status")]
+    pub exit_zero: bool,
+
+    /// Exit with a non-zero status code if any files were modified via fix,
+    /// even if no lint violations remain.
+    #[arg(long, help_heading = "Exit status")]
+    pub exit_non_zero_on_fix: bool,
+
+    /// Add a suppression directive to every line with a violation, merging
+    /// the rules into any existing directive of the line.
+    #[arg(long, conflicts_with_all = ["fix", "diff", "statistics"])]
+    pub add_noqa: bool,
+
+    /// Only report violations that aren't recorded within the given baseline
+    /// file. Use `--update-baseline` to create it.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["diff", "add_noqa"])]
+    pub baseline: Option<PathBuf>,
+
+    /// Create or re-record the baseline from the current violations, and
+    /// report the violations that have been fixed since it was last recorded.
+    #[arg(long, requires = "baseline", conflicts_with = "fix")]
+    pub update_baseline: bool,
+}
+
+impl CheckCommand {
+    /// Whether `.gitignore` files should be respected, if set on the command
+    /// line.
+    pub fn respect_gitignore(&self) -> Option<bool> {
+        resolve_bool_arg(self.respect_gitignore, self.no_respect_gitignore)
+    }
+
+    /// Whether the fixes that were applied should be listed.
+    pub fn show_fixes(&self) -> bool {
+        resolve_bool_arg(self.show_fixes, self.no_show_fixes).unwrap_or(false)
+    }
+
+    /// Whether preview mode is enabled, if set on the command line.
+    pub fn preview(&self) -> Option<bool> {
+        resolve_bool_arg(self.preview, self.no_preview)
+    }
+
+    /// Whether unsafe fixes should be applied, if set on the command line.
+    pub fn unsafe_fixes(&self) -> Option<UnsafeFixes> {
+        resolve_bool_arg(self.unsafe_fixes, self.no_unsafe_fixes).map(UnsafeFixes::from)
+    }
+
+    /// Whether exclusions should be applied to paths that are passed directly,
+    /// if set on the command line.
+    pub fn force_exclude(&self) -> Option<bool> {
+        resolve_bool_arg(self.force_exclude, self.no_force_exclude)
+    }
+}
+
+/// A `pattern:selector` pair of `--per-file-ignores`.
+#[derive(Clone, Debug)]
+pub struct PatternSelectorPair {
+    /// The glob pattern of the files.
+    pub pattern: String,
+
+    /// The rules that are disabled for the files.
+    pub selector: RuleSelector,
+}
+
+impl FromStr for PatternSelectorPair {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // Split on the last colon, since the pattern itself may contain one.
+        let Some((pattern, selector)) = value.rsplit_once(':') else {
+            bail!("expected `pattern:selector`, found `{value}`");
+        };
+
+        Ok(Self { pattern: pattern.trim().to_string(), selector: selector.trim().parse()? })
+    }
+}
+
+/// Resolve a pair of `--flag` and `--no-flag` arguments, returning `None` if
+/// neither was given.
+fn resolve_bool_arg(yes: bool, no: bool) -> Option<bool> {
+    match (yes, no) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        (false, false) => None,
+        (true, true) => unreachable!("clap should make the flags mutually exclusive"),
+    }
+}
+
+/// The format in which the violations of the `check` command are printed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Each violation with a snippet of the source that it refers to.
+    #[default]
+    Full,
+
+    /// Each violation on a single line.
+    Concise,
+
+    /// Violations grouped by the file that they refer to.
+    Grouped,
+
+    /// A JSON array of all violations, see `bl_lints::message::json` for the
+    /// schema of each violation.
+    Json,
+
+    /// Each violation as a JSON object on its own line.
+    JsonLines,
+
+    /// A SARIF 2.1.0 log, which includes the metadata of every rule.
+    Sarif,
+
+    /// GitHub Actions workflow commands, which annotate the violations.
+    Github,
+
+    /// A GitLab Code Quality report.
+    Gitlab,
+
+    /// Azure Pipelines logging commands.
+    Azure,
+
+    /// A Checkstyle XML report.
+    Checkstyle,
+
+    /// A JUnit XML report.
+    Junit,
+}
+
+impl OutputFormat {
+    /// Check whether the format is meant to be read by people, rather than
+    /// by other tools. Only these formats can be followed by summaries such
+    /// as the table of fixes.
+    pub fn is_human_readable(self) -> bool {
+        matches!(self, OutputFormat::Full | OutputFormat::Concise | OutputFormat::Grouped)
+    }
+
+    /// Check whether the format is machine-readable JSON.
+    pub fn is_json(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json
+                | OutputFormat::JsonLines
+                | OutputFormat::Sarif
+                | OutputFormat::Gitlab
+        )
+    }
+}
+
+#[derive(Clone, Debug, clap::Parser)]
+pub struct ParseCommand {
+    /// The file to parse, reads from stdin if omitted or `-`.
+    pub file: Option<PathBuf>,
+
+    /// The format to print the syntax tree in.
+    #[arg(long, value_enum, default_value_t = TreeFormat::Tree)]
+    pub format: TreeFormat,
+}
+
+/// The format in which a syntax tree is printed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TreeFormat {
+    /// An indented tree, with the byte range of each node.
+    #[default]
+    Tree,
+
+    /// The full syntax tree as JSON, each node is written as an object with
+    /// `kind` and `span` entries followed by the fields of the node.
+    Json,
+
+    /// The tree as an S-expression.
+    Sexp,
+}
+
+#[derive(Clone, Debug, clap::Parser)]
+pub struct TokensCommand {
+    /// The file to lex, reads from stdin if omitted or `-`.
+    pub file: Option<PathBuf>,
+
+    /// Include trivia tokens (i.e. whitespace within tags) in the output.
+    #[arg(long)]
+    pub trivia: bool,
 }
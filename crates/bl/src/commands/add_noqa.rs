@@ -0,0 +1,232 @@
+//! Implementation of `check --add-noqa`, which adds a suppression directive
+//! for every violation that is currently reported, i.e. when enabling a new
+//! rule on an existing project.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::Result;
+use bl_diagnostics::{Diagnostics, Edit, Fix};
+use bl_lints::{
+    fix::apply_fix,
+    suppression::{DirectiveKind, Suppressions, PREFIX},
+};
+use bl_parse::{
+    dialect::Dialect,
+    lexer::{Delimiters, Lexer},
+    token::TokenKind,
+};
+use bl_workspace::{
+    resolver::{find_files_in_paths, Discovery, FileSearch},
+    settings::{LinterSettings, Settings},
+    Member,
+};
+use rayon::prelude::*;
+
+use super::check::lint_member;
+
+/// The code of the rule that reports unused suppressions, its violations are
+/// resolved by removing directives rather than adding them.
+const UNUSED_SUPPRESSION: &str = "BL003";
+
+/// Add a suppression directive for each of the violations within the given
+/// files, returning the number of directives that were added or extended.
+pub fn add_noqa(files: &[PathBuf], settings: &Settings, discovery: &Discovery) -> Result<usize> {
+    let FileSearch { files, resolver, .. } = find_files_in_paths(files, settings, discovery)?;
+
+    let mut paths = vec![];
+    for file in files {
+        match file {
+            Ok(file) => paths.push(file.into_path()),
+            Err(error) => log::error!("{error}"),
+        }
+    }
+    paths.sort_unstable();
+
+    let counts: Vec<_> = paths
+        .into_par_iter()
+        .map(|path| {
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    log::error!("failed to read `{}`: {error}", path.display());
+                    return 0;
+                }
+            };
+
+            let settings = &resolver.resolve(&path).linter_settings;
+            let mut member = Member::new(path, contents);
+            let diagnostics = lint_member(&mut member, settings);
+
+            let Some((fixed, count)) = add_directives(&member, &diagnostics, settings) else {
+                return 0;
+            };
+
+            // Ensure that the directives didn't break the template.
+            let syntax_errors = |diagnostics: &Diagnostics| {
+                diagnostics.iter().filter(|diagnostic| diagnostic.code.is_none()).count()
+            };
+            let before = syntax_errors(&diagnostics);
+            member.set_contents(fixed);
+            if syntax_errors(&lint_member(&mut member, settings)) > before {
+                log::warn!(
+                    "adding suppressions introduced a syntax error in `{}`, skipping",
+                    member.path.display()
+                );
+                return 0;
+            }
+
+            match std::fs::write(&member.path, &member.contents) {
+                Ok(()) => count,
+                Err(error) => {
+                    log::error!("failed to write `{}`: {error}", member.path.display());
+                    0
+                }
+            }
+        })
+        .collect();
+
+    Ok(counts.into_iter().sum())
+}
+
+/// Compute the contents of the [Member] with a directive for each line that
+/// has a violation. Codes are added to any existing `ignore` directive of the
+/// line, otherwise a new directive is added at the end of the line. Returns
+/// the new contents and the number of directives that were added or extended.
+fn add_directives(
+    member: &Member,
+    diagnostics: &Diagnostics,
+    settings: &LinterSettings,
+) -> Option<(String, usize)> {
+    let delimiters = &settings.delimiters;
+    let source = member.contents.as_str();
+    let suppressions = Suppressions::parse(member.document.as_ref()?.ast_ref(), source);
+
+    // The codes of the violations on each line.
+    let mut lines: BTreeMap<usize, Vec<&'static str>> = BTreeMap::new();
+    for diagnostic in diagnostics {
+        let Some(code) = diagnostic.code.filter(|code| *code != UNUSED_SUPPRESSION) else {
+            continue;
+        };
+
+        let codes = lines.entry(suppressions.line_of(diagnostic.span.range.start())).or_default();
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+
+    let tags = tag_ranges(source, delimiters, settings.dialect_for(&member.path));
+    let inside_tag =
+        |offset: usize| tags.iter().any(|(start, end)| *start < offset && offset <= *end);
+
+    let mut edits = vec![];
+    for (line, mut codes) in lines {
+        codes.sort_unstable();
+
+        let existing = suppressions
+            .directives
+            .iter()
+            .find(|directive| directive.kind == DirectiveKind::Ignore && directive.line == line);
+
+        if let Some(directive) = existing {
+            let missing: Vec<_> =
+                codes.into_iter().filter(|code| !directive.applies_to(code)).collect();
+            if let (false, Some((_, end))) = (missing.is_empty(), directive.list) {
+                edits.push(Edit::insert(end, format!(", {}", missing.join(", "))));
+            }
+            continue;
+        }
+
+        let comment = format!(
+            "{} {PREFIX} ignore[{}] {}",
+            delimiters.comment_start,
+            codes.join(", "),
+            delimiters.comment_end
+        );
+
+        // Prefer a trailing directive, since it doesn't change the rendered
+        // output, otherwise add the directive on the line before.
+        let start = source.split_inclusive('\n').take(line).map(str::len).sum::<usize>();
+        let text = source[start..].split('\n').next().unwrap_or("");
+        let end = start + text.trim_end_matches('\r').len();
+
+        if !inside_tag(end) {
+            edits.push(Edit::insert(end, format!(" {comment}")));
+        } else if !inside_tag(start) {
+            let indent = &text[..text.len() - text.trim_start().len()];
+            edits.push(Edit::insert(start, format!("{indent}{comment}\n")));
+        } else {
+            log::warn!(
+                "unable to add a suppression to line {} of `{}`",
+                line + 1,
+                member.path.display()
+            );
+        }
+    }
+
+    if edits.is_empty() {
+        return None;
+    }
+
+    let count = edits.len();
+    Some((apply_fix(&Fix::safe_edits(edits), source)?, count))
+}
+
+/// Find the ranges of all of the variables, tags and comments within the
+/// source, the ends of the ranges are inclusive.
+fn tag_ranges(source: &str, delimiters: &Delimiters, dialect: Dialect) -> Vec<(usize, usize)> {
+    let tokens =
+        Lexer::new(source).with_delimiters(delimiters.clone()).with_dialect(dialect).tokenise();
+
+    let mut ranges = vec![];
+    let mut open = None;
+    for token in tokens.tokens {
+        match token.kind {
+            TokenKind::VariableStart | TokenKind::BlockStart => open = Some(token.range.start()),
+            TokenKind::VariableEnd | TokenKind::BlockEnd => {
+                if let Some(start) = open.take() {
+                    ranges.push((start, token.range.end()));
+                }
+            }
+            TokenKind::Comment => ranges.push((token.range.start(), token.range.end())),
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use bl_workspace::{settings::Settings, Member};
+
+    use super::add_directives;
+    use crate::commands::check::lint_member;
+
+    #[test]
+    fn add_and_merge_directives() {
+        let source = "\
+{% break %}
+{% break %}{% block a %}{% endblock %}{% block a %}{% endblock %}
+{% break %} {# bl: ignore[BL001] #}
+{% break %}{% if x
+   %}{% endif %}
+";
+        let settings = Settings::default().linter_settings;
+        let mut member = Member::new(PathBuf::from("index.html"), source.to_string());
+        let diagnostics = lint_member(&mut member, &settings);
+
+        let (fixed, count) = add_directives(&member, &diagnostics, &settings).unwrap();
+        let expected = "\
+{% break %} {# bl: ignore[BL002] #}
+{% break %}{% block a %}{% endblock %}{% block a %}{% endblock %} {# bl: ignore[BL001, BL002] #}
+{% break %} {# bl: ignore[BL001, BL002] #}
+{# bl: ignore[BL002] #}
+{% break %}{% if x
+   %}{% endif %}
+";
+        assert_eq!(fixed, expected);
+        assert_eq!(count, 4);
+    }
+}
@@ -1,31 +1,776 @@
 //! Implementation of the `check` command.
 
-use std::path::PathBuf;
-
-use anyhow::Result;
-use bl_diagnostics::Diagnostics;
-use bl_utils::timed;
-use bl_workspace::{resolver::find_files_in_paths, settings::Settings};
-
-pub fn check(files: &[PathBuf], settings: Settings) -> Result<Diagnostics> {
-    // Firstly, we need to discover all of the files in the provided paths.
-    let files = timed(
-        || find_files_in_paths(files, &settings),
-        log::Level::Info,
-        |duration| println!("Resolved files in {:?}", duration),
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use bl_ast::{ByteRange, SourceMap, Span};
+use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics};
+use bl_lints::{
+    fix::{apply_fixes, FixTable},
+    rule::Rule,
+    settings::FixMode,
+};
+use bl_utils::{fs, stream::CompilerOutputStream, stream_write, timed};
+use bl_workspace::{
+    resolver::{find_files_in_paths, is_file_excluded, Discovery, FileSearch, Resolver},
+    settings::{LinterSettings, Settings},
+    Member, WorkspaceBuilder,
+};
+use rayon::prelude::*;
+
+use crate::cache::{Cache, FileKey};
+
+/// The maximum number of times that fixes are applied to a single file, this
+/// guards against fixes that never converge (i.e. two fixes that undo one
+/// another).
+const MAX_FIX_ITERATIONS: usize = 100;
+
+/// The result of checking a set of files.
+#[derive(Default)]
+pub struct CheckResult {
+    /// All of the diagnostics that remain after any fixes were applied.
+    pub diagnostics: Diagnostics,
+
+    /// The number of fixes that were applied to each file, for each rule.
+    pub fixed: BTreeMap<PathBuf, FixTable>,
+
+    /// The files that fixes would change, these are only collected in
+    /// [FixMode::Diff].
+    pub changes: Vec<SourceChange>,
+}
+
+/// The contents of a file before and after its fixes were applied.
+pub struct SourceChange {
+    /// The path of the file.
+    pub path: PathBuf,
+
+    /// The original contents of the file.
+    pub original: String,
+
+    /// The contents of the file after the fixes were applied.
+    pub fixed: String,
+}
+
+impl CheckResult {
+    /// Get the total number of fixes that were applied.
+    pub fn fixed_count(&self) -> usize {
+        self.fixed.values().flat_map(|fixes| fixes.values()).sum()
+    }
+}
+
+pub fn check(
+    files: &[PathBuf],
+    settings: Settings,
+    discovery: Discovery,
+    cache: bool,
+) -> Result<CheckResult> {
+    let mut workspace =
+        WorkspaceBuilder::new().with_settings(settings).with_discovery(discovery).build();
+
+    // Firstly, we need to discover all of the files in the provided paths,
+    // along with the settings of each of them.
+    let FileSearch { files, resolver, .. } = timed(
+        || find_files_in_paths(files, &workspace.settings, &workspace.discovery),
+        log::Level::Debug,
+        |duration| log::debug!("resolved files in {:?}", duration),
     )?;
 
     if files.is_empty() {
-        // @@Todo: warn the user that there were no files to check.
-        return Ok(Diagnostics::default());
+        log::warn!("no template files found under the given paths");
+        return Ok(CheckResult::default());
+    }
+
+    let mut result = CheckResult::default();
+
+    // Order the files by their path, so that members are always created in the
+    // same order regardless of how the file walker was scheduled. A file is
+    // found more than once when it is also within another of the given paths.
+    let mut paths = vec![];
+    for file in files {
+        match file {
+            Ok(file) => paths.push(file.into_path()),
+            Err(error) => result.diagnostics.extend(io_error(error)),
+        }
+    }
+    paths.sort_unstable();
+    paths.dedup();
+
+    // Read all of the files, and then create members for them.
+    let contents: Vec<_> =
+        paths.into_par_iter().map(|path| (std::fs::read_to_string(&path), path)).collect();
+
+    for (contents, path) in contents {
+        match contents {
+            Ok(contents) => {
+                workspace.members.add_member(path.clone(), Member::new(path, contents));
+            }
+            Err(error) => result.diagnostics.push(read_error(&path, &error.to_string())),
+        }
+    }
+
+    log::debug!("checking {} files", workspace.members.len());
+
+    // Diffs are computed from the fixes of each file, so the cache can't be
+    // used since it only holds the remaining diagnostics.
+    let fix_mode = workspace.settings.linter_settings.fix_mode;
+    let mut cache = match cache && fix_mode != FixMode::Diff {
+        true => Some(Cache::open(&std::env::current_dir()?, &workspace.settings)),
+        false => None,
+    };
+
+    // Now parse and lint all of the members in parallel, members which have
+    // diagnostics in the cache are skipped.
+    let results: Vec<_> = timed(
+        || {
+            workspace
+                .members
+                .as_mut_slice()
+                .par_iter_mut()
+                .map(|member| {
+                    let settings = resolver.resolve(&member.path);
+                    let key = cache.as_ref().and_then(|_| FileKey::of(member, settings));
+                    let required = settings.linter_settings.unsafe_fixes.required_applicability();
+                    let cached = cache
+                        .as_ref()
+                        .zip(key.as_ref())
+                        .and_then(|(cache, key)| cache.get(member, key))
+                        // Fixable diagnostics still need to be fixed.
+                        .filter(|diagnostics| {
+                            fix_mode == FixMode::Generate
+                                || diagnostics.fixable_count_with(required) == 0
+                        });
+
+                    if let Some(diagnostics) = cached {
+                        return (diagnostics, FixTable::new(), None, None);
+                    }
+
+                    let settings = &settings.linter_settings;
+                    if settings.fix_mode == FixMode::Generate {
+                        return (lint_member(member, settings), FixTable::new(), key, None);
+                    }
+
+                    let original = member.contents.clone();
+                    let (mut diagnostics, fixes) = lint_fix(member, settings);
+                    if fixes.is_empty() {
+                        return (diagnostics, fixes, key, None);
+                    }
+
+                    if settings.fix_mode == FixMode::Diff {
+                        return (diagnostics, fixes, None, Some(original));
+                    }
+
+                    if let Err(error) = std::fs::write(&member.path, &member.contents) {
+                        diagnostics.push(write_error(member, &error.to_string()));
+                        return (diagnostics, fixes, None, None);
+                    }
+
+                    // The file has been written, so the key needs to be
+                    // computed from the fixed contents.
+                    let key = key.and_then(|_| FileKey::of(member, resolver.resolve(&member.path)));
+                    (diagnostics, fixes, key, None)
+                })
+                .collect()
+        },
+        log::Level::Debug,
+        |duration| log::debug!("checked files in {:?}", duration),
+    );
+
+    for (member, (diagnostics, fixes, key, original)) in workspace.members.iter().zip(results) {
+        if let (Some(cache), Some(key)) = (&mut cache, key) {
+            cache.update(member, key, &diagnostics);
+        }
+
+        if let Some(original) = original {
+            let fixed = member.contents.clone();
+            result.changes.push(SourceChange { path: member.path.clone(), original, fixed });
+        }
+
+        if !fixes.is_empty() {
+            result.fixed.insert(member.path.clone(), fixes);
+        }
+
+        result.diagnostics.extend(diagnostics);
+    }
+
+    if let Some(cache) = cache {
+        if let Err(error) = cache.persist() {
+            log::warn!("failed to write the cache: {error}");
+        }
+    }
+
+    result.diagnostics.dedup();
+    Ok(result)
+}
+
+/// Check a template that is read from stdin. The settings of the template are
+/// resolved from the given `filename`, which is also the path that is used in
+/// any diagnostics. When fixes are applied, the fixed template is written to
+/// stdout.
+pub fn check_stdin(
+    filename: Option<&Path>,
+    settings: Settings,
+    discovery: Discovery,
+) -> Result<CheckResult> {
+    let mut stream = CompilerOutputStream::stdout();
+    check_source(filename, io::stdin(), &mut stream, settings, discovery)
+}
+
+/// Check a template that is read from the given `input` like [check_stdin],
+/// writing the fixed template to the given `output` when fixes are applied.
+fn check_source(
+    filename: Option<&Path>,
+    mut input: impl Read,
+    output: &mut dyn Write,
+    settings: Settings,
+    discovery: Discovery,
+) -> Result<CheckResult> {
+    let workspace =
+        WorkspaceBuilder::new().with_settings(settings).with_discovery(discovery).build();
+
+    let path = match filename {
+        Some(filename) => fs::normalize_path(filename),
+        None => PathBuf::from("-"),
+    };
+
+    let mut resolver = Resolver::new(&workspace.settings, &workspace.discovery);
+    if filename.is_some() {
+        resolver.discover_ancestors(&path)?;
+    }
+
+    let mut contents = String::new();
+    input.read_to_string(&mut contents).context("failed to read from stdin")?;
+
+    // Excluded templates are still written back unchanged when fixing, since
+    // the output replaces the template.
+    if resolver.force_exclude() && is_file_excluded(&path, &resolver) {
+        log::debug!("ignoring excluded stdin file `{}`", path.display());
+        if resolver.resolve(&path).linter_settings.fix_mode == FixMode::Apply {
+            stream_write!(output, "{}", contents);
+        }
+        return Ok(CheckResult::default());
+    }
+
+    let mut member = Member::new(path, contents);
+    let settings = &resolver.resolve(&member.path).linter_settings;
+
+    let mut result = CheckResult::default();
+    if settings.fix_mode == FixMode::Generate {
+        result.diagnostics = lint_member(&mut member, settings);
+    } else {
+        let original = member.contents.clone();
+        let (diagnostics, fixes) = lint_fix(&mut member, settings);
+        result.diagnostics = diagnostics;
+
+        if !fixes.is_empty() {
+            result.fixed.insert(member.path.clone(), fixes);
+
+            if settings.fix_mode == FixMode::Diff {
+                let (path, fixed) = (member.path.clone(), member.contents.clone());
+                result.changes.push(SourceChange { path, original, fixed });
+            }
+        }
+    }
+
+    if settings.fix_mode == FixMode::Apply {
+        stream_write!(output, "{}", member.contents);
+    }
+
+    result.diagnostics.dedup();
+    Ok(result)
+}
+
+/// Parse and lint the given [Member], any parse errors are reported as error
+/// diagnostics alongside those of the lints.
+pub(crate) fn lint_member(member: &mut Member, settings: &LinterSettings) -> Diagnostics {
+    lint_member_with_rules(member, settings, &settings.rules_for(&member.path))
+}
+
+/// Parse and lint the given [Member] like [lint_member], with the given rules
+/// rather than the rules that are selected for the [Member].
+fn lint_member_with_rules(
+    member: &mut Member,
+    settings: &LinterSettings,
+    rules: &[&'static dyn Rule],
+) -> Diagnostics {
+    let id = member.source_id;
+    let dialect = settings.dialect_for(&member.path);
+    let result =
+        bl_parse::parse_with_delimiters(&member.contents, id, &settings.delimiters, dialect);
+
+    let mut diagnostics: Diagnostics = result
+        .errors
+        .into_iter()
+        .map(|error| {
+            Diagnostic::new(DiagnosticKind::Error, error.message, Span::new(error.range, id))
+        })
+        .collect();
+
+    let lints = bl_lints::lint(result.document.ast_ref(), &member.contents, rules, dialect);
+
+    // Apply any overrides of the severity of the lints, and promote or demote
+    // their fixes according to the settings.
+    diagnostics.extend(lints.into_iter().map(|mut diagnostic| {
+        if let Some(code) = diagnostic.code {
+            diagnostic.kind = settings.diagnostic_kind(code, diagnostic.kind);
+        }
+        if let (Some(code), Some(fix)) = (diagnostic.code, diagnostic.fix.take()) {
+            let applicability = settings.fix_applicability(code, fix.applicability());
+            diagnostic.fix = Some(fix.with_applicability(applicability));
+        }
+        diagnostic
+    }));
+    member.document = Some(result.document);
+    diagnostics
+}
+
+/// Lint the given [Member], and then repeatedly apply the fixes of the
+/// reported diagnostics and re-lint the fixed contents until there are no
+/// more fixes to apply. Fixes that would introduce a syntax error are
+/// reverted.
+///
+/// Returns the diagnostics of the fixed contents, and the number of fixes that
+/// were applied for each rule.
+fn lint_fix(member: &mut Member, settings: &LinterSettings) -> (Diagnostics, FixTable) {
+    lint_fix_with_rules(member, settings, &settings.rules_for(&member.path))
+}
+
+/// Lint and fix the given [Member] like [lint_fix], with the given rules
+/// rather than the rules that are selected for the [Member].
+fn lint_fix_with_rules(
+    member: &mut Member,
+    settings: &LinterSettings,
+    rules: &[&'static dyn Rule],
+) -> (Diagnostics, FixTable) {
+    /// Count the syntax errors within the given diagnostics.
+    fn syntax_errors(diagnostics: &Diagnostics) -> usize {
+        diagnostics.iter().filter(|diagnostic| diagnostic.code.is_none()).count()
+    }
+
+    let mut diagnostics = lint_member_with_rules(member, settings, rules);
+    let mut fixes = FixTable::new();
+    let mut iterations = 0;
+
+    let required = settings.unsafe_fixes.required_applicability();
+    while let Some(result) = apply_fixes(&diagnostics, &member.contents, required) {
+        if result.source == member.contents {
+            break;
+        }
+
+        if iterations == MAX_FIX_ITERATIONS {
+            log::warn!(
+                "fixes failed to converge after {MAX_FIX_ITERATIONS} iterations in `{}`",
+                member.path.display()
+            );
+            break;
+        }
+        iterations += 1;
+
+        let previous = member.clone();
+        member.set_contents(result.source);
+        let fixed = lint_member_with_rules(member, settings, rules);
+
+        if syntax_errors(&fixed) > syntax_errors(&diagnostics) {
+            log::warn!("fixes introduced a syntax error in `{}`, reverting", member.path.display());
+            *member = previous;
+            break;
+        }
+
+        for (code, count) in result.fixes {
+            *fixes.entry(code).or_default() += count;
+        }
+        diagnostics = fixed;
     }
 
-    // @@Todo: integrate a cache system here, we should be able to avoid re-linting
-    // already existent files and just skip them.
+    (diagnostics, fixes)
+}
+
+/// Create a [Diagnostic] for a file that couldn't be read.
+fn read_error(path: &Path, message: &str) -> Diagnostic {
+    let id = SourceMap::add(path, "");
+    let span = Span::new(ByteRange::default(), id);
+    Diagnostic::new(DiagnosticKind::Error, format!("failed to read file: {message}"), span)
+}
+
+/// Create a [Diagnostic] for a [Member] whose fixed contents couldn't be
+/// written.
+fn write_error(member: &Member, message: &str) -> Diagnostic {
+    let span = Span::new(ByteRange::default(), member.source_id);
+    Diagnostic::new(DiagnosticKind::Error, format!("failed to write fixes: {message}"), span)
+}
+
+/// Convert an error that occurred whilst walking the given paths into a
+/// [Diagnostic]. Errors which don't refer to a particular file are logged
+/// instead.
+fn io_error(error: ignore::Error) -> Option<Diagnostic> {
+    /// Find the path that the error refers to, along with the underlying error.
+    fn with_path(error: &ignore::Error) -> Option<(&Path, &ignore::Error)> {
+        match error {
+            ignore::Error::WithPath { path, err } => Some((path, err)),
+            ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+                with_path(err)
+            }
+            _ => None,
+        }
+    }
+
+    match with_path(&error) {
+        Some((path, err)) => Some(read_error(path, &err.to_string())),
+        None => {
+            log::error!("{error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use bl_ast::{AstNodeRef, ByteRange, Document, Span};
+    use bl_diagnostics::{Applicability, DiagnosticKind, Edit, Fix};
+    use bl_lints::{
+        context::LintContext,
+        message::{Emitter, TextEmitter},
+        registry::RuleSelector,
+        rule::{FixAvailability, Rule, RuleCategory},
+        settings::{FixMode, UnsafeFixes},
+    };
+    use bl_utils::stream::CompilerOutputStream;
+    use bl_workspace::{
+        configuration::Configuration,
+        resolver::Discovery,
+        settings::{FilePattern, LinterSettings, Settings},
+        Member,
+    };
+    use clap::Parser;
+
+    use super::{check, check_source, lint_fix_with_rules, CheckResult};
+    use crate::cli::CheckCommand;
+
+    /// A rule that reports each `TODO` within a template, with a fix of the
+    /// given applicability that removes it.
+    struct Todo {
+        code: &'static str,
+        applicability: Applicability,
+    }
+
+    static UNSAFE_TODO: Todo = Todo { code: "BL901", applicability: Applicability::Unsafe };
+    static SAFE_TODO: Todo = Todo { code: "BL902", applicability: Applicability::Safe };
+
+    impl Rule for Todo {
+        fn code(&self) -> &'static str {
+            self.code
+        }
+
+        fn name(&self) -> &'static str {
+            "todo"
+        }
+
+        fn description(&self) -> &'static str {
+            "Checks for `TODO`s."
+        }
+
+        fn category(&self) -> RuleCategory {
+            RuleCategory::Style
+        }
+
+        fn default_severity(&self) -> DiagnosticKind {
+            DiagnosticKind::Warning
+        }
+
+        fn fix_availability(&self) -> FixAvailability {
+            FixAvailability::Always
+        }
+
+        fn check(&self, document: AstNodeRef<Document>, context: &mut LintContext) {
+            for (offset, _) in context.source().match_indices("TODO") {
+                let range = ByteRange::new(offset, offset + 3);
+                let fix = Fix::new([Edit::delete(range)], self.applicability);
+                let span = Span::new(range, document.span().id);
+                let diagnostic = context.diagnostic("`TODO` in template", span).with_fix(fix);
+                context.push(diagnostic);
+            }
+        }
+    }
+
+    /// Fix a template with the given [Todo] rule, returning the fixed source
+    /// and the rendered report.
+    fn fix_todos(rule: &'static Todo, settings: &LinterSettings) -> (String, String) {
+        let mut member = Member::new(PathBuf::from("todo.html"), "a TODO b TODO".to_string());
+        let (diagnostics, fixes) = lint_fix_with_rules(&mut member, settings, &[rule]);
+
+        let mut stream = CompilerOutputStream::owned();
+        let mut emitter = TextEmitter::new()
+            .with_colours(false)
+            .with_fixed(fixes.values().sum())
+            .with_unsafe_fixes(settings.unsafe_fixes);
+        emitter.emit(&mut stream, &diagnostics).unwrap();
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        (member.contents, output)
+    }
+
+    /// Check the given `source` as if it was read from stdin with the given
+    /// `filename`, returning the result and anything written to stdout.
+    fn check_stdin(
+        filename: Option<&str>,
+        source: &str,
+        configuration: Configuration,
+    ) -> (CheckResult, String) {
+        let settings = configuration.into_settings().unwrap();
+        let mut stream = CompilerOutputStream::owned();
+        let filename = filename.map(Path::new);
+        let result =
+            check_source(filename, source.as_bytes(), &mut stream, settings, Discovery::Fixed)
+                .unwrap();
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        (result, output)
+    }
+
+    /// The codes and paths of the diagnostics within the [CheckResult].
+    fn codes(result: &CheckResult) -> Vec<(Option<&'static str>, String)> {
+        result
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let path = diagnostic.path().unwrap_or_default().display().to_string();
+                (diagnostic.code, path)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stdin_without_filename() {
+        let (result, output) = check_stdin(None, "{% break %}", Configuration::default());
+        assert_eq!(codes(&result), [(Some("BL002"), "-".to_string())]);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn stdin_filename_selects_settings() {
+        let cwd = std::env::current_dir().unwrap();
+        let configuration = || Configuration {
+            per_file_ignores: Some(vec![(
+                FilePattern::user("legacy/*", &cwd),
+                vec!["BL002".parse::<RuleSelector>().unwrap()],
+            )]),
+            ..Configuration::default()
+        };
+
+        let source = "{% for x in xs %}{% break %}{% endfor %}{% break %}";
+        let (result, _) = check_stdin(Some("legacy/index.html"), source, configuration());
+        assert!(result.diagnostics.is_empty());
+
+        let (result, _) = check_stdin(Some("app/index.html"), source, configuration());
+        assert_eq!(codes(&result).len(), 1);
 
-    // Now iterate the files in parallel, parse them and lint them.
+        // The dialect is determined by the extension of the filename.
+        let (result, _) = check_stdin(Some("app/index.twig"), source, configuration());
+        let messages: Vec<_> =
+            result.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+        assert_eq!(messages, ["`break` is not supported by the twig dialect"; 2]);
+    }
+
+    #[test]
+    fn stdin_fix_writes_fixed_source() {
+        let configuration = Configuration { fix_mode: Some(FixMode::Apply), ..Default::default() };
+        let source = "{% break %}{# bl: ignore[BL001] #}\n";
+        let (result, output) = check_stdin(Some("index.html"), source, configuration);
+
+        // The fixed template is the only output, the remaining violations are
+        // reported separately.
+        assert_eq!(output, "{% break %}\n");
+        assert_eq!(result.fixed_count(), 1);
+        assert_eq!(codes(&result).len(), 1);
+        assert_eq!(result.diagnostics.iter().next().unwrap().code, Some("BL002"));
+    }
+
+    #[test]
+    fn stdin_fix_echoes_excluded_source() {
+        let cwd = std::env::current_dir().unwrap();
+        let configuration = || Configuration {
+            extend_exclude: vec![FilePattern::user("vendor", &cwd)],
+            fix_mode: Some(FixMode::Apply),
+            force_exclude: Some(true),
+            ..Default::default()
+        };
+
+        let source = "{% break %}{# bl: ignore[BL001] #}\n";
+        let (result, output) = check_stdin(Some("vendor/index.html"), source, configuration());
+        assert_eq!(output, source);
+        assert!(result.diagnostics.is_empty());
+        assert_eq!(result.fixed_count(), 0);
+
+        let (_, output) = check_stdin(Some("index.html"), source, configuration());
+        assert_eq!(output, "{% break %}\n");
+    }
+
+    /// Create a project with the given files, returning its root.
+    fn project(name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bracketlint-{name}-{}", std::process::id()));
+        for (path, contents) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn check_reports_each_violation_once() {
+        let root = project("check-once", &[("index.html", b"{% break %}")]);
+
+        // The file is found within the directory, as well as being given.
+        let paths = [root.clone(), root.join("index.html")];
+        let settings = Configuration::default().into_settings().unwrap();
+        let result = check(&paths, settings, Discovery::Fixed, false).unwrap();
+        assert_eq!(
+            codes(&result),
+            [(Some("BL002"), root.join("index.html").display().to_string())]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn check_reports_read_errors() {
+        let root = project("check-read", &[("binary.html", b"\xff\xfe{% break %}")]);
+
+        let settings = Configuration::default().into_settings().unwrap();
+        let result = check(&[root.clone()], settings, Discovery::Fixed, false).unwrap();
+        assert_eq!(codes(&result), [(None, root.join("binary.html").display().to_string())]);
+
+        let diagnostic = result.diagnostics.iter().next().unwrap();
+        assert_eq!(diagnostic.kind, DiagnosticKind::Error);
+        assert!(diagnostic.message.starts_with("failed to read file: "), "{}", diagnostic.message);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 
-    println!("found {} files", files.len());
+    #[test]
+    fn check_orders_diagnostics_by_path_and_offset() {
+        let source: &[u8] = b"{% break %}{% continue %}";
+        let root = project(
+            "check-order",
+            &[("b.html", source), ("a/z.html", source), ("a.html", source), ("c.html", b"")],
+        );
 
-    Ok(Diagnostics::default())
+        let check = || {
+            let settings = Configuration::default().into_settings().unwrap();
+            let result = check(&[root.clone()], settings, Discovery::Fixed, false).unwrap();
+            result
+                .diagnostics
+                .iter()
+                .map(|diagnostic| {
+                    let path = diagnostic.path().unwrap();
+                    let path = path.strip_prefix(&root).unwrap().display().to_string();
+                    (path, diagnostic.span.range.start())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Paths are ordered by their components, so `a/z.html` is before
+        // `a.html`.
+        let expected = ["a/z.html", "a/z.html", "a.html", "a.html", "b.html", "b.html"]
+            .into_iter()
+            .zip([0, 11, 0, 11, 0, 11])
+            .map(|(path, offset)| (path.to_string(), offset))
+            .collect::<Vec<_>>();
+        assert_eq!(check(), expected);
+        assert_eq!(check(), expected);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn check_selects_rules_per_file() {
+        let source: &[u8] = b"{% break %}";
+        let root = project(
+            "check-rules",
+            &[
+                ("app/index.html", source),
+                ("legacy/index.html", source),
+                ("nested/index.html", source),
+                ("nested/bracketlint.toml", b"ignore = [\"BL002\"]\n"),
+            ],
+        );
+
+        let configuration = Configuration {
+            per_file_ignores: Some(vec![(
+                FilePattern::user("legacy/*", &root),
+                vec!["BL002".parse::<RuleSelector>().unwrap()],
+            )]),
+            ..Configuration::default()
+        };
+        let settings = configuration.clone().into_settings().unwrap();
+        let discovery = Discovery::Hierarchical(configuration);
+
+        // Only the file that isn't ignored by `per-file-ignores`, or by its own
+        // configuration file, is reported.
+        let result = check(&[root.clone()], settings, discovery, false).unwrap();
+        assert_eq!(
+            codes(&result),
+            [(Some("BL002"), root.join("app/index.html").display().to_string())]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn unsafe_fixes_are_only_applied_when_enabled() {
+        let settings =
+            |unsafe_fixes| LinterSettings { unsafe_fixes, ..Settings::default().linter_settings };
+
+        // By default, a hint is shown for the fixes that weren't applied.
+        let (fixed, output) = fix_todos(&UNSAFE_TODO, &settings(UnsafeFixes::Hint));
+        assert_eq!(fixed, "a TODO b TODO");
+        assert!(output.ends_with(
+            "Found 2 warnings.\n2 hidden fixes can be enabled with the `--unsafe-fixes` option.\n"
+        ));
+
+        let (fixed, output) = fix_todos(&UNSAFE_TODO, &settings(UnsafeFixes::Disabled));
+        assert_eq!(fixed, "a TODO b TODO");
+        assert!(output.ends_with("Found 2 warnings.\n"));
+
+        let (fixed, output) = fix_todos(&UNSAFE_TODO, &settings(UnsafeFixes::Enabled));
+        assert_eq!(fixed, "a  b ");
+        assert_eq!(output, "Found 2 violations (2 fixed, 0 remaining).\n");
+
+        let unsafe_fixes =
+            |args: &[&str]| CheckCommand::parse_from(["check"].iter().chain(args)).unsafe_fixes();
+        assert_eq!(unsafe_fixes(&[]), None);
+        assert_eq!(unsafe_fixes(&["--unsafe-fixes"]), Some(UnsafeFixes::Enabled));
+        assert_eq!(unsafe_fixes(&["--no-unsafe-fixes"]), Some(UnsafeFixes::Disabled));
+    }
+
+    #[test]
+    fn fixes_are_promoted_and_demoted() {
+        let settings = LinterSettings {
+            extend_safe_fixes: vec![UNSAFE_TODO.code],
+            extend_unsafe_fixes: vec![SAFE_TODO.code],
+            ..Settings::default().linter_settings
+        };
+
+        let (fixed, _) = fix_todos(&UNSAFE_TODO, &settings);
+        assert_eq!(fixed, "a  b ");
+
+        let (fixed, output) = fix_todos(&SAFE_TODO, &settings);
+        assert_eq!(fixed, "a TODO b TODO");
+        assert!(output.contains("2 hidden fixes can be enabled with the `--unsafe-fixes` option."));
+
+        // Demotion takes precedence over promotion.
+        let settings = LinterSettings {
+            extend_safe_fixes: vec![UNSAFE_TODO.code],
+            extend_unsafe_fixes: vec![UNSAFE_TODO.code],
+            ..Settings::default().linter_settings
+        };
+        assert_eq!(fix_todos(&UNSAFE_TODO, &settings).0, "a TODO b TODO");
+    }
 }
@@ -0,0 +1,17 @@
+//! Implementation of the `clean` command, which removes the cache.
+
+use anyhow::Result;
+
+use crate::cache::{self, CACHE_DIR};
+
+pub fn clean() -> Result<()> {
+    let root = std::env::current_dir()?;
+
+    if cache::clean(&root)? {
+        log::info!("removed `{}`", root.join(CACHE_DIR).display());
+    } else {
+        log::info!("no cache found in `{}`", root.display());
+    }
+
+    Ok(())
+}
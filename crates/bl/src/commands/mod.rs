@@ -1,4 +1,9 @@
 //! Defines all of the commands that are available to the user.
 
+pub mod add_noqa;
 pub mod check;
+pub mod clean;
+pub mod parse;
+pub mod show_files;
+pub mod tokens;
 pub mod version;
@@ -0,0 +1,176 @@
+//! Implementation of the `parse` command, which prints the syntax tree of a
+//! template.
+
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use bl_ast::{tree::AstTreeGenerator, LineRanges, SourceId};
+use bl_utils::{
+    stream::CompilerOutputStream,
+    stream_write, stream_writeln,
+    tree_writing::{SExpWriter, TreeWriter},
+};
+
+use crate::cli::TreeFormat;
+
+/// Read the contents of the given file, or `stdin` if the file is `-` or was
+/// not provided.
+pub(crate) fn read_source(file: Option<&Path>) -> Result<(String, String)> {
+    match file {
+        Some(path) if path != Path::new("-") => {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read `{}`", path.display()))?;
+            Ok((path.display().to_string(), contents))
+        }
+        _ => {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents).context("failed to read from stdin")?;
+            Ok(("-".to_string(), contents))
+        }
+    }
+}
+
+/// Parse the given file and print the resulting tree in the specified format.
+///
+/// Returns whether any parse errors were encountered.
+pub fn parse(file: Option<&Path>, format: TreeFormat) -> Result<bool> {
+    let (name, source) = read_source(file)?;
+    write_tree(&mut CompilerOutputStream::stdout(), &name, &source, format)
+}
+
+/// Parse the given source and write the resulting tree to the `output` in the
+/// specified format, any parse errors are logged.
+fn write_tree(
+    output: &mut dyn Write,
+    name: &str,
+    source: &str,
+    format: TreeFormat,
+) -> Result<bool> {
+    let result = bl_parse::parse(source, SourceId::default());
+
+    match format {
+        TreeFormat::Tree => {
+            let tree = AstTreeGenerator::generate(result.document.ast_ref());
+            stream_write!(output, "{}", TreeWriter::new(&tree))
+        }
+        TreeFormat::Json => {
+            serde_json::to_writer_pretty(&mut *output, &result.document)?;
+            stream_writeln!(output);
+        }
+        TreeFormat::Sexp => {
+            let tree = AstTreeGenerator::generate(result.document.ast_ref());
+            stream_write!(output, "{}", SExpWriter::new(&tree))
+        }
+    }
+
+    let lines = LineRanges::new_from_str(source);
+    for error in &result.errors {
+        log::error!("{name}:{}: {error}", lines.row_col(error.range.start()));
+    }
+
+    Ok(!result.errors.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_tree;
+    use crate::cli::TreeFormat;
+
+    /// A template with nested blocks, whose spans are inclusive byte offsets.
+    const SOURCE: &str =
+        "<main>{% block body %}{% if user %}{{ user.name }}{% endif %}{% endblock %}</main>\n";
+
+    fn dump(source: &str, format: TreeFormat) -> String {
+        let mut output = vec![];
+        assert!(!write_tree(&mut output, "-", source, format).unwrap());
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn tree_format() {
+        let expected = "\
+Document @0:82
+├─Text @0:5
+├─Block @6:74
+│ ├─label
+│ │ └─Name \"body\" @15:18
+│ └─children
+│   └─If @22:60
+│     └─clauses
+│       └─IfClause @22:49
+│         ├─condition
+│         │ └─VarExpr @28:31
+│         │   └─Name \"user\" @28:31
+│         └─Body @35:49
+│           └─Var @35:49
+│             └─AccessExpr @38:46
+│               ├─subject
+│               │ └─VarExpr @38:41
+│               │   └─Name \"user\" @38:41
+│               └─property
+│                 └─Name \"name\" @43:46
+└─Text @75:82
+";
+        assert_eq!(dump(SOURCE, TreeFormat::Tree), expected);
+    }
+
+    #[test]
+    fn sexp_format() {
+        let expected = "\
+(Document @0:82
+  (Text @0:5)
+  (Block @6:74
+    (label
+      (Name \"body\" @15:18))
+    (children
+      (If @22:60
+        (clauses
+          (IfClause @22:49
+            (condition
+              (VarExpr @28:31
+                (Name \"user\" @28:31)))
+            (Body @35:49
+              (Var @35:49
+                (AccessExpr @38:46
+                  (subject
+                    (VarExpr @38:41
+                      (Name \"user\" @38:41)))
+                  (property
+                    (Name \"name\" @43:46))))))))))
+  (Text @75:82))
+";
+        assert_eq!(dump(SOURCE, TreeFormat::Sexp), expected);
+    }
+
+    #[test]
+    fn json_format() {
+        let output = dump("{% block a %}{{ x }}{% endblock %}", TreeFormat::Json);
+        assert!(output.ends_with("}\n"));
+
+        let document: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let expected = serde_json::json!({
+            "kind": "Document",
+            "span": [0, 33],
+            "children": [{
+                "kind": "Block",
+                "span": [0, 33],
+                "label": { "kind": "Name", "span": [9, 9], "data": "a" },
+                "children": [{
+                    "kind": "Var",
+                    "span": [13, 19],
+                    "value": { "kind": "VarExpr", "span": [16, 16], "var": { "data": "x" } }
+                }]
+            }]
+        });
+        assert_eq!(document, expected);
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        let mut output = vec![];
+        assert!(write_tree(&mut output, "-", "{% block a %}", TreeFormat::Tree).unwrap());
+    }
+}
@@ -0,0 +1,40 @@
+//! Implementation of the `--show-files` option of the `check` command, which
+//! lists the files that would be checked rather than checking them.
+
+use std::{io::Write, path::PathBuf};
+
+use anyhow::Result;
+use bl_utils::{fs::relativize_path, stream::CompilerOutputStream, stream_writeln};
+use bl_workspace::{
+    resolver::{find_files_in_paths, Discovery, FileSearch},
+    settings::Settings,
+};
+
+/// Print each file that would be checked along with its dialect, followed by
+/// all of the paths that were excluded and the setting that excluded them.
+pub fn show_files(files: &[PathBuf], settings: &Settings, discovery: &Discovery) -> Result<()> {
+    let FileSearch { files, mut excluded, resolver } =
+        find_files_in_paths(files, settings, discovery)?;
+
+    let mut paths = vec![];
+    for file in files {
+        match file {
+            Ok(file) => paths.push(file.into_path()),
+            Err(error) => log::warn!("{error}"),
+        }
+    }
+    paths.sort_unstable();
+    excluded.sort_unstable_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+    let mut stream = CompilerOutputStream::stdout();
+    for path in paths {
+        let dialect = resolver.resolve(&path).linter_settings.dialect_for(&path);
+        stream_writeln!(stream, "{} ({})", relativize_path(&path), dialect);
+    }
+
+    for (path, exclusion) in excluded {
+        stream_writeln!(stream, "excluded: {} (matched {exclusion})", relativize_path(&path));
+    }
+
+    Ok(())
+}
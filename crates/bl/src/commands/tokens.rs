@@ -0,0 +1,103 @@
+//! Implementation of the `tokens` command, which prints the tokens of a
+//! template.
+
+use std::{io::Write, path::Path};
+
+use anyhow::Result;
+use bl_ast::LineRanges;
+use bl_parse::lexer::Lexer;
+use bl_utils::{stream::CompilerOutputStream, stream_writeln};
+
+use super::parse::read_source;
+
+/// Lex the given file and print each of the tokens, along with their range,
+/// position and text.
+///
+/// Returns whether any lexing errors were encountered.
+pub fn tokens(file: Option<&Path>, trivia: bool) -> Result<bool> {
+    let (name, source) = read_source(file)?;
+    Ok(write_tokens(&mut CompilerOutputStream::stdout(), &name, &source, trivia))
+}
+
+/// Lex the given source and write each of the tokens to the `output`, any
+/// lexing errors are logged.
+fn write_tokens(output: &mut dyn Write, name: &str, source: &str, trivia: bool) -> bool {
+    let result = Lexer::new(source).with_trivia(trivia).tokenise();
+    let lines = LineRanges::new_from_str(source);
+
+    for token in &result.tokens {
+        let text = &source[token.range.start()..=token.range.end()];
+
+        stream_writeln!(
+            output,
+            "{:<14} {:<12} {:<8} {:?}",
+            token.kind.name(),
+            token.range.to_string(),
+            lines.row_col(token.range.start()).to_string(),
+            text
+        );
+    }
+
+    for error in &result.errors {
+        log::error!("{name}:{}: {error}", lines.row_col(error.range.start()));
+    }
+
+    !result.errors.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_tokens;
+
+    fn dump(source: &str, trivia: bool) -> String {
+        let mut output = vec![];
+        assert!(!write_tokens(&mut output, "-", source, trivia));
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn tokens_are_dumped() {
+        // Ranges are inclusive byte offsets, and columns are counted in bytes
+        // too, so multi-byte characters take up more than one column.
+        let source = "<p>{{ café }}</p>\n{% if x %}ü{% endif %}\n";
+        assert_eq!(
+            dump(source, false),
+            [
+                r#"Text           0:2          1:1      "<p>""#,
+                r#"VariableStart  3:4          1:4      "{{""#,
+                r#"Ident          6:10         1:7      "café""#,
+                r#"VariableEnd    12:13        1:13     "}}""#,
+                r#"Text           14:18        1:15     "</p>\n""#,
+                r#"BlockStart     19:20        2:1      "{%""#,
+                r#"Ident          22:23        2:4      "if""#,
+                r#"Ident          25:25        2:7      "x""#,
+                r#"BlockEnd       27:28        2:9      "%}""#,
+                r#"Text           29:30        2:11     "ü""#,
+                r#"BlockStart     31:32        2:13     "{%""#,
+                r#"Ident          34:38        2:16     "endif""#,
+                r#"BlockEnd       40:41        2:22     "%}""#,
+                r#"Text           42:42        2:24     "\n""#,
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn trivia_is_only_dumped_when_enabled() {
+        let source = "{{ ü }}";
+        assert_eq!(
+            dump(source, true),
+            [
+                r#"VariableStart  0:1          1:1      "{{""#,
+                r#"Whitespace     2:2          1:3      " ""#,
+                r#"Ident          3:4          1:4      "ü""#,
+                r#"Whitespace     5:5          1:6      " ""#,
+                r#"VariableEnd    6:7          1:7      "}}""#,
+                "",
+            ]
+            .join("\n")
+        );
+        assert!(!dump(source, false).contains("Whitespace"));
+    }
+}
@@ -65,6 +65,7 @@ pub fn crash_handler(info: &PanicInfo) {
         );
     }
 
-    // Now call exit after we have printed all the relevant info
-    exit(1);
+    // Now call exit after we have printed all the relevant info, a crash is an
+    // internal error rather than a lint failure.
+    exit(2);
 }
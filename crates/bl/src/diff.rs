@@ -0,0 +1,96 @@
+//! Rendering of the changes that fixes would make to a file as a unified diff,
+//! which is used by `check --diff`.
+
+use std::io::Write;
+
+use bl_utils::{
+    fs::relativize_path,
+    highlight::{highlight, Colour, Highlighter, Modifier},
+    stream_write, stream_writeln,
+};
+use similar::{ChangeTag, TextDiff};
+
+use crate::commands::check::SourceChange;
+
+/// The number of unchanged lines that are shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Write the given [SourceChange] as a unified diff, with `---` and `+++`
+/// headers that refer to the path of the file.
+pub(crate) fn write_diff(writer: &mut dyn Write, change: &SourceChange, colours: bool) {
+    let path = relativize_path(&change.path);
+    let diff = TextDiff::from_lines(&change.original, &change.fixed);
+
+    stream_writeln!(writer, "{}", paint(Modifier::Bold, colours, format!("--- {path}")));
+    stream_writeln!(writer, "{}", paint(Modifier::Bold, colours, format!("+++ {path}")));
+
+    for hunk in diff.unified_diff().context_radius(CONTEXT_LINES).iter_hunks() {
+        stream_writeln!(writer, "{}", paint(Colour::Cyan, colours, hunk.header().to_string()));
+
+        for line in hunk.iter_changes() {
+            let value = line.value().strip_suffix('\n').unwrap_or(line.value());
+            match line.tag() {
+                ChangeTag::Delete => {
+                    stream_write!(writer, "{}", paint(Colour::Red, colours, format!("-{value}")))
+                }
+                ChangeTag::Insert => {
+                    stream_write!(writer, "{}", paint(Colour::Green, colours, format!("+{value}")))
+                }
+                ChangeTag::Equal => stream_write!(writer, " {value}"),
+            }
+            stream_writeln!(writer);
+
+            if line.missing_newline() {
+                stream_writeln!(writer, "\\ No newline at end of file");
+            }
+        }
+    }
+
+    stream_writeln!(writer);
+}
+
+/// Highlight the message if colours are enabled for the diff.
+fn paint(highlighter: impl Highlighter, colours: bool, message: String) -> String {
+    if colours {
+        highlight(highlighter, message)
+    } else {
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use bl_utils::stream::CompilerOutputStream;
+
+    use super::write_diff;
+    use crate::commands::check::SourceChange;
+
+    #[test]
+    fn unified_diff() {
+        let change = SourceChange {
+            path: PathBuf::from("index.html"),
+            original: "a\nb\nc\nd\ne\nf\ng\nh\n{% break %}".to_string(),
+            fixed: "a\nb\nc\nd\ne\nf\ng\nh\n".to_string(),
+        };
+
+        let mut stream = CompilerOutputStream::owned();
+        write_diff(&mut stream, &change, false);
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let expected = "\
+--- index.html
++++ index.html
+@@ -6,4 +6,3 @@
+ f
+ g
+ h
+-{% break %}
+\\ No newline at end of file
+
+";
+        assert_eq!(output, expected);
+    }
+}
@@ -2,22 +2,48 @@
 
 #![feature(panic_payload_as_str)]
 
+mod baseline;
+mod cache;
 pub mod cli;
 mod commands;
 mod crash;
+mod diff;
+mod resolve;
+mod summary;
 pub(crate) mod version;
 
 use std::{
+    io::Write,
     panic,
     path::{Path, PathBuf},
     process::ExitCode,
 };
 
-use anyhow::{Ok, Result};
-use bl_lints::settings::FixMode;
-use bl_utils::{logging::ToolLogger, stream::CompilerOutputStream};
-use bl_workspace::settings::Settings;
-use cli::CheckCommand;
+use anyhow::{bail, Ok, Result};
+use baseline::Baseline;
+use bl_diagnostics::{DiagnosticKind, Diagnostics};
+use bl_lints::{
+    message::{
+        AzureEmitter, CheckstyleEmitter, ConciseEmitter, Emitter, GithubEmitter, GitlabEmitter,
+        GroupedEmitter, JsonEmitter, JsonLinesEmitter, JunitEmitter, SarifEmitter, TextEmitter,
+    },
+    registry::RuleSelector,
+    settings::{FixMode, UnsafeFixes},
+};
+use bl_utils::{
+    highlight::{colours_enabled, set_colour_choice},
+    logging::{MessagingFormat, ToolLogger},
+    pluralise,
+    stream::CompilerOutputStream,
+    stream_less_ewriteln,
+};
+use bl_workspace::{
+    configuration::{Configuration, RuleSelection},
+    settings::FilePattern,
+};
+use clap::ValueEnum;
+use cli::{CheckCommand, OutputFormat, ParseCommand, PatternSelectorPair, TokensCommand};
+use commands::check::CheckResult;
 use crash::crash_handler;
 
 #[derive(Copy, Clone)]
@@ -26,7 +52,8 @@ pub enum ExitStatus {
     Success,
     /// Linting was successful but there were linting errors.
     Failure,
-    /// Linting failed.
+    /// Linting couldn't be completed, due to an internal error or invalid
+    /// configuration.
     Error,
 }
 
@@ -44,27 +71,45 @@ pub static LOGGER: ToolLogger = ToolLogger::new();
 
 /// Handler function which will delegate functionality to the appropriate
 /// command.
-pub fn run(cli::Cli { command }: cli::Cli) -> Result<ExitStatus> {
+pub fn run(cli::Cli { command, global_options }: cli::Cli) -> Result<ExitStatus> {
     // Initial grunt work, panic handler and logger setup...
     panic::set_hook(Box::new(crash_handler));
 
-    let output_stream = CompilerOutputStream::stdout;
-    let error_stream = CompilerOutputStream::stderr;
-
     log::set_logger(&LOGGER).unwrap_or_else(|_| panic!("couldn't initiate logger"));
 
-    LOGGER.error_stream.set(error_stream()).unwrap();
-    LOGGER.output_stream.set(output_stream()).unwrap();
-    log::set_max_level(log::LevelFilter::Debug);
+    LOGGER.error_stream.set(CompilerOutputStream::stderr()).unwrap();
+    LOGGER.output_stream.set(log_stream(&command)).unwrap();
+    log::set_max_level(if global_options.verbose {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Warn
+    });
+
+    set_colour_choice(global_options.color);
 
     // We also need to create a global-config
 
     match command {
-        cli::Command::Check(args) => check(args),
+        cli::Command::Check(args) => check(*args),
+        cli::Command::Clean => clean(),
+        cli::Command::Parse(args) => parse(args),
+        cli::Command::Tokens(args) => tokens(args),
         cli::Command::Version => version(),
     }
 }
 
+/// Get the stream that log records (other than errors) are written to. Machine
+/// readable reports are written to stdout, so log records are written to
+/// stderr instead to keep the reports parseable.
+fn log_stream(command: &cli::Command) -> CompilerOutputStream {
+    match command {
+        cli::Command::Check(args) if !args.output_format.is_human_readable() => {
+            CompilerOutputStream::stderr()
+        }
+        _ => CompilerOutputStream::stdout(),
+    }
+}
+
 /// Returns the default set of files if none are provided, otherwise returns
 /// `None`.
 fn resolve_default_files(files: Vec<PathBuf>, is_stdin: bool) -> Vec<PathBuf> {
@@ -80,14 +125,20 @@ fn resolve_default_files(files: Vec<PathBuf>, is_stdin: bool) -> Vec<PathBuf> {
 }
 
 fn check(args: CheckCommand) -> Result<ExitStatus> {
-    let files = resolve_default_files(args.files, false); // @@Todo: add stdin support.
+    // Keep any log messages consistent with the format of the results.
+    if args.output_format.is_json() {
+        LOGGER.set_messaging_format(MessagingFormat::Json);
+    }
+
+    let is_stdin = args.stdin_filename.is_some() || args.files == [Path::new("-")];
+    let files = resolve_default_files(args.files.clone(), is_stdin);
 
     // Fix rules are as follows:
     // - By default, generate all fixes, but don't apply them to the filesystem.
-    // - If `--fix` or `--fix-only` is set, apply applicable fixes to the filesystem
-    //   (or print them to stdout, if we're reading from stdin).
-    // - If `--diff` or `--fix-only` are set, don't print any violations (only
-    //   applicable fixes)
+    // - If `--fix` is set, apply applicable fixes to the filesystem (or print them
+    //   to stdout, if we're reading from stdin).
+    // - If `--diff` is set, don't apply or print any violations, only print a diff
+    //   of the applicable fixes.
 
     let fix_mode = if args.diff {
         FixMode::Diff
@@ -97,14 +148,380 @@ fn check(args: CheckCommand) -> Result<ExitStatus> {
         FixMode::Generate
     };
 
-    let settings = Settings::new(args.respect_gitignore, fix_mode);
-    let _messages = commands::check::check(&files, settings)?;
+    // Any patterns on the command line are relative to the current directory.
+    let cwd = std::env::current_dir()?;
+    let patterns = |patterns: &Option<Vec<String>>| {
+        patterns.as_ref().map(|patterns| {
+            patterns.iter().map(|pattern| FilePattern::user(pattern, &cwd)).collect::<Vec<_>>()
+        })
+    };
+
+    let overrides = Configuration {
+        include: patterns(&args.include),
+        exclude: patterns(&args.exclude),
+        extend_exclude: patterns(&args.extend_exclude).unwrap_or_default(),
+        force_exclude: args.force_exclude(),
+        respect_gitignore: args.respect_gitignore(),
+        fix_mode: Some(fix_mode),
+        unsafe_fixes: args.unsafe_fixes(),
+        rule_selections: vec![RuleSelection {
+            select: args.select.clone(),
+            extend_select: args.extend_select.clone().unwrap_or_default(),
+            ignore: args.ignore.clone().unwrap_or_default(),
+        }],
+        preview: args.preview(),
+        per_file_ignores: args.per_file_ignores.as_ref().map(|pairs| {
+            let mut ignores: Vec<(FilePattern, Vec<RuleSelector>)> = vec![];
+            for PatternSelectorPair { pattern, selector } in pairs {
+                let pattern = FilePattern::user(pattern, &cwd);
+                match ignores.iter_mut().find(|(existing, _)| *existing == pattern) {
+                    Some((_, selectors)) => selectors.push(selector.clone()),
+                    None => ignores.push((pattern, vec![selector.clone()])),
+                }
+            }
+            ignores
+        }),
+        ..Configuration::default()
+    };
+    let (settings, discovery) =
+        resolve::resolve_settings(args.config.as_deref(), args.isolated, overrides)?;
+
+    let unsafe_fixes = settings.linter_settings.unsafe_fixes;
+
+    if args.show_files {
+        commands::show_files::show_files(&files, &settings, &discovery)?;
+        return Ok(ExitStatus::Success);
+    }
+    if args.add_noqa {
+        if is_stdin {
+            bail!("`--add-noqa` can't be used when reading from stdin");
+        }
+
+        let count = commands::add_noqa::add_noqa(&files, &settings, &discovery)?;
+        if count > 0 {
+            stream_less_ewriteln!("Added {count} suppression directive{}.", pluralise!(count));
+        }
+        return Ok(ExitStatus::Success);
+    }
+
+    let mut result = if is_stdin {
+        commands::check::check_stdin(args.stdin_filename.as_deref(), settings, discovery)?
+    } else {
+        commands::check::check(&files, settings, discovery, !args.no_cache)?
+    };
+
+    // Violations that are recorded within the baseline aren't reported. The
+    // baseline must already exist, unless it is being (re-)recorded, so that a
+    // mistyped path can't silently pass.
+    if let Some(path) = &args.baseline {
+        let root = Baseline::root_of(path);
+        let current = Baseline::from_diagnostics(&result.diagnostics, &root);
+
+        match Baseline::read(path, args.update_baseline)? {
+            Some(baseline) if !args.update_baseline => {
+                result.diagnostics = baseline.filter(result.diagnostics, &root);
+            }
+            previous => {
+                current.write(path)?;
+
+                let fixed = previous.as_ref().map(|baseline| baseline.fixed(&current));
+                for entry in fixed.iter().flatten() {
+                    stream_less_ewriteln!(
+                        "Fixed: {} {}: {}",
+                        entry.path,
+                        entry.code.as_deref().unwrap_or("syntax-error"),
+                        entry.message
+                    );
+                }
+
+                let count = current.len();
+                let summary = match fixed {
+                    Some(fixed) => {
+                        format!(", {} {} fixed", fixed.len(), pluralise!("was", fixed.len()))
+                    }
+                    None => String::new(),
+                };
+                stream_less_ewriteln!(
+                    "Recorded {count} violation{} in `{}`{summary}.",
+                    pluralise!(count),
+                    path.display()
+                );
+                return Ok(ExitStatus::Success);
+            }
+        }
+    }
+
+    // In diff mode, only the changes that fixes would make are written, the
+    // violations themselves aren't reported.
+    if fix_mode == FixMode::Diff {
+        let mut stream = CompilerOutputStream::stdout();
+        for change in &result.changes {
+            diff::write_diff(&mut stream, change, colours_enabled());
+        }
+
+        let fixed = result.fixed_count();
+        if fixed > 0 {
+            stream_less_ewriteln!("Would fix {fixed} error{}.", pluralise!(fixed));
+        }
+
+        return Ok(if result.changes.is_empty() || args.exit_zero {
+            ExitStatus::Success
+        } else {
+            ExitStatus::Failure
+        });
+    }
+
+    let mut stream = report_stream(is_stdin, fix_mode);
+    write_report(&mut stream, &args, &result, unsafe_fixes)?;
+    Ok(exit_status(&args, &result.diagnostics, result.fixed_count()))
+}
+
+/// Get the stream that the report of the `check` command is written to. When
+/// fixing stdin, the fixed template is written to stdout, so the violations
+/// are written to stderr instead.
+fn report_stream(is_stdin: bool, fix_mode: FixMode) -> CompilerOutputStream {
+    if is_stdin && fix_mode == FixMode::Apply {
+        CompilerOutputStream::stderr()
+    } else {
+        CompilerOutputStream::stdout()
+    }
+}
+
+/// Write the results of the `check` command in the requested output format,
+/// along with any of the requested summaries.
+fn write_report(
+    writer: &mut dyn Write,
+    args: &CheckCommand,
+    result: &CheckResult,
+    unsafe_fixes: UnsafeFixes,
+) -> Result<()> {
+    let format = args.output_format;
+
+    // Statistics replace the violations, but can only be written as text or
+    // as plain JSON, other formats (including JSON based formats such as SARIF)
+    // report the violations as usual.
+    if args.statistics {
+        let json = matches!(format, OutputFormat::Json | OutputFormat::JsonLines);
+        if format.is_human_readable() || json {
+            return summary::write_statistics(
+                writer,
+                &result.diagnostics,
+                unsafe_fixes.required_applicability(),
+                json,
+                colours_enabled(),
+            );
+        }
+
+        let name = format.to_possible_value().map(|value| value.get_name().to_string());
+        log::warn!(
+            "`--statistics` can't be used with the `{}` output format, reporting violations instead",
+            name.unwrap_or_default()
+        );
+    }
+
+    let fixed = result.fixed_count();
+    let mut emitter: Box<dyn Emitter> = match format {
+        OutputFormat::Full => {
+            Box::new(TextEmitter::new().with_fixed(fixed).with_unsafe_fixes(unsafe_fixes))
+        }
+        OutputFormat::Concise => {
+            Box::new(ConciseEmitter::new().with_fixed(fixed).with_unsafe_fixes(unsafe_fixes))
+        }
+        OutputFormat::Grouped => {
+            Box::new(GroupedEmitter::new().with_fixed(fixed).with_unsafe_fixes(unsafe_fixes))
+        }
+        OutputFormat::Json => Box::new(JsonEmitter),
+        OutputFormat::JsonLines => Box::new(JsonLinesEmitter),
+        OutputFormat::Sarif => Box::new(SarifEmitter),
+        OutputFormat::Github => Box::new(GithubEmitter),
+        OutputFormat::Gitlab => Box::new(GitlabEmitter::new()),
+        OutputFormat::Azure => Box::new(AzureEmitter),
+        OutputFormat::Checkstyle => Box::new(CheckstyleEmitter),
+        OutputFormat::Junit => Box::new(JunitEmitter),
+    };
+    emitter.emit(writer, &result.diagnostics)?;
+
+    // The table would corrupt any machine readable output.
+    if args.show_fixes() && format.is_human_readable() {
+        summary::write_fix_table(writer, &result.fixed, colours_enabled());
+    }
+
+    Ok(())
+}
+
+/// Determine the [ExitStatus] of the `check` command, which fails when any
+/// errors remain, or any warnings remain with `--warnings-as-errors`. Notes
+/// never cause a failure.
+fn exit_status(args: &CheckCommand, diagnostics: &Diagnostics, fixed: usize) -> ExitStatus {
+    if args.exit_zero {
+        return ExitStatus::Success;
+    }
 
-    // @@TODO: display the actual diagnostics that were collected.
+    let failed = diagnostics.count(DiagnosticKind::Error) > 0
+        || (args.warnings_as_errors && diagnostics.count(DiagnosticKind::Warning) > 0)
+        || (args.exit_non_zero_on_fix && fixed > 0);
+
+    if failed {
+        ExitStatus::Failure
+    } else {
+        ExitStatus::Success
+    }
+}
+
+fn clean() -> Result<ExitStatus> {
+    commands::clean::clean()?;
     Ok(ExitStatus::Success)
 }
 
+fn parse(args: ParseCommand) -> Result<ExitStatus> {
+    let has_errors = commands::parse::parse(args.file.as_deref(), args.format)?;
+    Ok(if has_errors { ExitStatus::Failure } else { ExitStatus::Success })
+}
+
+fn tokens(args: TokensCommand) -> Result<ExitStatus> {
+    let has_errors = commands::tokens::tokens(args.file.as_deref(), args.trivia)?;
+    Ok(if has_errors { ExitStatus::Failure } else { ExitStatus::Success })
+}
+
 fn version() -> Result<ExitStatus> {
     commands::version::version()?;
     Ok(ExitStatus::Success)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind};
+    use bl_lints::{
+        fix::FixTable,
+        settings::{FixMode, UnsafeFixes},
+    };
+    use bl_utils::{highlight::ColourChoice, stream::CompilerOutputStream};
+    use clap::Parser;
+
+    use super::{log_stream, report_stream, set_colour_choice, write_report};
+    use crate::{
+        cli::{CheckCommand, Command},
+        commands::check::CheckResult,
+    };
+
+    #[test]
+    fn summaries_keep_xml_reports_valid() {
+        let id = SourceMap::add("report.html", "{% break %}");
+        let span = Span::new(ByteRange::new(0, 10), id);
+        let result = CheckResult {
+            diagnostics: [
+                Diagnostic::new(DiagnosticKind::Error, "message", span).with_code("BL002")
+            ]
+            .into_iter()
+            .collect(),
+            fixed: BTreeMap::from([(PathBuf::from("report.html"), FixTable::from([("BL003", 1)]))]),
+            changes: vec![],
+        };
+
+        for format in ["checkstyle", "junit"] {
+            for flag in ["--show-fixes", "--statistics"] {
+                let args = CheckCommand::parse_from(["check", "--output-format", format, flag]);
+                let mut stream = CompilerOutputStream::owned();
+                write_report(&mut stream, &args, &result, UnsafeFixes::default()).unwrap();
+
+                let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+                let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+                if let Err(error) = roxmltree::Document::parse(&output) {
+                    panic!("invalid XML for `{format}` with `{flag}`: {error}\n{output}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fix_table_follows_remaining_violations() {
+        let id = SourceMap::add("remaining.html", "{% break %}");
+        let span = Span::new(ByteRange::new(0, 10), id);
+        let result = CheckResult {
+            diagnostics: [
+                Diagnostic::new(DiagnosticKind::Error, "message", span).with_code("BL002")
+            ]
+            .into_iter()
+            .collect(),
+            fixed: BTreeMap::from([(
+                PathBuf::from("remaining.html"),
+                FixTable::from([("BL003", 2)]),
+            )]),
+            changes: vec![],
+        };
+
+        set_colour_choice(ColourChoice::Never);
+        let args =
+            CheckCommand::parse_from(["check", "--output-format", "concise", "--show-fixes"]);
+        let mut stream = CompilerOutputStream::owned();
+        write_report(&mut stream, &args, &result, UnsafeFixes::default()).unwrap();
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let expected = "\
+remaining.html:1:1: BL002 message
+Found 3 violations (2 fixed, 1 error remaining).
+Fixed 2 errors:
+- remaining.html:
+    2 × BL003 (unused-suppression)
+";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn summaries_keep_json_reports_valid() {
+        let id = SourceMap::add("report.json", "{% break %}");
+        let span = Span::new(ByteRange::new(0, 10), id);
+        let result = CheckResult {
+            diagnostics: [
+                Diagnostic::new(DiagnosticKind::Error, "message", span).with_code("BL002")
+            ]
+            .into_iter()
+            .collect(),
+            fixed: BTreeMap::from([(PathBuf::from("report.json"), FixTable::from([("BL003", 1)]))]),
+            changes: vec![],
+        };
+
+        for format in ["sarif", "gitlab"] {
+            for flag in ["--show-fixes", "--statistics"] {
+                let args = CheckCommand::parse_from(["check", "--output-format", format, flag]);
+                let mut stream = CompilerOutputStream::owned();
+                write_report(&mut stream, &args, &result, UnsafeFixes::default()).unwrap();
+
+                let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+                let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+                let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+                // The violations are reported rather than the statistics.
+                let valid = match format {
+                    "sarif" => report["runs"][0]["results"][0]["ruleId"] == "BL002",
+                    _ => report[0]["check_name"] == "BL002",
+                };
+                assert!(valid, "invalid report for `{format}` with `{flag}`:\n{output}");
+            }
+        }
+    }
+
+    #[test]
+    fn fixing_stdin_reports_to_stderr() {
+        assert!(matches!(report_stream(true, FixMode::Apply), CompilerOutputStream::Stderr(_)));
+        assert!(matches!(report_stream(true, FixMode::Generate), CompilerOutputStream::Stdout(_)));
+        assert!(matches!(report_stream(false, FixMode::Apply), CompilerOutputStream::Stdout(_)));
+    }
+
+    #[test]
+    fn machine_readable_formats_log_to_stderr() {
+        let stream = |format| {
+            let args = CheckCommand::parse_from(["check", "--output-format", format]);
+            log_stream(&Command::Check(Box::new(args)))
+        };
+
+        assert!(matches!(stream("full"), CompilerOutputStream::Stdout(_)));
+        for format in ["json", "checkstyle", "junit", "github", "azure", "sarif"] {
+            assert!(matches!(stream(format), CompilerOutputStream::Stderr(_)), "{format}");
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::process::ExitCode;
 
-use bracketlint::{cli::Cli, run};
+use bracketlint::{cli::Cli, run, ExitStatus};
 use clap::Parser;
 
 pub fn main() -> ExitCode {
@@ -19,7 +19,10 @@ pub fn main() -> ExitCode {
         Ok(status) => status.into(),
         Err(e) => {
             eprintln!("error: {e}");
-            ExitCode::from(1)
+            for cause in e.chain().skip(1) {
+                eprintln!("  Caused by: {cause}");
+            }
+            ExitStatus::Error.into()
         }
     }
 }
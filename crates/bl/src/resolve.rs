@@ -0,0 +1,42 @@
+//! Resolution of the [Settings] that are used by the `check` command, from the
+//! discovered configuration file and the command line arguments.
+
+use std::path::Path;
+
+use anyhow::Result;
+use bl_workspace::{
+    configuration::Configuration, options::find_config_file, resolver::Discovery,
+    settings::Settings,
+};
+
+/// Resolve the root [Settings] to use, and how the settings of nested
+/// directories are discovered. The configuration is read from the given
+/// `config` file, or otherwise discovered from the current directory, unless
+/// `isolated` is set. The `overrides` from the command line take precedence
+/// over all configuration files.
+///
+/// Configuration files in nested directories are only used when the
+/// configuration is discovered, an explicit `config` file applies to every
+/// file.
+pub(crate) fn resolve_settings(
+    config: Option<&Path>,
+    isolated: bool,
+    overrides: Configuration,
+) -> Result<(Settings, Discovery)> {
+    let (configuration, discovery) = if isolated {
+        (Configuration::default(), Discovery::Fixed)
+    } else if let Some(path) = config {
+        (Configuration::from_file(path)?, Discovery::Fixed)
+    } else {
+        let configuration = match find_config_file(&std::env::current_dir()?)? {
+            Some(path) => {
+                log::debug!("using configuration from `{}`", path.display());
+                Configuration::from_file(&path)?
+            }
+            None => Configuration::default(),
+        };
+        (configuration, Discovery::Hierarchical(overrides.clone()))
+    };
+
+    Ok((overrides.combine(configuration).into_settings()?, discovery))
+}
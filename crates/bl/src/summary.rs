@@ -0,0 +1,204 @@
+//! Summaries of the results of the `check` command, the table of the fixes
+//! that were applied (`--show-fixes`) and the number of violations of each
+//! rule (`--statistics`).
+
+use std::{collections::BTreeMap, io::Write, path::PathBuf};
+
+use anyhow::Result;
+use bl_diagnostics::{Applicability, Diagnostics};
+use bl_lints::{fix::FixTable, registry::rule_by_code};
+use bl_utils::{
+    fs::relativize_path,
+    highlight::{highlight, Colour, Modifier},
+    pluralise, stream_writeln,
+};
+use serde::Serialize;
+
+/// The name that is shown for diagnostics that don't belong to a rule, i.e.
+/// parse errors.
+const SYNTAX_ERROR: &str = "syntax-error";
+
+/// Write a table of the fixes that were applied, grouped by file and then by
+/// rule, i.e.
+///
+/// ```text
+/// Fixed 3 errors:
+/// - templates/index.html:
+///     1 × BL002 (loop-control-outside-loop)
+///     2 × BL003 (unused-suppression)
+/// ```
+pub(crate) fn write_fix_table(
+    writer: &mut dyn Write,
+    fixed: &BTreeMap<PathBuf, FixTable>,
+    colours: bool,
+) {
+    let total: usize = fixed.values().flat_map(|fixes| fixes.values()).sum();
+    if total == 0 {
+        return;
+    }
+
+    let bold = |message: String| if colours { highlight(Modifier::Bold, message) } else { message };
+    stream_writeln!(writer, "{}", bold(format!("Fixed {total} error{}:", pluralise!(total))));
+
+    for (path, fixes) in fixed {
+        stream_writeln!(writer, "- {}:", bold(relativize_path(path)));
+
+        let width = fixes.values().map(|count| count.to_string().len()).max().unwrap_or(0);
+        for (code, count) in fixes {
+            let name = rule_by_code(code).map_or("", |rule| rule.name());
+            let code = if colours {
+                highlight(Colour::Red | Modifier::Bold, code)
+            } else {
+                code.to_string()
+            };
+            stream_writeln!(writer, "    {count:>width$} × {code} ({name})");
+        }
+    }
+}
+
+/// The number of violations of a single rule.
+#[derive(Serialize)]
+struct RuleStatistic {
+    /// The code of the rule, or `None` for syntax errors.
+    code: Option<&'static str>,
+
+    /// The name of the rule.
+    name: &'static str,
+
+    /// The number of violations of the rule.
+    count: usize,
+
+    /// Whether any of the violations can be fixed.
+    fixable: bool,
+}
+
+/// Collect the number of violations of each rule, ordered by the number of
+/// violations and then by code.
+fn statistics(diagnostics: &Diagnostics, required: Applicability) -> Vec<RuleStatistic> {
+    let mut statistics: BTreeMap<Option<&'static str>, RuleStatistic> = BTreeMap::new();
+
+    for diagnostic in diagnostics {
+        let statistic = statistics.entry(diagnostic.code).or_insert_with(|| RuleStatistic {
+            code: diagnostic.code,
+            name: diagnostic.code.and_then(rule_by_code).map_or(SYNTAX_ERROR, |rule| rule.name()),
+            count: 0,
+            fixable: false,
+        });
+
+        statistic.count += 1;
+        statistic.fixable |= diagnostic.is_fixable_with(required);
+    }
+
+    let mut statistics: Vec<_> = statistics.into_values().collect();
+    statistics.sort_by(|lhs, rhs| rhs.count.cmp(&lhs.count).then(lhs.code.cmp(&rhs.code)));
+    statistics
+}
+
+/// Write one line for each rule that was violated with the number of
+/// violations and whether they can be fixed, i.e. `12  BL002  [*] name`.
+/// When `json` is set, the statistics are written as a JSON array instead.
+pub(crate) fn write_statistics(
+    writer: &mut dyn Write,
+    diagnostics: &Diagnostics,
+    required: Applicability,
+    json: bool,
+    colours: bool,
+) -> Result<()> {
+    let statistics = statistics(diagnostics, required);
+
+    if json {
+        serde_json::to_writer_pretty(&mut *writer, &statistics)?;
+        stream_writeln!(writer);
+        return Ok(());
+    }
+
+    let count_width =
+        statistics.iter().map(|statistic| statistic.count.to_string().len()).max().unwrap_or(0);
+    let code_width =
+        statistics.iter().map(|statistic| statistic.code.map_or(0, str::len)).max().unwrap_or(0);
+
+    for statistic in &statistics {
+        let code = format!("{:<code_width$}", statistic.code.unwrap_or(""));
+        let code = if colours { highlight(Colour::Red | Modifier::Bold, code) } else { code };
+        let marker = if statistic.fixable { "[*]" } else { "[ ]" };
+
+        stream_writeln!(
+            writer,
+            "{:>count_width$}\t{code}\t{marker} {}",
+            statistic.count,
+            statistic.name
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Applicability, Diagnostic, DiagnosticKind, Diagnostics, Edit, Fix};
+    use bl_lints::fix::FixTable;
+    use bl_utils::stream::CompilerOutputStream;
+
+    use super::{write_fix_table, write_statistics};
+
+    #[test]
+    fn fixes_are_grouped_by_file() {
+        let fixed = BTreeMap::from([
+            (PathBuf::from("templates/index.html"), FixTable::from([("BL002", 1), ("BL003", 12)])),
+            (PathBuf::from("templates/base.html"), FixTable::from([("BL003", 1)])),
+        ]);
+
+        let mut stream = CompilerOutputStream::owned();
+        write_fix_table(&mut stream, &fixed, false);
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let expected = "\
+Fixed 14 errors:
+- templates/base.html:
+    1 × BL003 (unused-suppression)
+- templates/index.html:
+     1 × BL002 (loop-control-outside-loop)
+    12 × BL003 (unused-suppression)
+";
+        assert_eq!(output, expected);
+
+        // Nothing is written when nothing was fixed.
+        let mut stream = CompilerOutputStream::owned();
+        write_fix_table(&mut stream, &BTreeMap::new(), false);
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        assert!(output.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn statistics_are_ordered_by_count() {
+        let id = SourceMap::add("index.html", "{% break %}{% break %}{% block a %}{% block a %}");
+        let span = Span::new(ByteRange::new(0, 10), id);
+        let diagnostic =
+            |code| Diagnostic::new(DiagnosticKind::Error, "message", span).with_code(code);
+
+        let diagnostics: Diagnostics = [
+            diagnostic("BL001"),
+            diagnostic("BL002"),
+            diagnostic("BL002").with_fix(Fix::safe_edits([Edit::delete(ByteRange::new(0, 10))])),
+            Diagnostic::new(DiagnosticKind::Error, "unexpected end of file", span),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut stream = CompilerOutputStream::owned();
+        write_statistics(&mut stream, &diagnostics, Applicability::Safe, false, false).unwrap();
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let expected = "\
+2\tBL002\t[*] loop-control-outside-loop
+1\t     \t[ ] syntax-error
+1\tBL001\t[ ] duplicate-block
+";
+        assert_eq!(output, expected);
+    }
+}
@@ -2,6 +2,7 @@
 //! `bracketlint` supports parsing.
 
 use std::{
+    fmt,
     iter::repeat,
     ops::{Deref, DerefMut},
 };
@@ -13,7 +14,10 @@ use parking_lot::{RwLock, RwLockWriteGuard};
 use replace_with::replace_with_or_abort;
 use thin_vec::{thin_vec, ThinVec};
 
+#[cfg(feature = "serde")]
+use crate::serialise::SerdeNode;
 use crate::{
+    ident::Identifier,
     location::{SourceId, Span},
     ByteRange,
 };
@@ -183,6 +187,21 @@ pub struct AstNode<T> {
 }
 
 impl<T> AstNode<T> {
+    /// Create a new [AstNode] with the given body and [Span].
+    pub fn new(body: T, span: Span) -> Self {
+        Self { body: Box::new(body), id: SpanMap::add_span(span) }
+    }
+
+    /// Create a new [AstNode] with an existing [AstNodeId].
+    pub fn with_id(body: T, id: AstNodeId) -> Self {
+        Self { body: Box::new(body), id }
+    }
+
+    /// Get the [Span] of this [AstNode].
+    pub fn span(&self) -> Span {
+        SpanMap::span_of(self.id)
+    }
+
     /// Create an [AstNodeRef] from this [AstNode].
     pub fn ast_ref(&self) -> AstNodeRef<T> {
         AstNodeRef { body: self.body.as_ref(), id: self.id }
@@ -334,6 +353,40 @@ pub trait OwnsAstNode<T> {
     }
 }
 
+/// Structural equality of nodes, which compares the kinds and fields of nodes
+/// whilst ignoring their [AstNodeId]s (and hence their spans). This means that
+/// two parses of the same source are always structurally equal, unlike when
+/// using [PartialEq] which compares the ids of nodes.
+///
+/// This trait is implemented for each node by the `define_tree!` macro.
+pub trait StructuralEq {
+    /// Check whether `self` and `other` are structurally equal.
+    fn structural_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: StructuralEq> StructuralEq for AstNode<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.body.structural_eq(&other.body)
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for AstNodes<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.nodes.len() == other.nodes.len()
+            && self.nodes.iter().zip(other.nodes.iter()).all(|(lhs, rhs)| lhs.structural_eq(rhs))
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Option<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(lhs), Some(rhs)) => lhs.structural_eq(rhs),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
 /// A collection of [AstNode]s with an optional shared
 /// span. This is often used to represent collections
 /// of [AstNode]s when they are wrapped within some kind
@@ -423,12 +476,13 @@ define_tree! {
         visitor_node_ref_base_type_name: AstNodeRef,
         get_ref_from_node_function_base_name: ast_ref,
         ref_change_body_function_base_name: with_body,
-        root_module: bl_ast::ast,
+        root_module: bl_ast,
     }}
 
-    #[derive(Hash, Debug, Clone, Copy, PartialEq, Eq)]
+    /// All binary operators.
+    #[derive(Copy, Clone, Debug, PartialEq)]
     #[node]
-    pub enum Op {
+    pub enum BinOp {
         /// +
         Add,
         /// -
@@ -437,14 +491,14 @@ define_tree! {
         Mul,
         /// /
         Div,
+        /// //
+        FloorDiv,
         /// %
         Modulo,
-    }
-
-    /// All logic operators
-    #[derive(Copy, Clone, Debug, PartialEq)]
-    #[node]
-    pub enum BinOp {
+        /// **
+        Pow,
+        /// ~
+        Concat,
         /// >
         Gt,
         /// >=
@@ -462,7 +516,9 @@ define_tree! {
         /// `or`
         Or,
         /// `in`
-        In
+        In,
+        /// `is`
+        Is,
     }
 
     /// Unary operators
@@ -480,35 +536,49 @@ define_tree! {
     pub enum Lit {
         Bool(bool),
         Float(f64),
-        Int(i64)
+        Int(i64),
+        Str(String),
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct LitExpr {
-        lit: Lit
+        pub lit: Lit
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct ArrayExpr {
-        children: Children!(Expr),
+        pub children: Children!(Expr),
     }
 
+    /// A single `key: value` entry within a [DictExpr].
     #[derive(Clone, Debug, PartialEq)]
     #[node]
-    pub struct BinExpr {
-        lhs: Child!(Expr),
-        rhs: Child!(Expr),
-        op: Child!(BinOp),
+    pub struct DictEntry {
+        pub key: Child!(Expr),
+        pub value: Child!(Expr),
     }
 
-    pub type Identifier = u32;
+    /// A dictionary literal, i.e. `{"class": "btn", "id": id}`.
+    #[derive(Clone, Debug, PartialEq)]
+    #[node]
+    pub struct DictExpr {
+        pub entries: Children!(DictEntry),
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    #[node]
+    pub struct BinExpr {
+        pub lhs: Child!(Expr),
+        pub rhs: Child!(Expr),
+        pub op: Child!(BinOp),
+    }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Name {
-        data: Identifier
+        pub data: Identifier
     }
 
     /// A path reference, possibly to another file, or a module, i.e. in an `include`
@@ -521,21 +591,46 @@ define_tree! {
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Path {
-        data: String
+        pub data: String
     }
 
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct VarExpr {
-        var: Name,
+        pub var: Name,
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct UnaryExpr {
-        op: Child!(UnaryOp),
-        expr: Child!(Expr),
+        pub op: Child!(UnaryOp),
+        pub expr: Child!(Expr),
+    }
+
+    /// An attribute access on some subject, i.e. `user.name`.
+    #[derive(Clone, Debug, PartialEq)]
+    #[node]
+    pub struct AccessExpr {
+        pub subject: Child!(Expr),
+        pub property: Child!(Name),
+    }
+
+    /// A subscript on some subject, i.e. `users[0]`.
+    #[derive(Clone, Debug, PartialEq)]
+    #[node]
+    pub struct IndexExpr {
+        pub subject: Child!(Expr),
+        pub index: Child!(Expr),
+    }
+
+    /// An inline conditional expression, i.e. `x if cond else y`.
+    #[derive(Clone, Debug, PartialEq)]
+    #[node]
+    pub struct CondExpr {
+        pub value: Child!(Expr),
+        pub condition: Child!(Expr),
+        pub otherwise: OptionalChild!(Expr),
     }
 
     /// An argument to a function, filter or a custom taf call.
@@ -545,30 +640,30 @@ define_tree! {
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Arg {
-        name: OptionalChild!(Name),
-        value: OptionalChild!(Expr),
+        pub name: OptionalChild!(Name),
+        pub value: OptionalChild!(Expr),
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct CallExpr {
-        subject: Child!(Expr),
-        args: Children!(Arg),
+        pub subject: Child!(Expr),
+        pub args: Children!(Arg),
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct MacroCallExpr {
-        name: Child!(Name),
-        namespace: Child!(Name),
-        args: Children!(Arg),
+        pub name: Child!(Name),
+        pub namespace: Child!(Name),
+        pub args: Children!(Arg),
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct FilteredExpr {
-        subject: Child!(Expr),
-        filters: Children!(CallExpr),
+        pub subject: Child!(Expr),
+        pub filters: Children!(CallExpr),
     }
 
     #[derive(Clone, Debug, PartialEq)]
@@ -577,8 +672,12 @@ define_tree! {
         Unary(UnaryExpr),
         Lit(LitExpr),
         Array(ArrayExpr),
+        Dict(DictExpr),
         Bin(BinExpr),
         Var(VarExpr),
+        Access(AccessExpr),
+        Index(IndexExpr),
+        Cond(CondExpr),
         Call(CallExpr),
         MacroCall(MacroCallExpr),
         FilteredExpr(FilteredExpr)
@@ -594,37 +693,44 @@ define_tree! {
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Block {
-        label: OptionalChild!(Name),
-        children: Children!(Statement),
+        pub label: OptionalChild!(Name),
+        pub children: Children!(Statement),
     }
 
+    /// A macro definition, the arguments of the macro are represented as [Arg]s
+    /// so that default values can be stored alongside the name of the argument.
+    ///
+    /// ```html
+    /// {% macro input(name, type="text") %}
+    /// {% endmacro %}
+    /// ```
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct MacroDef {
-        name: Child!(Name),
-        args: Children!(Name),
-        inner: Children!(Statement),
+        pub name: Child!(Name),
+        pub args: Children!(Arg),
+        pub inner: Children!(Statement),
     }
 
     /// Directly insert the contents of another file into the current template.
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Include {
-        path: Child!(Path),
+        pub path: Child!(Path),
     }
 
     /// Extend the current template with the contents of another file.
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Extends {
-        path: Child!(Path),
+        pub path: Child!(Path),
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Import {
-        path: Child!(Path),
-        name: OptionalChild!(Name),
+        pub path: Child!(Path),
+        pub name: OptionalChild!(Name),
     }
 
     /// A tag to set a value in place, i.e.
@@ -642,8 +748,8 @@ define_tree! {
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Set {
-        name: Child!(Name),
-        value: Child!(Expr),
+        pub name: Child!(Name),
+        pub value: Child!(Expr),
     }
 
     #[derive(Clone, Debug, PartialEq)]
@@ -656,8 +762,8 @@ define_tree! {
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Tag {
-        name: Child!(Name),
-        args: Children!(Arg),
+        pub name: Child!(Name),
+        pub args: Children!(Arg),
     }
 
     /// A hunk of text, the [Span] of this node exactly represents the range
@@ -667,16 +773,17 @@ define_tree! {
     pub struct Text {
     }
 
+    /// An interpolated expression, i.e. `{{ user.name | title }}`.
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Var {
-        identifier: Child!(Name),
+        pub value: Child!(Expr),
     }
 
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct Body {
-        contents: Children!(Statement),
+        pub contents: Children!(Statement),
     }
 
     #[derive(Debug, PartialEq, Clone)]
@@ -698,13 +805,23 @@ define_tree! {
         pub otherwise: OptionalChild!(Body),
     }
 
+    /// A `for` loop over some iterable, i.e.
+    ///
+    /// ```html
+    /// {% for key, value in items if value %}
+    /// {% else %}
+    /// {% endfor %}
+    /// ```
     #[derive(Clone, Debug, PartialEq)]
     #[node]
     pub struct For {
-        key: OptionalChild!(Name),
-        value: Child!(Name),
-        loop_body: Child!(Body),
-        loop_empty: OptionalChild!(Body),
+        pub key: OptionalChild!(Name),
+        pub value: Child!(Name),
+        pub iterable: Child!(Expr),
+        /// An optional filtering condition on the items of the loop.
+        pub condition: OptionalChild!(Expr),
+        pub loop_body: Child!(Body),
+        pub loop_empty: OptionalChild!(Body),
     }
 
     /// Control flow statement to skip the current iteration of a [`For`] loop.
@@ -772,3 +889,51 @@ define_tree! {
         pub children: Children!(Statement),
     }
 }
+
+impl BinOp {
+    /// Get the textual representation of the [BinOp].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::FloorDiv => "//",
+            BinOp::Modulo => "%",
+            BinOp::Pow => "**",
+            BinOp::Concat => "~",
+            BinOp::Gt => ">",
+            BinOp::Gte => ">=",
+            BinOp::Lt => "<",
+            BinOp::Lte => "<=",
+            BinOp::Eq => "==",
+            BinOp::NotEq => "!=",
+            BinOp::And => "and",
+            BinOp::Or => "or",
+            BinOp::In => "in",
+            BinOp::Is => "is",
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl UnaryOp {
+    /// Get the textual representation of the [UnaryOp].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnaryOp::Not => "not",
+            UnaryOp::Neg => "-",
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,74 @@
+//! Interned identifiers that are used within the AST. An [Identifier] is a
+//! cheap, copyable handle to a string that has been stored in the global
+//! identifier map, which means that comparing identifiers is as cheap as
+//! comparing two integers.
+
+use std::{collections::HashMap, fmt};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+/// The global map of all of the interned identifiers.
+///
+/// ##Note: interned strings are leaked since they live for the entire duration
+/// of the run, and this allows us to hand out `&'static str` references.
+static IDENTS: Lazy<RwLock<IdentifierMap>> = Lazy::new(|| RwLock::new(IdentifierMap::default()));
+
+#[derive(Default)]
+struct IdentifierMap {
+    /// Mapping from the string contents to the [Identifier].
+    lookup: HashMap<&'static str, Identifier>,
+
+    /// The contents of each [Identifier], indexed by the identifier itself.
+    strings: Vec<&'static str>,
+}
+
+/// An interned name that is used to refer to variables, tags, filters, etc.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Identifier(u32);
+
+impl Identifier {
+    /// Intern the given string, returning the associated [Identifier].
+    pub fn intern(value: &str) -> Self {
+        if let Some(ident) = IDENTS.read().lookup.get(value) {
+            return *ident;
+        }
+
+        let mut map = IDENTS.write();
+
+        // Another thread might have interned the string between us dropping
+        // the reader and acquiring the writer.
+        if let Some(ident) = map.lookup.get(value) {
+            return *ident;
+        }
+
+        let value: &'static str = Box::leak(value.to_owned().into_boxed_str());
+        let ident = Identifier(map.strings.len() as u32);
+        map.strings.push(value);
+        map.lookup.insert(value, ident);
+        ident
+    }
+
+    /// Get the string contents of the [Identifier].
+    pub fn as_str(&self) -> &'static str {
+        IDENTS.read().strings[self.0 as usize]
+    }
+}
+
+impl From<&str> for Identifier {
+    fn from(value: &str) -> Self {
+        Identifier::intern(value)
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Debug for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
@@ -1,10 +1,19 @@
 //! Contains all of AST definitions for HTML templates.
 
 mod ast;
+mod ident;
 mod location;
+#[cfg(feature = "serde")]
+pub mod serialise;
+mod source;
+pub mod tree;
 
 pub use ast::*;
-pub use location::{ByteRange, Span};
+#[doc(hidden)]
+pub use bl_macros::difference;
+pub use ident::Identifier;
+pub use location::{ByteRange, LineRanges, RowCol, RowColRange, SourceId, Span, SpannedSource};
+pub use source::{SourceFile, SourceMap};
 
 pub mod visitor {
     pub use super::ast::{
@@ -2,14 +2,16 @@
 use std::{cmp, fmt};
 
 use derive_more::Constructor;
+use line_span::LineSpanExt;
 
 pub static SOURCE_COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
 
 #[derive(Debug, Clone, Copy, Constructor, PartialEq, Eq, Hash)]
 pub struct SourceId(u32);
 
-impl SourceId {
-    pub fn default() -> Self {
+impl Default for SourceId {
+    /// Create a new unique [SourceId].
+    fn default() -> Self {
         SourceId(SOURCE_COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst))
     }
 }
@@ -160,3 +162,88 @@ impl<'s> SpannedSource<'s> {
         &self.0[range.start()..end]
     }
 }
+
+/// A [RowCol] is a human-readable position within a source, where both the
+/// `row` and `column` are zero-indexed. When displayed, both of the components
+/// are incremented by one to match the convention that editors use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RowCol {
+    /// The line number, starting from zero.
+    pub row: usize,
+
+    /// The column number, starting from zero.
+    pub column: usize,
+}
+
+impl fmt::Display for RowCol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.row + 1, self.column + 1)
+    }
+}
+
+/// A [RowColRange] is the human-readable equivalent of a [ByteRange].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RowColRange {
+    /// The starting position of the range.
+    pub start: RowCol,
+
+    /// The (inclusive) ending position of the range.
+    pub end: RowCol,
+}
+
+impl fmt::Display for RowColRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// [LineRanges] stores the byte offsets at which each line of a source begins,
+/// which allows for byte offsets to be converted into [RowCol]s quickly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRanges(Vec<usize>);
+
+impl LineRanges {
+    /// Compute the [LineRanges] of the given source.
+    pub fn new_from_str(source: &str) -> Self {
+        let mut starts: Vec<_> = source.line_spans().map(|span| span.start()).collect();
+
+        // An empty source still has a single (empty) line, and so does the end
+        // of a source that is terminated by a newline.
+        if starts.is_empty() || source.ends_with('\n') {
+            starts.push(source.len());
+        }
+
+        Self(starts)
+    }
+
+    /// Get the number of lines in the source.
+    pub fn line_count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Get the byte offset at which the given (zero-indexed) line starts.
+    pub fn line_start(&self, row: usize) -> Option<usize> {
+        self.0.get(row).copied()
+    }
+
+    /// Convert a byte offset into a [RowCol].
+    ///
+    /// ##Note: the column is computed in bytes, not characters.
+    pub fn row_col(&self, offset: usize) -> RowCol {
+        let row = match self.0.binary_search(&offset) {
+            Ok(row) => row,
+            Err(row) => row.saturating_sub(1),
+        };
+
+        RowCol { row, column: offset - self.0[row] }
+    }
+
+    /// Convert a [ByteRange] into a [RowColRange].
+    pub fn row_col_range(&self, range: ByteRange) -> RowColRange {
+        RowColRange { start: self.row_col(range.start()), end: self.row_col(range.end()) }
+    }
+}
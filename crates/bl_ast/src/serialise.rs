@@ -0,0 +1,220 @@
+//! Serialisation and deserialisation of the AST, this is only available when
+//! the `serde` feature is enabled.
+//!
+//! Each [AstNode] is written as a map in the form of `{kind, span, ...fields}`,
+//! where the `span` is the [ByteRange] of the node resolved through the span
+//! map, and [Identifier]s are resolved through the interner. [AstNodes] are
+//! written as a sequence of their nodes.
+//!
+//! When deserialising, new [AstNodeId]s are created for every node, and the
+//! spans are attributed to the [SourceId] given to [with_source].
+
+use std::cell::Cell;
+
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value};
+use thin_vec::ThinVec;
+
+use crate::{ident::Identifier, AstNode, AstNodes, ByteRange, SourceId, Span};
+
+/// A node that can be serialised as `{kind, span, ...fields}`. This trait is
+/// implemented for each node by the [`bl_macros::define_tree`] macro.
+pub trait SerdeNode: Sized {
+    /// The kind of the node, i.e. the name of the node definition.
+    fn kind(&self) -> &'static str;
+
+    /// Write each of the fields of the node into the given map.
+    fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error>;
+
+    /// Re-construct a node of the given `kind` from its fields.
+    fn deserialize_fields(
+        kind: &str,
+        fields: &mut Map<String, Value>,
+    ) -> Result<Self, serde_json::Error>;
+
+    /// Remove and deserialise a field from the given map, missing fields are
+    /// treated as `null` so that optional children can be omitted.
+    fn take_field<T: DeserializeOwned>(
+        fields: &mut Map<String, Value>,
+        name: &str,
+    ) -> Result<T, serde_json::Error> {
+        let value = fields.remove(name).unwrap_or(Value::Null);
+        serde_json::from_value(value)
+            .map_err(|err| serde_json::Error::custom(format!("field `{name}`: {err}")))
+    }
+
+    /// Check that the given `kind` matches the `expected` kind.
+    fn expect_kind(kind: &str, expected: &str) -> Result<(), serde_json::Error> {
+        if kind == expected {
+            Ok(())
+        } else {
+            Err(Self::unknown_kind(kind, expected))
+        }
+    }
+
+    /// Create an error for an unexpected node `kind`.
+    fn unknown_kind(kind: &str, expected: &str) -> serde_json::Error {
+        serde_json::Error::custom(format!("expected a node of kind `{expected}`, found `{kind}`"))
+    }
+}
+
+thread_local! {
+    /// The [SourceId] that deserialised nodes are attributed to.
+    static SOURCE: Cell<Option<SourceId>> = const { Cell::new(None) };
+}
+
+/// Run the given function, attributing all nodes deserialised within it to the
+/// given [SourceId]. Nodes that are deserialised outside of this function are
+/// attributed to a [SourceId] that is unique to the current thread.
+pub fn with_source<R>(source: SourceId, f: impl FnOnce() -> R) -> R {
+    let previous = SOURCE.replace(Some(source));
+    let result = f();
+    SOURCE.set(previous);
+    result
+}
+
+/// Get the [SourceId] that deserialised nodes should be attributed to.
+fn current_source() -> SourceId {
+    SOURCE.get().unwrap_or_else(|| {
+        let source = SourceId::default();
+        SOURCE.set(Some(source));
+        source
+    })
+}
+
+impl<T: SerdeNode> Serialize for AstNode<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let range = self.span().range;
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.body.kind())?;
+        map.serialize_entry("span", &(range.start(), range.end()))?;
+        self.body.serialize_fields(&mut map)?;
+        map.end()
+    }
+}
+
+impl<'de, T: SerdeNode> Deserialize<'de> for AstNode<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = Map::deserialize(deserializer)?;
+
+        let kind: String = T::take_field(&mut fields, "kind").map_err(D::Error::custom)?;
+        let (start, end): (usize, usize) =
+            T::take_field(&mut fields, "span").map_err(D::Error::custom)?;
+
+        if start > end {
+            return Err(D::Error::custom(format!("invalid span `{start}:{end}`")));
+        }
+
+        let body = T::deserialize_fields(&kind, &mut fields).map_err(D::Error::custom)?;
+        Ok(AstNode::new(body, Span::new(ByteRange::new(start, end), current_source())))
+    }
+}
+
+impl<T: SerdeNode> Serialize for AstNodes<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.nodes.len()))?;
+        for node in self.nodes.iter() {
+            seq.serialize_element(node)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: SerdeNode> Deserialize<'de> for AstNodes<T> {
+    /// ##Note: the span of the collection is not serialised, so it is
+    /// re-constructed from the spans of the nodes. Empty collections are given
+    /// an empty span at the start of the source.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let nodes = Vec::<AstNode<T>>::deserialize(deserializer)?;
+
+        let span = nodes
+            .iter()
+            .map(|node| node.span())
+            .reduce(|acc, span| acc.join(span))
+            .unwrap_or_else(|| Span::new(ByteRange::default(), current_source()));
+
+        Ok(AstNodes::new(ThinVec::from(nodes), span))
+    }
+}
+
+impl Serialize for Identifier {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Identifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Identifier::intern(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use thin_vec::thin_vec;
+
+    use crate::{
+        AstNode, AstNodes, BinExpr, BinOp, ByteRange, Document, Expr, Identifier, Lit, LitExpr,
+        Name, SourceId, Span, Statement, Var, VarExpr,
+    };
+
+    fn node<T>(body: T, start: usize, end: usize, source: SourceId) -> AstNode<T> {
+        AstNode::new(body, Span::new(ByteRange::new(start, end), source))
+    }
+
+    #[test]
+    fn round_trip_document() {
+        let source = SourceId::default();
+
+        // {{ x + 1 }}
+        let name = Name { data: Identifier::intern("x") };
+        let lhs = node(Expr::Var(VarExpr { var: name }), 3, 3, source);
+        let rhs = node(Expr::Lit(LitExpr { lit: Lit::Int(1) }), 7, 7, source);
+        let op = node(BinOp::Add, 5, 5, source);
+        let value = node(Expr::Bin(BinExpr { lhs, rhs, op }), 3, 7, source);
+        let var = node(Statement::Var(Var { value }), 0, 10, source);
+        let children = AstNodes::new(thin_vec![var], Span::new(ByteRange::new(0, 10), source));
+        let document = node(Document { children }, 0, 10, source);
+
+        let value = serde_json::to_value(&document).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "kind": "Document",
+                "span": [0, 10],
+                "children": [{
+                    "kind": "Var",
+                    "span": [0, 10],
+                    "value": {
+                        "kind": "BinExpr",
+                        "span": [3, 7],
+                        "lhs": { "kind": "VarExpr", "span": [3, 3], "var": { "data": "x" } },
+                        "rhs": { "kind": "LitExpr", "span": [7, 7], "lit": { "Int": 1 } },
+                        "op": { "kind": "BinOp", "span": [5, 5], "value": "Add" },
+                    }
+                }]
+            })
+        );
+
+        let other = SourceId::default();
+        let parsed: AstNode<Document> =
+            super::with_source(other, || serde_json::from_value(value.clone()).unwrap());
+
+        assert_eq!(parsed.span().id, other);
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), value);
+    }
+
+    #[test]
+    fn reject_unknown_kind() {
+        let value = json!({ "kind": "Nope", "span": [0, 0] });
+        let error = serde_json::from_value::<AstNode<Expr>>(value).unwrap_err();
+        assert_eq!(error.to_string(), "expected a node of kind `Expr`, found `Nope`");
+    }
+}
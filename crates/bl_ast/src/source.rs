@@ -0,0 +1,85 @@
+//! The [SourceMap] stores the path and the contents of every source that has
+//! been loaded, so that a [Span] can be resolved back into a file, and a line
+//! and column within that file.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+use crate::location::{LineRanges, RowCol, SourceId, Span};
+
+/// The global map of all of the sources that have been loaded.
+static SOURCE_MAP: Lazy<RwLock<HashMap<SourceId, Arc<SourceFile>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// A source that has been loaded into the [SourceMap].
+#[derive(Debug)]
+pub struct SourceFile {
+    /// The path of the source, sources that are read from `stdin` use the
+    /// path that is given to them, or `-`.
+    path: PathBuf,
+
+    /// The contents of the source.
+    contents: String,
+
+    /// The line ranges of the source.
+    lines: LineRanges,
+}
+
+impl SourceFile {
+    /// Get the path of the source.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the contents of the source.
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    /// Get the [LineRanges] of the source.
+    pub fn lines(&self) -> &LineRanges {
+        &self.lines
+    }
+
+    /// Convert a byte offset within the source into a [RowCol].
+    pub fn row_col(&self, offset: usize) -> RowCol {
+        self.lines.row_col(offset)
+    }
+}
+
+/// Utilities for working with the [`SOURCE_MAP`].
+pub struct SourceMap;
+
+impl SourceMap {
+    /// Add a new source to the map, returning the [SourceId] that refers to it.
+    pub fn add(path: impl Into<PathBuf>, contents: impl Into<String>) -> SourceId {
+        let contents = contents.into();
+        let file =
+            SourceFile { path: path.into(), lines: LineRanges::new_from_str(&contents), contents };
+
+        let id = SourceId::default();
+        SOURCE_MAP.write().insert(id, Arc::new(file));
+        id
+    }
+
+    /// Get the [SourceFile] that is associated with the given [SourceId].
+    pub fn get(id: SourceId) -> Option<Arc<SourceFile>> {
+        SOURCE_MAP.read().get(&id).cloned()
+    }
+
+    /// Get the path of the source that is associated with the given [SourceId].
+    pub fn path_of(id: SourceId) -> Option<PathBuf> {
+        SOURCE_MAP.read().get(&id).map(|file| file.path.clone())
+    }
+
+    /// Get the position of the start of the given [Span].
+    pub fn row_col_of(span: Span) -> Option<RowCol> {
+        SOURCE_MAP.read().get(&span.id).map(|file| file.row_col(span.range.start()))
+    }
+}
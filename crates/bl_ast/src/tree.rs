@@ -0,0 +1,377 @@
+//! Defines a [AstTreeGenerator] which converts the AST into a [TreeNode] that
+//! can then be printed as a tree or as an S-expression. The generator is
+//! implemented as an [AstVisitor] so that any new nodes that are added to the
+//! tree must also be handled here.
+
+use std::convert::Infallible;
+
+use bl_utils::tree_writing::TreeNode;
+
+use crate::{ast::*, visitor::walk};
+
+/// A visitor that converts the AST into a [TreeNode].
+pub struct AstTreeGenerator;
+
+impl AstTreeGenerator {
+    /// Generate a [TreeNode] for the given [Document].
+    pub fn generate(document: AstNodeRef<Document>) -> TreeNode {
+        AstTreeGenerator.visit_document(document).unwrap()
+    }
+}
+
+/// Attach the span of the given node onto the [TreeNode].
+fn spanned<T>(node: AstNodeRef<T>, tree: TreeNode) -> TreeNode {
+    let range = node.span().range;
+    tree.with_span(range.start(), range.end())
+}
+
+/// Create a branch with a single child, this is used to label the fields of a
+/// node.
+fn field(label: &'static str, child: TreeNode) -> TreeNode {
+    TreeNode::branch(label, vec![child])
+}
+
+/// Create a branch for a collection of children, if the collection is empty,
+/// then no branch is created.
+fn fields(label: &'static str, children: Vec<TreeNode>) -> Option<TreeNode> {
+    (!children.is_empty()).then(|| TreeNode::branch(label, children))
+}
+
+impl AstVisitor for AstTreeGenerator {
+    type Error = Infallible;
+
+    type BinOpRet = TreeNode;
+    fn visit_bin_op(&self, node: AstNodeRef<BinOp>) -> Result<Self::BinOpRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf(format!("BinOp `{}`", node.body()))))
+    }
+
+    type UnaryOpRet = TreeNode;
+    fn visit_unary_op(&self, node: AstNodeRef<UnaryOp>) -> Result<Self::UnaryOpRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf(format!("UnaryOp `{}`", node.body()))))
+    }
+
+    type LitRet = TreeNode;
+    fn visit_lit(&self, node: AstNodeRef<Lit>) -> Result<Self::LitRet, Self::Error> {
+        let label = match node.body() {
+            Lit::Bool(value) => format!("Lit {value}"),
+            Lit::Float(value) => format!("Lit {value:?}"),
+            Lit::Int(value) => format!("Lit {value}"),
+            Lit::Str(value) => format!("Lit {value:?}"),
+        };
+
+        Ok(spanned(node, TreeNode::leaf(label)))
+    }
+
+    type LitExprRet = TreeNode;
+    fn visit_lit_expr(&self, node: AstNodeRef<LitExpr>) -> Result<Self::LitExprRet, Self::Error> {
+        let walk::LitExpr { lit } = walk::walk_lit_expr(self, node)?;
+        Ok(lit)
+    }
+
+    type ArrayExprRet = TreeNode;
+    fn visit_array_expr(
+        &self,
+        node: AstNodeRef<ArrayExpr>,
+    ) -> Result<Self::ArrayExprRet, Self::Error> {
+        let walk::ArrayExpr { children } = walk::walk_array_expr(self, node)?;
+        Ok(spanned(node, TreeNode::branch("ArrayExpr", children)))
+    }
+
+    type DictEntryRet = TreeNode;
+    fn visit_dict_entry(
+        &self,
+        node: AstNodeRef<DictEntry>,
+    ) -> Result<Self::DictEntryRet, Self::Error> {
+        let walk::DictEntry { key, value } = walk::walk_dict_entry(self, node)?;
+        Ok(spanned(
+            node,
+            TreeNode::branch("DictEntry", vec![field("key", key), field("value", value)]),
+        ))
+    }
+
+    type DictExprRet = TreeNode;
+    fn visit_dict_expr(
+        &self,
+        node: AstNodeRef<DictExpr>,
+    ) -> Result<Self::DictExprRet, Self::Error> {
+        let walk::DictExpr { entries } = walk::walk_dict_expr(self, node)?;
+        Ok(spanned(node, TreeNode::branch("DictExpr", entries)))
+    }
+
+    type BinExprRet = TreeNode;
+    fn visit_bin_expr(&self, node: AstNodeRef<BinExpr>) -> Result<Self::BinExprRet, Self::Error> {
+        let walk::BinExpr { lhs, rhs, op } = walk::walk_bin_expr(self, node)?;
+        Ok(spanned(
+            node,
+            TreeNode::branch("BinExpr", vec![op, field("lhs", lhs), field("rhs", rhs)]),
+        ))
+    }
+
+    type NameRet = TreeNode;
+    fn visit_name(&self, node: AstNodeRef<Name>) -> Result<Self::NameRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf(format!("Name {:?}", node.data))))
+    }
+
+    type PathRet = TreeNode;
+    fn visit_path(&self, node: AstNodeRef<Path>) -> Result<Self::PathRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf(format!("Path {:?}", node.data))))
+    }
+
+    type VarExprRet = TreeNode;
+    fn visit_var_expr(&self, node: AstNodeRef<VarExpr>) -> Result<Self::VarExprRet, Self::Error> {
+        let walk::VarExpr { var } = walk::walk_var_expr(self, node)?;
+        Ok(spanned(node, TreeNode::branch("VarExpr", vec![var])))
+    }
+
+    type UnaryExprRet = TreeNode;
+    fn visit_unary_expr(
+        &self,
+        node: AstNodeRef<UnaryExpr>,
+    ) -> Result<Self::UnaryExprRet, Self::Error> {
+        let walk::UnaryExpr { op, expr } = walk::walk_unary_expr(self, node)?;
+        Ok(spanned(node, TreeNode::branch("UnaryExpr", vec![op, field("expr", expr)])))
+    }
+
+    type AccessExprRet = TreeNode;
+    fn visit_access_expr(
+        &self,
+        node: AstNodeRef<AccessExpr>,
+    ) -> Result<Self::AccessExprRet, Self::Error> {
+        let walk::AccessExpr { subject, property } = walk::walk_access_expr(self, node)?;
+        Ok(spanned(
+            node,
+            TreeNode::branch(
+                "AccessExpr",
+                vec![field("subject", subject), field("property", property)],
+            ),
+        ))
+    }
+
+    type IndexExprRet = TreeNode;
+    fn visit_index_expr(
+        &self,
+        node: AstNodeRef<IndexExpr>,
+    ) -> Result<Self::IndexExprRet, Self::Error> {
+        let walk::IndexExpr { subject, index } = walk::walk_index_expr(self, node)?;
+        Ok(spanned(
+            node,
+            TreeNode::branch("IndexExpr", vec![field("subject", subject), field("index", index)]),
+        ))
+    }
+
+    type CondExprRet = TreeNode;
+    fn visit_cond_expr(
+        &self,
+        node: AstNodeRef<CondExpr>,
+    ) -> Result<Self::CondExprRet, Self::Error> {
+        let walk::CondExpr { value, condition, otherwise } = walk::walk_cond_expr(self, node)?;
+        let children = [Some(field("value", value)), Some(field("condition", condition))]
+            .into_iter()
+            .chain([otherwise.map(|otherwise| field("otherwise", otherwise))])
+            .flatten()
+            .collect();
+
+        Ok(spanned(node, TreeNode::branch("CondExpr", children)))
+    }
+
+    type ArgRet = TreeNode;
+    fn visit_arg(&self, node: AstNodeRef<Arg>) -> Result<Self::ArgRet, Self::Error> {
+        let walk::Arg { name, value } = walk::walk_arg(self, node)?;
+        let children = [name.map(|name| field("name", name)), value.map(|v| field("value", v))]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(spanned(node, TreeNode::branch("Arg", children)))
+    }
+
+    type CallExprRet = TreeNode;
+    fn visit_call_expr(
+        &self,
+        node: AstNodeRef<CallExpr>,
+    ) -> Result<Self::CallExprRet, Self::Error> {
+        let walk::CallExpr { subject, args } = walk::walk_call_expr(self, node)?;
+        let children = [Some(field("subject", subject)), fields("args", args)];
+
+        Ok(spanned(node, TreeNode::branch("CallExpr", children.into_iter().flatten().collect())))
+    }
+
+    type MacroCallExprRet = TreeNode;
+    fn visit_macro_call_expr(
+        &self,
+        node: AstNodeRef<MacroCallExpr>,
+    ) -> Result<Self::MacroCallExprRet, Self::Error> {
+        let walk::MacroCallExpr { name, namespace, args } = walk::walk_macro_call_expr(self, node)?;
+        let children =
+            [Some(field("namespace", namespace)), Some(field("name", name)), fields("args", args)];
+
+        Ok(spanned(
+            node,
+            TreeNode::branch("MacroCallExpr", children.into_iter().flatten().collect()),
+        ))
+    }
+
+    type FilteredExprRet = TreeNode;
+    fn visit_filtered_expr(
+        &self,
+        node: AstNodeRef<FilteredExpr>,
+    ) -> Result<Self::FilteredExprRet, Self::Error> {
+        let walk::FilteredExpr { subject, filters } = walk::walk_filtered_expr(self, node)?;
+        let children = [Some(field("subject", subject)), fields("filters", filters)];
+
+        Ok(spanned(
+            node,
+            TreeNode::branch("FilteredExpr", children.into_iter().flatten().collect()),
+        ))
+    }
+
+    type ExprRet = TreeNode;
+    fn visit_expr(&self, node: AstNodeRef<Expr>) -> Result<Self::ExprRet, Self::Error> {
+        walk::walk_expr_same_children(self, node)
+    }
+
+    type BlockRet = TreeNode;
+    fn visit_block(&self, node: AstNodeRef<Block>) -> Result<Self::BlockRet, Self::Error> {
+        let walk::Block { label, children } = walk::walk_block(self, node)?;
+        let children = [label.map(|label| field("label", label)), fields("children", children)];
+
+        Ok(spanned(node, TreeNode::branch("Block", children.into_iter().flatten().collect())))
+    }
+
+    type MacroDefRet = TreeNode;
+    fn visit_macro_def(
+        &self,
+        node: AstNodeRef<MacroDef>,
+    ) -> Result<Self::MacroDefRet, Self::Error> {
+        let walk::MacroDef { name, args, inner } = walk::walk_macro_def(self, node)?;
+        let children = [Some(field("name", name)), fields("args", args), fields("inner", inner)];
+
+        Ok(spanned(node, TreeNode::branch("MacroDef", children.into_iter().flatten().collect())))
+    }
+
+    type IncludeRet = TreeNode;
+    fn visit_include(&self, node: AstNodeRef<Include>) -> Result<Self::IncludeRet, Self::Error> {
+        let walk::Include { path } = walk::walk_include(self, node)?;
+        Ok(spanned(node, TreeNode::branch("Include", vec![path])))
+    }
+
+    type ExtendsRet = TreeNode;
+    fn visit_extends(&self, node: AstNodeRef<Extends>) -> Result<Self::ExtendsRet, Self::Error> {
+        let walk::Extends { path } = walk::walk_extends(self, node)?;
+        Ok(spanned(node, TreeNode::branch("Extends", vec![path])))
+    }
+
+    type ImportRet = TreeNode;
+    fn visit_import(&self, node: AstNodeRef<Import>) -> Result<Self::ImportRet, Self::Error> {
+        let walk::Import { path, name } = walk::walk_import(self, node)?;
+        let children = [Some(path), name.map(|name| field("name", name))];
+
+        Ok(spanned(node, TreeNode::branch("Import", children.into_iter().flatten().collect())))
+    }
+
+    type SetRet = TreeNode;
+    fn visit_set(&self, node: AstNodeRef<Set>) -> Result<Self::SetRet, Self::Error> {
+        let walk::Set { name, value } = walk::walk_set(self, node)?;
+        Ok(spanned(node, TreeNode::branch("Set", vec![field("name", name), field("value", value)])))
+    }
+
+    type SuperRet = TreeNode;
+    fn visit_super(&self, node: AstNodeRef<Super>) -> Result<Self::SuperRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf("Super")))
+    }
+
+    type TagRet = TreeNode;
+    fn visit_tag(&self, node: AstNodeRef<Tag>) -> Result<Self::TagRet, Self::Error> {
+        let walk::Tag { name, args } = walk::walk_tag(self, node)?;
+        let children = [Some(field("name", name)), fields("args", args)];
+
+        Ok(spanned(node, TreeNode::branch("Tag", children.into_iter().flatten().collect())))
+    }
+
+    type TextRet = TreeNode;
+    fn visit_text(&self, node: AstNodeRef<Text>) -> Result<Self::TextRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf("Text")))
+    }
+
+    type VarRet = TreeNode;
+    fn visit_var(&self, node: AstNodeRef<Var>) -> Result<Self::VarRet, Self::Error> {
+        let walk::Var { value } = walk::walk_var(self, node)?;
+        Ok(spanned(node, TreeNode::branch("Var", vec![value])))
+    }
+
+    type BodyRet = TreeNode;
+    fn visit_body(&self, node: AstNodeRef<Body>) -> Result<Self::BodyRet, Self::Error> {
+        let walk::Body { contents } = walk::walk_body(self, node)?;
+        Ok(spanned(node, TreeNode::branch("Body", contents)))
+    }
+
+    type IfClauseRet = TreeNode;
+    fn visit_if_clause(
+        &self,
+        node: AstNodeRef<IfClause>,
+    ) -> Result<Self::IfClauseRet, Self::Error> {
+        let walk::IfClause { condition, if_body } = walk::walk_if_clause(self, node)?;
+        Ok(spanned(
+            node,
+            TreeNode::branch("IfClause", vec![field("condition", condition), if_body]),
+        ))
+    }
+
+    type IfRet = TreeNode;
+    fn visit_if(&self, node: AstNodeRef<If>) -> Result<Self::IfRet, Self::Error> {
+        let walk::If { clauses, otherwise } = walk::walk_if(self, node)?;
+        let children = [fields("clauses", clauses), otherwise.map(|o| field("otherwise", o))];
+
+        Ok(spanned(node, TreeNode::branch("If", children.into_iter().flatten().collect())))
+    }
+
+    type ForRet = TreeNode;
+    fn visit_for(&self, node: AstNodeRef<For>) -> Result<Self::ForRet, Self::Error> {
+        let walk::For { key, value, iterable, condition, loop_body, loop_empty } =
+            walk::walk_for(self, node)?;
+        let children = [
+            key.map(|key| field("key", key)),
+            Some(field("value", value)),
+            Some(field("iterable", iterable)),
+            condition.map(|condition| field("condition", condition)),
+            Some(field("loop_body", loop_body)),
+            loop_empty.map(|loop_empty| field("loop_empty", loop_empty)),
+        ];
+
+        Ok(spanned(node, TreeNode::branch("For", children.into_iter().flatten().collect())))
+    }
+
+    type ContinueRet = TreeNode;
+    fn visit_continue(&self, node: AstNodeRef<Continue>) -> Result<Self::ContinueRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf("Continue")))
+    }
+
+    type BreakRet = TreeNode;
+    fn visit_break(&self, node: AstNodeRef<Break>) -> Result<Self::BreakRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf("Break")))
+    }
+
+    type RawRet = TreeNode;
+    fn visit_raw(&self, node: AstNodeRef<Raw>) -> Result<Self::RawRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf("Raw")))
+    }
+
+    type CommentRet = TreeNode;
+    fn visit_comment(&self, node: AstNodeRef<Comment>) -> Result<Self::CommentRet, Self::Error> {
+        Ok(spanned(node, TreeNode::leaf("Comment")))
+    }
+
+    type StatementRet = TreeNode;
+    fn visit_statement(
+        &self,
+        node: AstNodeRef<Statement>,
+    ) -> Result<Self::StatementRet, Self::Error> {
+        walk::walk_statement_same_children(self, node)
+    }
+
+    type DocumentRet = TreeNode;
+    fn visit_document(&self, node: AstNodeRef<Document>) -> Result<Self::DocumentRet, Self::Error> {
+        let walk::Document { children } = walk::walk_document(self, node)?;
+        Ok(spanned(node, TreeNode::branch("Document", children)))
+    }
+}
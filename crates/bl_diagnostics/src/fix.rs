@@ -0,0 +1,150 @@
+//! Definitions of fixes that can be attached to a [`crate::Diagnostic`].
+
+use std::fmt;
+
+use bl_ast::ByteRange;
+
+/// Whether a [Fix] can be applied without changing the meaning of the
+/// template.
+///
+/// ##Note: the variants are ordered from the least to the most safe, so that
+/// a fix can be applied when its applicability is at least the required one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Applicability {
+    /// The fix is only shown to the user as a suggestion, and is never
+    /// applied, i.e. when the correct fix can't be determined.
+    DisplayOnly,
+
+    /// The fix is unsafe and might change the meaning of the template, these
+    /// fixes are only applied when explicitly requested.
+    Unsafe,
+
+    /// The fix is safe to apply, and does not change the meaning of the
+    /// template. Any fix that could change the rendered output of a template
+    /// must not be safe, since safe fixes are applied by `--fix` without
+    /// any further opt-in.
+    Safe,
+}
+
+impl fmt::Display for Applicability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Applicability::DisplayOnly => write!(f, "display-only"),
+            Applicability::Unsafe => write!(f, "unsafe"),
+            Applicability::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+/// A single textual edit to a source.
+///
+/// ##Note: unlike [ByteRange], the `end` of an [Edit] is exclusive so that
+/// insertions can be represented as empty edits.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Edit {
+    /// The offset at which the edit begins.
+    start: usize,
+
+    /// The (exclusive) offset at which the edit ends.
+    end: usize,
+
+    /// The content that replaces the range of the edit.
+    content: String,
+}
+
+impl Edit {
+    /// Create an [Edit] that inserts `content` at the given offset.
+    pub fn insert(offset: usize, content: impl Into<String>) -> Self {
+        Self { start: offset, end: offset, content: content.into() }
+    }
+
+    /// Create an [Edit] that deletes the given [ByteRange].
+    pub fn delete(range: ByteRange) -> Self {
+        Self { start: range.start(), end: range.end() + 1, content: String::new() }
+    }
+
+    /// Create an [Edit] that replaces the given [ByteRange] with `content`.
+    pub fn replace(range: ByteRange, content: impl Into<String>) -> Self {
+        Self { start: range.start(), end: range.end() + 1, content: content.into() }
+    }
+
+    /// Get the offset at which the edit begins.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Get the (exclusive) offset at which the edit ends.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Get the content that the edit inserts.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Check whether the edit is an insertion.
+    pub fn is_insertion(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// A fix for a [`crate::Diagnostic`], which is comprised of a set of edits
+/// that must all be applied together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// The edits of the fix, ordered by their position.
+    edits: Vec<Edit>,
+
+    /// Whether the fix is safe to apply.
+    applicability: Applicability,
+}
+
+impl Fix {
+    /// Create a new [Fix] from the given edits.
+    pub fn new(edits: impl IntoIterator<Item = Edit>, applicability: Applicability) -> Self {
+        let mut edits: Vec<_> = edits.into_iter().collect();
+        edits.sort();
+        Self { edits, applicability }
+    }
+
+    /// Create a safe [Fix] from the given edits.
+    pub fn safe_edits(edits: impl IntoIterator<Item = Edit>) -> Self {
+        Self::new(edits, Applicability::Safe)
+    }
+
+    /// Create an unsafe [Fix] from the given edits.
+    pub fn unsafe_edits(edits: impl IntoIterator<Item = Edit>) -> Self {
+        Self::new(edits, Applicability::Unsafe)
+    }
+
+    /// Create a [Fix] from the given edits that is never applied.
+    pub fn display_only_edits(edits: impl IntoIterator<Item = Edit>) -> Self {
+        Self::new(edits, Applicability::DisplayOnly)
+    }
+
+    /// Change the applicability of the fix.
+    pub fn with_applicability(mut self, applicability: Applicability) -> Self {
+        self.applicability = applicability;
+        self
+    }
+
+    /// Check whether the fix can be applied when fixes must be at least as
+    /// safe as the `required` applicability. Display-only fixes are never
+    /// applied.
+    pub fn applies(&self, required: Applicability) -> bool {
+        self.applicability != Applicability::DisplayOnly && self.applicability >= required
+    }
+
+    /// Get the edits of the fix.
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    /// Get the applicability of the fix.
+    pub fn applicability(&self) -> Applicability {
+        self.applicability
+    }
+}
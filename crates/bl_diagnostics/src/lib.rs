@@ -1,14 +1,291 @@
 //! Definitions for all of the diagnostics that are available to the user.
 
+mod fix;
+
+use std::{fmt, path::PathBuf};
+
+use bl_ast::{SourceMap, Span};
+pub use fix::{Applicability, Edit, Fix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum DiagnosticKind {
     Error,
     Warning,
     Note,
 }
 
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::Error => write!(f, "error"),
+            DiagnosticKind::Warning => write!(f, "warning"),
+            DiagnosticKind::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A secondary location that is attached to a [Diagnostic], along with a
+/// message describing its relevance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label {
+    /// The location of the label.
+    pub span: Span,
+
+    /// The message of the label.
+    pub message: String,
+}
+
+impl Label {
+    /// Create a new [Label].
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Self { span, message: message.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Diagnostic {
     pub kind: DiagnosticKind,
+
+    /// The code of the rule that produced this diagnostic, i.e. `BL001`.
+    pub code: Option<&'static str>,
+
+    /// The message of the diagnostic.
+    pub message: String,
+
+    /// The primary location in the source that the diagnostic refers to.
+    pub span: Span,
+
+    /// Any secondary locations that are related to the diagnostic.
+    pub labels: Vec<Label>,
+
+    /// Additional notes that explain the diagnostic.
+    pub notes: Vec<String>,
+
+    /// A suggestion of how to resolve the diagnostic.
+    pub help: Option<String>,
+
+    /// A fix that resolves the diagnostic.
+    pub fix: Option<Fix>,
+
+    /// Related diagnostics that are reported as part of this diagnostic.
+    pub children: Vec<Diagnostic>,
 }
 
-#[derive(Default)]
+impl Diagnostic {
+    /// Create a new [Diagnostic] without a rule code.
+    pub fn new(kind: DiagnosticKind, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            kind,
+            code: None,
+            message: message.into(),
+            span,
+            labels: vec![],
+            notes: vec![],
+            help: None,
+            fix: None,
+            children: vec![],
+        }
+    }
+
+    /// Attach a rule code to the [Diagnostic].
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Attach a secondary [Label] to the [Diagnostic].
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label::new(span, message));
+        self
+    }
+
+    /// Attach a note to the [Diagnostic].
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Attach help text to the [Diagnostic].
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Attach a [Fix] to the [Diagnostic].
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
+    /// Attach a related child [Diagnostic].
+    pub fn with_child(mut self, child: Diagnostic) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Check whether the [Diagnostic] has a fix.
+    pub fn is_fixable(&self) -> bool {
+        self.fix.is_some()
+    }
+
+    /// Check whether the [Diagnostic] has a fix that can be applied when fixes
+    /// must be at least as safe as the `required` applicability.
+    pub fn is_fixable_with(&self, required: Applicability) -> bool {
+        self.fix.as_ref().is_some_and(|fix| fix.applies(required))
+    }
+
+    /// Get the path of the source that the [Diagnostic] refers to, if the
+    /// source has been registered in the [SourceMap].
+    pub fn path(&self) -> Option<PathBuf> {
+        SourceMap::path_of(self.span.id)
+    }
+}
+
+/// A collection of [Diagnostic]s.
+#[derive(Debug, Default, Clone)]
 pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostics {
+    /// Create a new empty collection of [Diagnostic]s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a [Diagnostic] to the collection.
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.0.push(diagnostic);
+    }
+
+    /// Get the number of diagnostics in the collection.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check whether the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over all of the diagnostics.
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter()
+    }
+
+    /// Count the number of diagnostics of the given kind.
+    pub fn count(&self, kind: DiagnosticKind) -> usize {
+        self.0.iter().filter(|diagnostic| diagnostic.kind == kind).count()
+    }
+
+    /// Count the number of diagnostics that have a fix.
+    pub fn fixable_count(&self) -> usize {
+        self.0.iter().filter(|diagnostic| diagnostic.is_fixable()).count()
+    }
+
+    /// Count the number of diagnostics that have a fix that can be applied
+    /// when fixes must be at least as safe as the `required` applicability.
+    pub fn fixable_count_with(&self, required: Applicability) -> usize {
+        self.0.iter().filter(|diagnostic| diagnostic.is_fixable_with(required)).count()
+    }
+
+    /// Sort the diagnostics by file, position and then by rule code.
+    ///
+    /// ##Note: diagnostics from sources that are not within the [SourceMap]
+    /// are ordered after all other diagnostics.
+    pub fn sort(&mut self) {
+        self.0.sort_by_cached_key(|diagnostic| {
+            (
+                diagnostic.path().is_none(),
+                diagnostic.path(),
+                diagnostic.span.range.start(),
+                diagnostic.span.range.end(),
+                diagnostic.code,
+            )
+        });
+    }
+
+    /// Sort the diagnostics, and then remove any duplicates which refer to the
+    /// same location with the same code and message.
+    pub fn dedup(&mut self) {
+        self.sort();
+        self.0.dedup_by(|a, b| a.span == b.span && a.code == b.code && a.message == b.message);
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl FromIterator<Diagnostic> for Diagnostics {
+    fn from_iter<T: IntoIterator<Item = Diagnostic>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceMap, Span};
+
+    use super::{Diagnostic, DiagnosticKind, Diagnostics};
+
+    fn diagnostic(span: Span, code: &'static str) -> Diagnostic {
+        Diagnostic::new(DiagnosticKind::Error, "message", span).with_code(code)
+    }
+
+    #[test]
+    fn sort_and_dedup() {
+        let b = SourceMap::add("b.html", "{{ x }}\n{{ y }}");
+        let a = SourceMap::add("a.html", "{{ x }}");
+        let span = |start, end, id| Span::new(ByteRange::new(start, end), id);
+
+        let mut diagnostics: Diagnostics = [
+            diagnostic(span(8, 14, b), "BL001"),
+            diagnostic(span(0, 6, b), "BL002"),
+            diagnostic(span(0, 6, b), "BL001"),
+            diagnostic(span(0, 6, a), "BL001"),
+            diagnostic(span(8, 14, b), "BL001"),
+        ]
+        .into_iter()
+        .collect();
+
+        diagnostics.dedup();
+
+        let order = diagnostics
+            .iter()
+            .map(|d| {
+                (d.path().unwrap().display().to_string(), d.span.range.start(), d.code.unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            order,
+            [
+                ("a.html".to_string(), 0, "BL001"),
+                ("b.html".to_string(), 0, "BL001"),
+                ("b.html".to_string(), 0, "BL002"),
+                ("b.html".to_string(), 8, "BL001"),
+            ]
+        );
+    }
+}
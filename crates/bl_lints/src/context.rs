@@ -0,0 +1,68 @@
+//! The context that is given to rules whilst they check a document.
+
+use bl_ast::Span;
+use bl_diagnostics::Diagnostic;
+
+use crate::{rule::Rule, settings::Dialect};
+
+/// The [LintContext] is given to a [Rule] whilst it is checking a document,
+/// it provides access to the source of the document and collects all of the
+/// diagnostics that the rule reports.
+pub struct LintContext<'s> {
+    /// The source of the document that is being checked.
+    source: &'s str,
+
+    /// The rule that is currently being run.
+    rule: &'static dyn Rule,
+
+    /// The dialect that the document is written in.
+    dialect: Dialect,
+
+    /// The diagnostics that have been reported.
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'s> LintContext<'s> {
+    /// Create a new [LintContext] for running the given rule on a document
+    /// of the given [Dialect].
+    pub fn new(source: &'s str, rule: &'static dyn Rule, dialect: Dialect) -> Self {
+        Self { source, rule, dialect, diagnostics: vec![] }
+    }
+
+    /// Get the source of the document that is being checked.
+    pub fn source(&self) -> &'s str {
+        self.source
+    }
+
+    /// Get the [Dialect] of the document that is being checked.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Get the text of the source that the given [Span] covers.
+    pub fn text(&self, span: Span) -> &'s str {
+        &self.source[span.range.start()..=span.range.end()]
+    }
+
+    /// Create a [Diagnostic] for a violation of the current rule at the given
+    /// [Span], which can then be annotated and reported with [Self::push].
+    pub fn diagnostic(&self, message: impl Into<String>, span: Span) -> Diagnostic {
+        Diagnostic::new(self.rule.default_severity(), message, span).with_code(self.rule.code())
+    }
+
+    /// Report a [Diagnostic] that was created with [Self::diagnostic].
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Report a violation of the current rule at the given [Span].
+    pub fn report(&mut self, message: impl Into<String>, span: Span) {
+        let diagnostic = self.diagnostic(message, span);
+        self.push(diagnostic);
+    }
+
+    /// Consume the [LintContext], returning all of the reported diagnostics.
+    pub fn finish(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
@@ -0,0 +1,160 @@
+//! The fixer, which applies the [Fix]es of a set of diagnostics to a source.
+
+use std::collections::BTreeMap;
+
+use bl_diagnostics::{Applicability, Diagnostic, Edit, Fix};
+
+/// The number of fixes that were applied for each rule, keyed by the code of
+/// the rule.
+pub type FixTable = BTreeMap<&'static str, usize>;
+
+/// The result of applying fixes to a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixResult {
+    /// The fixed source.
+    pub source: String,
+
+    /// The number of fixes that were applied for each rule.
+    pub fixes: FixTable,
+}
+
+/// Apply the fixes of the given diagnostics that are at least as safe as the
+/// `required` applicability to the source. Fixes are
+/// applied in the order of their position, and any fix that overlaps with a
+/// fix that has already been applied is skipped, re-checking the fixed source
+/// will report it again so that it can be applied in the next iteration.
+///
+/// Returns `None` if there were no fixes to apply.
+pub fn apply_fixes<'d>(
+    diagnostics: impl IntoIterator<Item = &'d Diagnostic>,
+    source: &str,
+    required: Applicability,
+) -> Option<FixResult> {
+    let mut fixes: Vec<_> = diagnostics
+        .into_iter()
+        .filter_map(|diagnostic| Some((diagnostic.code?, diagnostic.fix.as_ref()?)))
+        .filter(|(_, fix)| fix.applies(required))
+        .filter(|(_, fix)| !fix.edits().is_empty())
+        .collect();
+
+    if fixes.is_empty() {
+        return None;
+    }
+
+    // Order the fixes by the position of their first edit, and then by their
+    // rule so that the result doesn't depend on the order of the diagnostics.
+    fixes.sort_by(|(lhs_code, lhs), (rhs_code, rhs)| {
+        (&lhs.edits()[0], lhs_code).cmp(&(&rhs.edits()[0], rhs_code))
+    });
+
+    let mut output = String::with_capacity(source.len());
+    let mut last_pos = 0;
+    let mut applied: Vec<&Fix> = vec![];
+    let mut table = FixTable::new();
+
+    for (code, fix) in fixes {
+        // The same fix might be reported by several diagnostics, i.e. when
+        // one fix resolves multiple violations.
+        if applied.contains(&fix) {
+            continue;
+        }
+
+        if fix.edits()[0].start() < last_pos {
+            log::debug!("skipping fix for {code} that overlaps with a previous fix");
+            continue;
+        }
+
+        if !is_valid(fix.edits(), source) {
+            log::debug!("skipping invalid fix for {code}: {:?}", fix.edits());
+            continue;
+        }
+
+        last_pos = write_edits(&mut output, source, fix.edits(), last_pos);
+        applied.push(fix);
+        *table.entry(code).or_default() += 1;
+    }
+
+    output.push_str(&source[last_pos..]);
+    Some(FixResult { source: output, fixes: table })
+}
+
+/// Apply all of the edits of a single [Fix] to the source, regardless of its
+/// applicability. Returns `None` if the edits are invalid.
+pub fn apply_fix(fix: &Fix, source: &str) -> Option<String> {
+    if !is_valid(fix.edits(), source) {
+        return None;
+    }
+
+    let mut output = String::with_capacity(source.len());
+    let last_pos = write_edits(&mut output, source, fix.edits(), 0);
+    output.push_str(&source[last_pos..]);
+    Some(output)
+}
+
+/// Write the source from `last_pos` up until each of the edits, followed by the
+/// content of the edit. Returns the offset after the last edit.
+fn write_edits(output: &mut String, source: &str, edits: &[Edit], mut last_pos: usize) -> usize {
+    for edit in edits {
+        output.push_str(&source[last_pos..edit.start()]);
+        output.push_str(edit.content());
+        last_pos = edit.end();
+    }
+
+    last_pos
+}
+
+/// Check whether the edits of a fix are all within the source and don't
+/// overlap with one another.
+fn is_valid(edits: &[Edit], source: &str) -> bool {
+    let within_source = edits.iter().all(|edit| {
+        edit.start() <= edit.end()
+            && source.is_char_boundary(edit.start())
+            && source.is_char_boundary(edit.end())
+    });
+
+    within_source && edits.windows(2).all(|pair| pair[0].end() <= pair[1].start())
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceId, Span};
+    use bl_diagnostics::{Applicability, Diagnostic, DiagnosticKind, Edit, Fix};
+
+    use super::apply_fixes;
+
+    fn diagnostic(code: &'static str, fix: Fix) -> Diagnostic {
+        let span = Span::new(ByteRange::default(), SourceId::default());
+        Diagnostic::new(DiagnosticKind::Error, "message", span).with_code(code).with_fix(fix)
+    }
+
+    #[test]
+    fn apply_non_overlapping_fixes() {
+        let source = "{{ a }}{% break %}{{ b }}";
+        let diagnostics = [
+            diagnostic("BL002", Fix::safe_edits([Edit::delete(ByteRange::new(7, 17))])),
+            diagnostic("BL001", Fix::safe_edits([Edit::replace(ByteRange::new(3, 3), "x")])),
+            // Overlaps with the deletion, so it is skipped.
+            diagnostic("BL001", Fix::safe_edits([Edit::insert(10, "y")])),
+            diagnostic("BL001", Fix::safe_edits([Edit::insert(25, "\n")])),
+            // Unsafe fixes are only applied when requested.
+            diagnostic("BL002", Fix::unsafe_edits([Edit::insert(0, "z")])),
+            diagnostic("BL002", Fix::display_only_edits([Edit::insert(0, "w")])),
+            // The edits of the fix overlap with each other.
+            diagnostic(
+                "BL002",
+                Fix::safe_edits([
+                    Edit::delete(ByteRange::new(19, 21)),
+                    Edit::delete(ByteRange::new(20, 22)),
+                ]),
+            ),
+        ];
+
+        let result = apply_fixes(&diagnostics, source, Applicability::Safe).unwrap();
+        assert_eq!(result.source, "{{ x }}{{ b }}\n");
+        assert_eq!(result.fixes.into_iter().collect::<Vec<_>>(), [("BL001", 2), ("BL002", 1)]);
+
+        // Display-only fixes are never applied, even with unsafe fixes.
+        let result = apply_fixes(&diagnostics, source, Applicability::Unsafe).unwrap();
+        assert_eq!(result.source, "z{{ x }}{{ b }}\n");
+    }
+}
@@ -1,4 +1,64 @@
 //! Contains all of lint definitions and the general linting engine.
 
+pub mod context;
+pub mod fix;
+pub mod message;
 pub mod registry;
+pub mod rule;
+mod rules;
 pub mod settings;
+pub mod suppression;
+
+use bl_ast::{AstNodeRef, Document};
+use bl_diagnostics::Diagnostic;
+use context::LintContext;
+use rule::Rule;
+use settings::Dialect;
+
+/// Check the given [Document], which is written in the given [Dialect], with
+/// each of the given rules, returning all of the diagnostics that were
+/// reported and that weren't suppressed by a directive within the document.
+pub fn lint(
+    document: AstNodeRef<Document>,
+    source: &str,
+    rules: &[&'static dyn Rule],
+    dialect: Dialect,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    for rule in rules {
+        let mut context = LintContext::new(source, *rule, dialect);
+        rule.check(document, &mut context);
+        diagnostics.extend(context.finish());
+    }
+
+    suppression::suppress(diagnostics, document, source, rules)
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use bl_ast::SourceId;
+    use bl_diagnostics::Diagnostic;
+    use bl_parse::lexer::Delimiters;
+
+    use crate::{rule::Rule, settings::Dialect};
+
+    /// Parse the given source and check it with a single rule, the source is
+    /// expected to parse without any errors.
+    pub(crate) fn check(rule: &'static dyn Rule, source: &str) -> Vec<Diagnostic> {
+        check_dialect(rule, source, Dialect::default())
+    }
+
+    /// Check the given source like [check], as a document of the given
+    /// [Dialect].
+    pub(crate) fn check_dialect(
+        rule: &'static dyn Rule,
+        source: &str,
+        dialect: Dialect,
+    ) -> Vec<Diagnostic> {
+        let id = SourceId::default();
+        let result = bl_parse::parse_with_delimiters(source, id, &Delimiters::default(), dialect);
+        assert!(result.errors.is_empty(), "unexpected errors: {:?}", result.errors);
+        crate::lint(result.document.ast_ref(), source, &[rule], dialect)
+    }
+}
@@ -0,0 +1,53 @@
+//! The Azure emitter, which writes each diagnostic as an Azure Pipelines
+//! [logging command] so that it is reported as an issue of the task.
+//!
+//! [logging command]: https://learn.microsoft.com/en-us/azure/devops/pipelines/scripts/logging-commands
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::{DiagnosticKind, Diagnostics};
+use bl_utils::stream_writeln;
+
+use super::{Emitter, Location};
+
+/// Writes each diagnostic as a `##vso[task.logissue]` logging command.
+#[derive(Default)]
+pub struct AzureEmitter;
+
+impl Emitter for AzureEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        for diagnostic in diagnostics {
+            let Location { path, start, .. } = Location::of(diagnostic);
+
+            // Azure only supports errors and warnings.
+            let kind = match diagnostic.kind {
+                DiagnosticKind::Error => "error",
+                DiagnosticKind::Warning | DiagnosticKind::Note => "warning",
+            };
+
+            let code = diagnostic.code.map(|code| format!("code={code};")).unwrap_or_default();
+
+            stream_writeln!(
+                writer,
+                "##vso[task.logissue type={kind};sourcepath={};linenumber={};columnnumber={};{code}]{}",
+                escape(&path),
+                start.row + 1,
+                start.column + 1,
+                escape(&diagnostic.message),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape a value of a logging command.
+fn escape(value: &str) -> String {
+    value
+        .replace('%', "%AZP25")
+        .replace(';', "%3B")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+        .replace(']', "%5D")
+}
@@ -0,0 +1,51 @@
+//! The Checkstyle emitter, which writes the diagnostics as a Checkstyle XML
+//! report that is grouped by file.
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::{DiagnosticKind, Diagnostics};
+use bl_utils::stream_writeln;
+use itertools::Itertools;
+
+use super::{xml_escape, Emitter, Location};
+
+/// Writes the diagnostics as a Checkstyle XML report.
+///
+/// ##Note: the diagnostics are expected to be sorted by file.
+#[derive(Default)]
+pub struct CheckstyleEmitter;
+
+impl Emitter for CheckstyleEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        stream_writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        stream_writeln!(writer, r#"<checkstyle version="4.3">"#);
+
+        let located = diagnostics.iter().map(|diagnostic| (Location::of(diagnostic), diagnostic));
+        for (path, group) in &located.chunk_by(|(location, _)| location.path.clone()) {
+            stream_writeln!(writer, r#"  <file name="{}">"#, xml_escape(&path));
+
+            for (Location { start, .. }, diagnostic) in group {
+                let severity = match diagnostic.kind {
+                    DiagnosticKind::Error => "error",
+                    DiagnosticKind::Warning => "warning",
+                    DiagnosticKind::Note => "info",
+                };
+
+                stream_writeln!(
+                    writer,
+                    r#"    <error line="{}" column="{}" severity="{severity}" message="{}" source="{}"/>"#,
+                    start.row + 1,
+                    start.column + 1,
+                    xml_escape(&diagnostic.message),
+                    diagnostic.code.unwrap_or("syntax-error"),
+                );
+            }
+
+            stream_writeln!(writer, "  </file>");
+        }
+
+        stream_writeln!(writer, "</checkstyle>");
+        Ok(())
+    }
+}
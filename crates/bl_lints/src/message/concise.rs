@@ -0,0 +1,119 @@
+//! The concise emitter, which writes each diagnostic on a single line, i.e.
+//! `index.html:3:1: BL002 `break` used outside of a `for` loop`.
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::{Diagnostic, Diagnostics};
+use bl_utils::{
+    highlight::{colours_enabled, highlight, Colour, Modifier},
+    stream_writeln,
+};
+
+use super::{write_summary, Emitter, Location};
+use crate::settings::UnsafeFixes;
+
+/// Writes each diagnostic on a single line, followed by a summary of how many
+/// diagnostics were found.
+pub struct ConciseEmitter {
+    /// Whether the output should be coloured.
+    colours: bool,
+
+    /// The number of diagnostics that were fixed, which is included in the
+    /// summary.
+    fixed: usize,
+
+    /// Whether unsafe fixes are applied, which determines which diagnostics
+    /// are fixable.
+    unsafe_fixes: UnsafeFixes,
+}
+
+impl Default for ConciseEmitter {
+    fn default() -> Self {
+        Self { colours: colours_enabled(), fixed: 0, unsafe_fixes: UnsafeFixes::default() }
+    }
+}
+
+impl ConciseEmitter {
+    /// Create a new [ConciseEmitter], colours are enabled based on the global
+    /// colour choice.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specify whether the output should be coloured.
+    pub fn with_colours(mut self, colours: bool) -> Self {
+        self.colours = colours;
+        self
+    }
+
+    /// Specify the number of diagnostics that were fixed.
+    pub fn with_fixed(mut self, fixed: usize) -> Self {
+        self.fixed = fixed;
+        self
+    }
+
+    /// Specify whether unsafe fixes are applied.
+    pub fn with_unsafe_fixes(mut self, unsafe_fixes: UnsafeFixes) -> Self {
+        self.unsafe_fixes = unsafe_fixes;
+        self
+    }
+}
+
+impl Emitter for ConciseEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        for diagnostic in diagnostics {
+            let location = Location::of(diagnostic);
+            let path = if self.colours {
+                highlight(Modifier::Bold, &location.path)
+            } else {
+                location.path
+            };
+
+            stream_writeln!(
+                writer,
+                "{path}:{}: {}",
+                location.start,
+                DiagnosticBody {
+                    diagnostic,
+                    unsafe_fixes: self.unsafe_fixes,
+                    colours: self.colours
+                }
+            );
+        }
+
+        write_summary(writer, diagnostics, self.fixed, self.unsafe_fixes, self.colours);
+        Ok(())
+    }
+}
+
+/// The rule code, fix marker and message of a diagnostic, i.e.
+/// `BL001 [*] block `a` is defined multiple times`. This is shared with the
+/// [super::GroupedEmitter].
+pub(crate) struct DiagnosticBody<'d> {
+    pub diagnostic: &'d Diagnostic,
+    pub unsafe_fixes: UnsafeFixes,
+    pub colours: bool,
+}
+
+impl std::fmt::Display for DiagnosticBody<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let highlight = |decoration, message: &str| {
+            if self.colours {
+                highlight(decoration, message)
+            } else {
+                message.to_string()
+            }
+        };
+
+        if let Some(code) = self.diagnostic.code {
+            write!(f, "{} ", highlight(Colour::Red | Modifier::Bold, code))?;
+        }
+
+        if self.diagnostic.is_fixable_with(self.unsafe_fixes.required_applicability()) {
+            write!(f, "{} ", highlight(Colour::Cyan | Modifier::Bold, "[*]"))?;
+        }
+
+        write!(f, "{}", self.diagnostic.message)
+    }
+}
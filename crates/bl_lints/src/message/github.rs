@@ -0,0 +1,94 @@
+//! The GitHub emitter, which writes each diagnostic as a GitHub Actions
+//! [workflow command] so that it is shown as an annotation on the file.
+//!
+//! [workflow command]: https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::{DiagnosticKind, Diagnostics};
+use bl_utils::stream_writeln;
+
+use super::{Emitter, Location};
+
+/// Writes each diagnostic as an `::error` workflow command.
+#[derive(Default)]
+pub struct GithubEmitter;
+
+impl Emitter for GithubEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        for diagnostic in diagnostics {
+            let Location { path, start, end } = Location::of(diagnostic);
+
+            let command = match diagnostic.kind {
+                DiagnosticKind::Error => "error",
+                DiagnosticKind::Warning => "warning",
+                DiagnosticKind::Note => "notice",
+            };
+
+            let title = match diagnostic.code {
+                Some(code) => format!("bracketlint ({code})"),
+                None => "bracketlint".to_string(),
+            };
+
+            let message = match diagnostic.code {
+                Some(code) => format!("{path}:{start}: {code} {}", diagnostic.message),
+                None => format!("{path}:{start}: {}", diagnostic.message),
+            };
+
+            stream_writeln!(
+                writer,
+                "::{command} title={},file={},line={},col={},endLine={},endColumn={}::{}",
+                escape_property(&title),
+                escape_property(&path),
+                start.row + 1,
+                start.column + 1,
+                end.row + 1,
+                end.column + 1,
+                escape_data(&message),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, i.e. the `file`.
+fn escape_property(property: &str) -> String {
+    escape_data(property).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics};
+    use bl_utils::stream::CompilerOutputStream;
+
+    use super::GithubEmitter;
+    use crate::message::Emitter;
+
+    #[test]
+    fn workflow_commands() {
+        let id = SourceMap::add("a,b.html", "{{ x }}\n{% break %}");
+        let span = Span::new(ByteRange::new(8, 18), id);
+        let diagnostics: Diagnostics =
+            [Diagnostic::new(DiagnosticKind::Error, "100% wrong\nreally", span).with_code("BL002")]
+                .into_iter()
+                .collect();
+
+        let mut stream = CompilerOutputStream::owned();
+        GithubEmitter.emit(&mut stream, &diagnostics).unwrap();
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+
+        assert_eq!(
+            output,
+            "::error title=bracketlint (BL002),file=a%2Cb.html,line=2,col=1,endLine=2,endColumn=12::a,b.html:2:1: BL002 100%25 wrong%0Areally\n"
+        );
+    }
+}
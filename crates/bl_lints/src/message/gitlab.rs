@@ -0,0 +1,134 @@
+//! The GitLab emitter, which writes the diagnostics as a [Code Quality] report.
+//!
+//! Paths are reported relative to the `CI_PROJECT_DIR` environment variable
+//! if it is set, and the current working directory otherwise.
+//!
+//! [Code Quality]: https://docs.gitlab.com/ee/ci/testing/code_quality.html#implement-a-custom-tool
+
+use std::{collections::HashMap, io::Write, path::PathBuf};
+
+use anyhow::Result;
+use bl_diagnostics::{DiagnosticKind, Diagnostics};
+use bl_utils::{
+    fs::{relativize_path, relativize_path_to},
+    stream_writeln,
+};
+use serde_json::json;
+
+use super::{fingerprint, Emitter, Location};
+
+/// Writes the diagnostics as a GitLab Code Quality report.
+pub struct GitlabEmitter {
+    /// The directory that paths are reported relative to.
+    project_dir: Option<PathBuf>,
+}
+
+impl Default for GitlabEmitter {
+    fn default() -> Self {
+        Self { project_dir: std::env::var_os("CI_PROJECT_DIR").map(PathBuf::from) }
+    }
+}
+
+impl GitlabEmitter {
+    /// Create a new [GitlabEmitter], which reports paths relative to the
+    /// `CI_PROJECT_DIR` environment variable if it is set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Emitter for GitlabEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        // The number of times that each fingerprint has been seen, so that
+        // identical violations still produce unique fingerprints.
+        let mut occurrences: HashMap<u64, usize> = HashMap::new();
+
+        let report: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let Location { path, start, end } = Location::of(diagnostic);
+                let path = match &self.project_dir {
+                    Some(project_dir) => relativize_path_to(&path, project_dir),
+                    None => relativize_path(&path),
+                };
+
+                let occurrence = occurrences.entry(fingerprint(diagnostic, &path, 0)).or_default();
+                let fingerprint = fingerprint(diagnostic, &path, *occurrence);
+                *occurrence += 1;
+
+                let severity = match diagnostic.kind {
+                    DiagnosticKind::Error => "major",
+                    DiagnosticKind::Warning => "minor",
+                    DiagnosticKind::Note => "info",
+                };
+
+                json!({
+                    "description": match diagnostic.code {
+                        Some(code) => format!("({code}) {}", diagnostic.message),
+                        None => diagnostic.message.clone(),
+                    },
+                    "check_name": diagnostic.code.unwrap_or("syntax-error"),
+                    "fingerprint": format!("{fingerprint:016x}"),
+                    "severity": severity,
+                    "location": {
+                        "path": path,
+                        "lines": { "begin": start.row + 1, "end": end.row + 1 },
+                    },
+                })
+            })
+            .collect();
+
+        serde_json::to_writer_pretty(&mut *writer, &report)?;
+        stream_writeln!(writer);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics};
+    use bl_utils::stream::CompilerOutputStream;
+    use serde_json::Value;
+
+    use super::GitlabEmitter;
+    use crate::message::Emitter;
+
+    /// Report a `break` violation for every `{% break %}` in the source.
+    fn report(source: &str) -> Value {
+        let id = SourceMap::add("index.html", source);
+        let diagnostics: Diagnostics = source
+            .match_indices("{% break %}")
+            .map(|(start, text)| {
+                let span = Span::new(ByteRange::new(start, start + text.len() - 1), id);
+                Diagnostic::new(DiagnosticKind::Error, "`break` used outside of a `for` loop", span)
+                    .with_code("BL002")
+            })
+            .collect();
+
+        let mut stream = CompilerOutputStream::owned();
+        GitlabEmitter { project_dir: None }.emit(&mut stream, &diagnostics).unwrap();
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = output.lock().unwrap();
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    #[test]
+    fn fingerprints_are_stable_and_unique() {
+        let before = report("{% break %}\n{% break %}\n");
+        let after = report("{{ x }}\n\n{% break %}\n{% break %}\n");
+
+        let fingerprints = |report: &Value| {
+            report
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| entry["fingerprint"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_ne!(fingerprints(&before)[0], fingerprints(&before)[1]);
+        assert_eq!(fingerprints(&before), fingerprints(&after));
+        assert_eq!(after[0]["location"]["lines"]["begin"], 3);
+    }
+}
@@ -0,0 +1,96 @@
+//! The grouped emitter, which writes the diagnostics of each file underneath a
+//! heading with the path of the file.
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::Diagnostics;
+use bl_utils::{
+    highlight::{colours_enabled, highlight, Modifier},
+    stream_writeln,
+};
+use itertools::Itertools;
+
+use super::{concise::DiagnosticBody, write_summary, Emitter, Location};
+use crate::settings::UnsafeFixes;
+
+/// Writes the diagnostics grouped by the file that they refer to, followed by
+/// a summary of how many diagnostics were found.
+///
+/// ##Note: the diagnostics are expected to be sorted by file.
+pub struct GroupedEmitter {
+    /// Whether the output should be coloured.
+    colours: bool,
+
+    /// The number of diagnostics that were fixed, which is included in the
+    /// summary.
+    fixed: usize,
+
+    /// Whether unsafe fixes are applied, which determines which diagnostics
+    /// are fixable.
+    unsafe_fixes: UnsafeFixes,
+}
+
+impl Default for GroupedEmitter {
+    fn default() -> Self {
+        Self { colours: colours_enabled(), fixed: 0, unsafe_fixes: UnsafeFixes::default() }
+    }
+}
+
+impl GroupedEmitter {
+    /// Create a new [GroupedEmitter], colours are enabled based on the global
+    /// colour choice.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specify whether the output should be coloured.
+    pub fn with_colours(mut self, colours: bool) -> Self {
+        self.colours = colours;
+        self
+    }
+
+    /// Specify the number of diagnostics that were fixed.
+    pub fn with_fixed(mut self, fixed: usize) -> Self {
+        self.fixed = fixed;
+        self
+    }
+
+    /// Specify whether unsafe fixes are applied.
+    pub fn with_unsafe_fixes(mut self, unsafe_fixes: UnsafeFixes) -> Self {
+        self.unsafe_fixes = unsafe_fixes;
+        self
+    }
+}
+
+impl Emitter for GroupedEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        let located = diagnostics.iter().map(|diagnostic| (Location::of(diagnostic), diagnostic));
+
+        for (path, group) in &located.chunk_by(|(location, _)| location.path.clone()) {
+            let group = group.collect_vec();
+
+            let heading = if self.colours { highlight(Modifier::Underline, &path) } else { path };
+            stream_writeln!(writer, "{heading}:");
+
+            // Align all of the messages within the group.
+            let positions =
+                group.iter().map(|(location, _)| location.start.to_string()).collect_vec();
+            let width = positions.iter().map(|position| position.len()).max().unwrap_or(0);
+
+            for (position, (_, diagnostic)) in positions.iter().zip(&group) {
+                let body = DiagnosticBody {
+                    diagnostic,
+                    unsafe_fixes: self.unsafe_fixes,
+                    colours: self.colours,
+                };
+                stream_writeln!(writer, "  {position:<width$} {body}");
+            }
+
+            stream_writeln!(writer);
+        }
+
+        write_summary(writer, diagnostics, self.fixed, self.unsafe_fixes, self.colours);
+        Ok(())
+    }
+}
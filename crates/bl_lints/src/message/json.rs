@@ -0,0 +1,218 @@
+//! The JSON emitters, which write the diagnostics in a machine-readable format.
+//!
+//! Each diagnostic is written as an object with the following schema, all of
+//! the positions are one-indexed, and the `end_location` refers to the position
+//! directly after the end of the diagnostic or edit:
+//!
+//! ```json
+//! {
+//!   "filename": "templates/index.html",
+//!   "code": "BL001",
+//!   "severity": "error",
+//!   "message": "block `content` is defined multiple times",
+//!   "help": "rename one of the blocks, or remove the duplicate",
+//!   "location": { "row": 3, "column": 10 },
+//!   "end_location": { "row": 3, "column": 17 },
+//!   "fix": {
+//!     "applicability": "safe",
+//!     "edits": [
+//!       {
+//!         "content": "",
+//!         "location": { "row": 3, "column": 1 },
+//!         "end_location": { "row": 4, "column": 1 }
+//!       }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! The `code`, `help` and `fix` entries are `null` if they are not present,
+//! i.e. syntax errors don't have a code.
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_ast::{RowCol, SourceMap};
+use bl_diagnostics::{Diagnostic, Diagnostics, Fix};
+use bl_utils::stream_writeln;
+use serde::Serialize;
+
+use super::{Emitter, Location};
+
+/// Writes all of the diagnostics as a single JSON array.
+#[derive(Default)]
+pub struct JsonEmitter;
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        let diagnostics: Vec<_> = diagnostics.iter().map(JsonDiagnostic::new).collect();
+        serde_json::to_writer_pretty(&mut *writer, &diagnostics)?;
+        stream_writeln!(writer);
+        Ok(())
+    }
+}
+
+/// Writes each diagnostic as a JSON object on its own line.
+#[derive(Default)]
+pub struct JsonLinesEmitter;
+
+impl Emitter for JsonLinesEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        for diagnostic in diagnostics {
+            serde_json::to_writer(&mut *writer, &JsonDiagnostic::new(diagnostic))?;
+            stream_writeln!(writer);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'d> {
+    filename: String,
+    code: Option<&'static str>,
+    severity: String,
+    message: &'d str,
+    help: Option<&'d str>,
+    location: JsonLocation,
+    end_location: JsonLocation,
+    fix: Option<JsonFix<'d>>,
+}
+
+impl<'d> JsonDiagnostic<'d> {
+    fn new(diagnostic: &'d Diagnostic) -> Self {
+        let Location { path, start, end } = Location::of(diagnostic);
+
+        Self {
+            filename: path,
+            code: diagnostic.code,
+            severity: diagnostic.kind.to_string(),
+            message: &diagnostic.message,
+            help: diagnostic.help.as_deref(),
+            location: start.into(),
+            end_location: end.into(),
+            fix: diagnostic.fix.as_ref().map(|fix| JsonFix::new(diagnostic, fix)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonFix<'d> {
+    applicability: String,
+    edits: Vec<JsonEdit<'d>>,
+}
+
+impl<'d> JsonFix<'d> {
+    fn new(diagnostic: &Diagnostic, fix: &'d Fix) -> Self {
+        let file = SourceMap::get(diagnostic.span.id);
+        let row_col = |offset| file.as_ref().map(|file| file.row_col(offset)).unwrap_or_default();
+
+        let edits = fix
+            .edits()
+            .iter()
+            .map(|edit| JsonEdit {
+                content: edit.content(),
+                location: row_col(edit.start()).into(),
+                end_location: row_col(edit.end()).into(),
+            })
+            .collect();
+
+        Self { applicability: fix.applicability().to_string(), edits }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonEdit<'d> {
+    content: &'d str,
+    location: JsonLocation,
+    end_location: JsonLocation,
+}
+
+/// A one-indexed position within a source.
+#[derive(Serialize)]
+struct JsonLocation {
+    row: usize,
+    column: usize,
+}
+
+impl From<RowCol> for JsonLocation {
+    fn from(position: RowCol) -> Self {
+        Self { row: position.row + 1, column: position.column + 1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics, Edit, Fix};
+    use bl_utils::stream::CompilerOutputStream;
+    use serde_json::json;
+
+    use super::{JsonEmitter, JsonLinesEmitter};
+    use crate::message::Emitter;
+
+    fn render(emitter: &mut dyn Emitter, diagnostics: &Diagnostics) -> String {
+        let mut stream = CompilerOutputStream::owned();
+        emitter.emit(&mut stream, diagnostics).unwrap();
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = output.lock().unwrap();
+        String::from_utf8(output.clone()).unwrap()
+    }
+
+    #[test]
+    fn json_schema() {
+        let id = SourceMap::add("index.html", "{{ x }}\n{% break %}\n");
+        let span = Span::new(ByteRange::new(8, 18), id);
+
+        let diagnostics: Diagnostics = [
+            Diagnostic::new(DiagnosticKind::Error, "`break` used outside of a `for` loop", span)
+                .with_code("BL002")
+                .with_fix(Fix::unsafe_edits([Edit::delete(ByteRange::new(8, 19))])),
+            Diagnostic::new(
+                DiagnosticKind::Error,
+                "unexpected `}}`",
+                Span::new(ByteRange::new(5, 6), id),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let expected = json!([
+            {
+                "filename": "index.html",
+                "code": "BL002",
+                "severity": "error",
+                "message": "`break` used outside of a `for` loop",
+                "help": null,
+                "location": { "row": 2, "column": 1 },
+                "end_location": { "row": 2, "column": 12 },
+                "fix": {
+                    "applicability": "unsafe",
+                    "edits": [{
+                        "content": "",
+                        "location": { "row": 2, "column": 1 },
+                        "end_location": { "row": 3, "column": 1 },
+                    }]
+                }
+            },
+            {
+                "filename": "index.html",
+                "code": null,
+                "severity": "error",
+                "message": "unexpected `}}`",
+                "help": null,
+                "location": { "row": 1, "column": 6 },
+                "end_location": { "row": 1, "column": 8 },
+                "fix": null
+            }
+        ]);
+
+        let output = render(&mut JsonEmitter, &diagnostics);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&output).unwrap(), expected);
+
+        let output = render(&mut JsonLinesEmitter, &diagnostics);
+        let lines: Vec<serde_json::Value> =
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(serde_json::Value::Array(lines), expected);
+    }
+}
@@ -0,0 +1,80 @@
+//! The JUnit emitter, which writes the diagnostics as a JUnit XML report with
+//! a test suite for each file, and a failing test case for each diagnostic.
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::Diagnostics;
+use bl_utils::stream_writeln;
+use itertools::Itertools;
+
+use super::{xml_escape, Emitter, Location};
+
+/// Writes the diagnostics as a JUnit XML report.
+///
+/// ##Note: the diagnostics are expected to be sorted by file.
+#[derive(Default)]
+pub struct JunitEmitter;
+
+impl Emitter for JunitEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        let total = diagnostics.len();
+
+        stream_writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        stream_writeln!(
+            writer,
+            r#"<testsuites name="bracketlint" tests="{}" failures="{total}" errors="0">"#,
+            total.max(1)
+        );
+
+        // A report without any test cases is treated as a failure by some CI
+        // systems, so a passing test case is written instead.
+        if diagnostics.is_empty() {
+            stream_writeln!(
+                writer,
+                r#"  <testsuite name="bracketlint" tests="1" failures="0" errors="0">"#
+            );
+            stream_writeln!(
+                writer,
+                r#"    <testcase name="No errors found" classname="bracketlint"/>"#
+            );
+            stream_writeln!(writer, "  </testsuite>");
+        }
+
+        let located = diagnostics.iter().map(|diagnostic| (Location::of(diagnostic), diagnostic));
+        for (path, group) in &located.chunk_by(|(location, _)| location.path.clone()) {
+            let group = group.collect_vec();
+            let path = xml_escape(&path);
+
+            stream_writeln!(
+                writer,
+                r#"  <testsuite name="{path}" tests="{count}" failures="{count}" errors="0">"#,
+                count = group.len()
+            );
+
+            for (Location { start, .. }, diagnostic) in group {
+                let code = diagnostic.code.unwrap_or("syntax-error");
+                let message = xml_escape(&diagnostic.message);
+
+                stream_writeln!(
+                    writer,
+                    r#"    <testcase name="bracketlint.{code}" classname="{path}" line="{}" column="{}">"#,
+                    start.row + 1,
+                    start.column + 1,
+                );
+                stream_writeln!(
+                    writer,
+                    r#"      <failure message="{message}">line {}, col {}, {message}</failure>"#,
+                    start.row + 1,
+                    start.column + 1,
+                );
+                stream_writeln!(writer, "    </testcase>");
+            }
+
+            stream_writeln!(writer, "  </testsuite>");
+        }
+
+        stream_writeln!(writer, "</testsuites>");
+        Ok(())
+    }
+}
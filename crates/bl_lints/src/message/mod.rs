@@ -0,0 +1,265 @@
+//! Emitters which write collected [Diagnostic]s to an output stream in the
+//! various formats that are supported by the linter.
+
+mod azure;
+mod checkstyle;
+mod concise;
+mod github;
+mod gitlab;
+mod grouped;
+mod json;
+mod junit;
+mod sarif;
+mod text;
+
+use std::{collections::HashMap, hash::Hasher, io::Write, ops::Range, sync::Arc};
+
+use anyhow::Result;
+pub use azure::AzureEmitter;
+use bl_ast::{RowCol, SourceFile, SourceId, SourceMap};
+use bl_diagnostics::{Applicability, Diagnostic, DiagnosticKind, Diagnostics};
+use bl_utils::{
+    hash::StableHasher,
+    highlight::{highlight, Colour, Modifier},
+    pluralise, stream_writeln,
+};
+pub use checkstyle::CheckstyleEmitter;
+use codespan_reporting::files::{Error, Files};
+pub use concise::ConciseEmitter;
+pub use github::GithubEmitter;
+pub use gitlab::GitlabEmitter;
+pub use grouped::GroupedEmitter;
+pub use json::{JsonEmitter, JsonLinesEmitter};
+pub use junit::JunitEmitter;
+pub use sarif::SarifEmitter;
+pub use text::TextEmitter;
+
+use crate::settings::UnsafeFixes;
+
+/// An [Emitter] writes a collection of [Diagnostics] to an output stream in a
+/// particular format.
+pub trait Emitter {
+    /// Write all of the given diagnostics to the `writer`.
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()>;
+}
+
+/// The resolved location of a [Diagnostic], this is used by all of the
+/// emitters which don't render a snippet of the source.
+pub(crate) struct Location {
+    /// The path of the source, or `-` if the source is unknown.
+    pub path: String,
+
+    /// The position at which the diagnostic starts.
+    pub start: RowCol,
+
+    /// The position directly after the end of the diagnostic.
+    pub end: RowCol,
+}
+
+impl Location {
+    /// Resolve the location of the given [Diagnostic].
+    pub(crate) fn of(diagnostic: &Diagnostic) -> Self {
+        let span = diagnostic.span;
+
+        match SourceMap::get(span.id) {
+            Some(file) => Location {
+                path: file.path().display().to_string(),
+                start: file.row_col(span.range.start()),
+                end: file.row_col(span.range.end() + 1),
+            },
+            None => {
+                Location { path: "-".to_string(), start: RowCol::default(), end: RowCol::default() }
+            }
+        }
+    }
+}
+
+/// Compute a fingerprint of a [Diagnostic] which remains the same when lines
+/// are added or removed elsewhere in the file. The fingerprint is computed from
+/// the path, rule code, message and the source text that the diagnostic
+/// covers. The `occurrence` is used to distinguish between diagnostics which
+/// are otherwise identical, i.e. the same violation on two separate lines.
+pub fn fingerprint(diagnostic: &Diagnostic, path: &str, occurrence: usize) -> u64 {
+    let snippet = SourceMap::get(diagnostic.span.id).map(|file| {
+        let range = diagnostic.span.range;
+        file.contents().get(range.start()..=range.end()).unwrap_or_default().trim().to_string()
+    });
+
+    let parts = [
+        path,
+        diagnostic.code.unwrap_or_default(),
+        &diagnostic.message,
+        snippet.as_deref().unwrap_or_default(),
+        &occurrence.to_string(),
+    ];
+
+    let mut hasher = StableHasher::new();
+    for part in parts {
+        // Separate each of the parts so that `("ab", "c")` and `("a", "bc")`
+        // produce different fingerprints.
+        hasher.write(part.as_bytes());
+        hasher.write_u8(0);
+    }
+    hasher.finish()
+}
+
+/// Escape the given text so that it can be used within XML content and
+/// attributes.
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Write the summary line, i.e. `Found 12 errors and 3 warnings (4 fixable).`,
+/// or a message stating that there were no diagnostics. When fixes were
+/// applied, the number of fixed and remaining diagnostics is written instead,
+/// i.e. `Found 12 violations (8 fixed, 4 errors remaining).`. Unless disabled,
+/// a hint is written when there are unsafe fixes that weren't applied.
+pub(crate) fn write_summary(
+    writer: &mut dyn Write,
+    diagnostics: &Diagnostics,
+    fixed: usize,
+    unsafe_fixes: UnsafeFixes,
+    colours: bool,
+) {
+    let highlight = |decoration, message: String| {
+        if colours {
+            highlight(decoration, message)
+        } else {
+            message
+        }
+    };
+
+    if diagnostics.is_empty() && fixed == 0 {
+        let message = highlight(Colour::Green | Modifier::Bold, "All checks passed!".into());
+        stream_writeln!(writer, "{message}");
+        return;
+    }
+
+    // The remaining diagnostics are counted separately for each kind, i.e.
+    // `3 errors, 2 warnings and 1 note`.
+    let counts: Vec<_> = [DiagnosticKind::Error, DiagnosticKind::Warning, DiagnosticKind::Note]
+        .into_iter()
+        .map(|kind| (kind, diagnostics.count(kind)))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| format!("{count} {kind}{}", pluralise!(count)))
+        .collect();
+    let counts = match counts.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+        None => "0".to_string(),
+    };
+
+    let colour =
+        if diagnostics.count(DiagnosticKind::Error) > 0 { Colour::Red } else { Colour::Yellow };
+
+    let fixable = diagnostics.fixable_count_with(unsafe_fixes.required_applicability());
+    if fixed > 0 {
+        let total = diagnostics.len() + fixed;
+        let message = highlight(
+            colour | Modifier::Bold,
+            format!("Found {total} violation{}", pluralise!(total)),
+        );
+        stream_writeln!(writer, "{message} ({fixed} fixed, {counts} remaining).");
+    } else {
+        let message = highlight(colour | Modifier::Bold, format!("Found {counts}"));
+        if fixable > 0 {
+            stream_writeln!(writer, "{message} ({fixable} fixable).");
+        } else {
+            stream_writeln!(writer, "{message}.");
+        }
+    }
+
+    let hidden = diagnostics.fixable_count_with(Applicability::Unsafe) - fixable;
+    if unsafe_fixes == UnsafeFixes::Hint && hidden > 0 {
+        stream_writeln!(
+            writer,
+            "{hidden} hidden fix{} can be enabled with the `--unsafe-fixes` option.",
+            if hidden == 1 { "" } else { "es" }
+        );
+    }
+}
+
+/// A snapshot of the sources that are referenced by a collection of
+/// [Diagnostic]s, which implements [Files] so that the diagnostics can be
+/// rendered by `codespan-reporting`.
+#[derive(Default)]
+pub(crate) struct SourceFiles {
+    files: HashMap<SourceId, Arc<SourceFile>>,
+}
+
+impl SourceFiles {
+    /// Collect all of the sources that the given diagnostics (and any of
+    /// their children) refer to.
+    pub(crate) fn collect<'d>(diagnostics: impl IntoIterator<Item = &'d Diagnostic>) -> Self {
+        let mut files = Self::default();
+        for diagnostic in diagnostics {
+            files.add(diagnostic);
+        }
+        files
+    }
+
+    fn add(&mut self, diagnostic: &Diagnostic) {
+        let ids = std::iter::once(diagnostic.span.id)
+            .chain(diagnostic.labels.iter().map(|label| label.span.id));
+
+        for id in ids {
+            if let Some(file) = SourceMap::get(id) {
+                self.files.entry(id).or_insert(file);
+            }
+        }
+
+        for child in &diagnostic.children {
+            self.add(child);
+        }
+    }
+
+    /// Check whether the source with the given [SourceId] is available.
+    pub(crate) fn contains(&self, id: SourceId) -> bool {
+        self.files.contains_key(&id)
+    }
+
+    fn file(&self, id: SourceId) -> Result<&SourceFile, Error> {
+        self.files.get(&id).map(|file| file.as_ref()).ok_or(Error::FileMissing)
+    }
+}
+
+impl<'a> Files<'a> for SourceFiles {
+    type FileId = SourceId;
+    type Name = String;
+    type Source = &'a str;
+
+    fn name(&'a self, id: SourceId) -> Result<String, Error> {
+        Ok(self.file(id)?.path().display().to_string())
+    }
+
+    fn source(&'a self, id: SourceId) -> Result<&'a str, Error> {
+        Ok(self.file(id)?.contents())
+    }
+
+    fn line_index(&'a self, id: SourceId, byte_index: usize) -> Result<usize, Error> {
+        Ok(self.file(id)?.row_col(byte_index).row)
+    }
+
+    fn line_range(&'a self, id: SourceId, line_index: usize) -> Result<Range<usize>, Error> {
+        let file = self.file(id)?;
+        let lines = file.lines();
+
+        let start = lines
+            .line_start(line_index)
+            .ok_or(Error::LineTooLarge { given: line_index, max: lines.line_count() - 1 })?;
+        let end = lines.line_start(line_index + 1).unwrap_or(file.contents().len());
+
+        Ok(start..end)
+    }
+}
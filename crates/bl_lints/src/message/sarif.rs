@@ -0,0 +1,270 @@
+//! The SARIF emitter, which writes the diagnostics as a [SARIF 2.1.0] log so
+//! that they can be uploaded to code-scanning dashboards.
+//!
+//! The log contains a single run, which describes every rule within the
+//! [RULES] registry and a result for each diagnostic. Diagnostics without a
+//! rule code (i.e. syntax errors) are reported without a `ruleId`.
+//!
+//! Paths within the current directory are written as URIs relative to the
+//! `%SRCROOT%` base, which is the current directory, and any other paths are
+//! written as absolute `file://` URIs. Columns are counted in Unicode code
+//! points, as declared by the `columnKind` of the run, rather than the bytes
+//! that are used internally.
+//!
+//! [SARIF 2.1.0]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::{io::Write, path::Path};
+
+use anyhow::Result;
+use bl_ast::{RowCol, SourceFile, SourceMap};
+use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics, Fix};
+use bl_utils::{fs::normalize_path, stream_writeln};
+use serde_json::{json, Value};
+use url::Url;
+
+use super::Emitter;
+use crate::{registry::RULES, rule::Rule};
+
+/// The URI of the SARIF 2.1.0 schema.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The name of the base that relative URIs are resolved against.
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Writes the diagnostics as a SARIF log.
+#[derive(Default)]
+pub struct SarifEmitter;
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        let root = Url::from_directory_path(std::env::current_dir()?).ok();
+        let results: Vec<_> =
+            diagnostics.iter().map(|diagnostic| sarif_result(diagnostic, root.as_ref())).collect();
+
+        let mut log = json!({
+            "$schema": SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "bracketlint",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": RULES.iter().map(|rule| sarif_rule(*rule)).collect::<Vec<_>>(),
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": results,
+            }],
+        });
+
+        if let Some(root) = root {
+            log["runs"][0]["originalUriBaseIds"] = json!({ SRCROOT: { "uri": root.as_str() } });
+        }
+
+        serde_json::to_writer_pretty(&mut *writer, &log)?;
+        stream_writeln!(writer);
+        Ok(())
+    }
+}
+
+/// Convert a [DiagnosticKind] into a SARIF `level`.
+fn level(kind: DiagnosticKind) -> &'static str {
+    match kind {
+        DiagnosticKind::Error => "error",
+        DiagnosticKind::Warning => "warning",
+        DiagnosticKind::Note => "note",
+    }
+}
+
+/// Create a SARIF `artifactLocation` for the given path. Paths within the
+/// `root` are relative to the [SRCROOT] base, other paths are absolute.
+fn artifact_location(path: &Path, root: Option<&Url>) -> Value {
+    let Ok(uri) = Url::from_file_path(normalize_path(path)) else {
+        return json!({ "uri": path.display().to_string().replace('\\', "/") });
+    };
+
+    match root.and_then(|root| uri.as_str().strip_prefix(root.as_str())) {
+        Some(relative) => json!({ "uri": relative, "uriBaseId": SRCROOT }),
+        None => json!({ "uri": uri.as_str() }),
+    }
+}
+
+/// Convert a byte offset within the given file into a (zero-indexed) position,
+/// whose column is counted in Unicode code points.
+fn position(file: Option<&SourceFile>, offset: usize) -> RowCol {
+    let Some(file) = file else {
+        return RowCol::default();
+    };
+
+    let RowCol { row, column } = file.row_col(offset);
+    let line = &file.contents()[offset - column..offset];
+    RowCol { row, column: line.chars().count() }
+}
+
+/// Create a SARIF `region` between the given (zero-indexed) positions.
+fn region(start: RowCol, end: RowCol) -> Value {
+    json!({
+        "startLine": start.row + 1,
+        "startColumn": start.column + 1,
+        "endLine": end.row + 1,
+        "endColumn": end.column + 1,
+    })
+}
+
+/// Create a SARIF `reportingDescriptor` for the given [Rule].
+fn sarif_rule(rule: &dyn Rule) -> Value {
+    json!({
+        "id": rule.code(),
+        "name": rule.name(),
+        "shortDescription": { "text": rule.description() },
+        "helpUri": rule.url(),
+        "defaultConfiguration": { "level": level(rule.default_severity()) },
+        "properties": { "tags": [rule.category().as_str()] },
+    })
+}
+
+/// Create a SARIF `result` for the given [Diagnostic].
+fn sarif_result(diagnostic: &Diagnostic, root: Option<&Url>) -> Value {
+    let file = SourceMap::get(diagnostic.span.id);
+    let location = match &file {
+        Some(file) => artifact_location(file.path(), root),
+        None => json!({ "uri": "-" }),
+    };
+
+    let range = diagnostic.span.range;
+    let (start, end) =
+        (position(file.as_deref(), range.start()), position(file.as_deref(), range.end() + 1));
+
+    let mut result = json!({
+        "level": level(diagnostic.kind),
+        "message": { "text": diagnostic.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": location,
+                "region": region(start, end),
+            }
+        }],
+    });
+
+    if let Some(code) = diagnostic.code {
+        result["ruleId"] = json!(code);
+
+        if let Some(index) = RULES.iter().position(|rule| rule.code() == code) {
+            result["ruleIndex"] = json!(index);
+        }
+    }
+
+    if let Some(fix) = &diagnostic.fix {
+        result["fixes"] = json!([sarif_fix(diagnostic, fix, file.as_deref(), &location)]);
+    }
+
+    result
+}
+
+/// Create a SARIF `fix` for the given [Fix], each edit is converted into a
+/// replacement of the region that it covers.
+fn sarif_fix(
+    diagnostic: &Diagnostic,
+    fix: &Fix,
+    file: Option<&SourceFile>,
+    location: &Value,
+) -> Value {
+    let row_col = |offset| position(file, offset);
+
+    let replacements: Vec<_> = fix
+        .edits()
+        .iter()
+        .map(|edit| {
+            json!({
+                "deletedRegion": region(row_col(edit.start()), row_col(edit.end())),
+                "insertedContent": { "text": edit.content() },
+            })
+        })
+        .collect();
+
+    json!({
+        "description": { "text": diagnostic.help.as_deref().unwrap_or(&diagnostic.message) },
+        "artifactChanges": [{
+            "artifactLocation": location,
+            "replacements": replacements,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics, Edit, Fix};
+    use bl_utils::stream::CompilerOutputStream;
+    use serde_json::Value;
+
+    use super::SarifEmitter;
+    use crate::message::Emitter;
+
+    /// A vendored copy of the SARIF 2.1.0 schema.
+    const SCHEMA: &str = include_str!("../../resources/test/sarif-schema-2.1.0.json");
+
+    #[test]
+    fn validate_against_schema() {
+        let id = SourceMap::add("templates/index.html", "{{ x }}\n{% break %}\n");
+        let outside = SourceMap::add("/elsewhere/é.html", "é {% break %}");
+
+        let diagnostics: Diagnostics = [
+            Diagnostic::new(
+                DiagnosticKind::Error,
+                "`break` used outside of a `for` loop",
+                Span::new(ByteRange::new(8, 18), id),
+            )
+            .with_code("BL002")
+            .with_fix(Fix::unsafe_edits([Edit::delete(ByteRange::new(8, 19))])),
+            Diagnostic::new(
+                DiagnosticKind::Error,
+                "unexpected `}}`",
+                Span::new(ByteRange::new(5, 6), id),
+            ),
+            Diagnostic::new(
+                DiagnosticKind::Error,
+                "`break` used outside of a `for` loop",
+                Span::new(ByteRange::new(3, 13), outside),
+            )
+            .with_code("BL002"),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut stream = CompilerOutputStream::owned();
+        SarifEmitter.emit(&mut stream, &diagnostics).unwrap();
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let log: Value = serde_json::from_slice(&output.lock().unwrap()).unwrap();
+
+        let schema = serde_json::from_str(SCHEMA).unwrap();
+        let validator = jsonschema::JSONSchema::compile(&schema).unwrap();
+        if let Err(errors) = validator.validate(&log) {
+            let errors: Vec<_> = errors.map(|error| error.to_string()).collect();
+            panic!("invalid SARIF log: {errors:#?}");
+        }
+
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results[0]["ruleId"], "BL002");
+        assert_eq!(
+            results[0]["fixes"][0]["artifactChanges"][0]["replacements"][0]["deletedRegion"]
+                ["endLine"],
+            3
+        );
+        assert!(results[1].get("ruleId").is_none());
+
+        // Paths within the current directory are relative to its base.
+        let root = &log["runs"][0]["originalUriBaseIds"]["%SRCROOT%"]["uri"];
+        assert!(root.as_str().unwrap().starts_with("file:///"), "{root}");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "templates/index.html");
+        assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+
+        // Other paths are absolute, and columns are counted in code points.
+        let location = &results[2]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "file:///elsewhere/%C3%A9.html");
+        assert!(location["artifactLocation"].get("uriBaseId").is_none());
+        assert_eq!(location["region"]["startColumn"], 3);
+        assert_eq!(location["region"]["endColumn"], 14);
+    }
+}
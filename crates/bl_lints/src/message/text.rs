@@ -0,0 +1,260 @@
+//! The human-readable emitter, which renders each diagnostic with a snippet of
+//! the source that it refers to.
+
+use std::io::Write;
+
+use anyhow::Result;
+use bl_diagnostics::{Diagnostic, DiagnosticKind, Diagnostics};
+use bl_utils::highlight::colours_enabled;
+use codespan_reporting::{
+    diagnostic::{Diagnostic as CodespanDiagnostic, Label, Severity},
+    files::Files,
+    term::{
+        self,
+        termcolor::{Ansi, NoColor, WriteColor},
+    },
+};
+
+use super::{write_summary, Emitter, SourceFiles};
+use crate::settings::UnsafeFixes;
+
+/// Renders [Diagnostic]s using `codespan-reporting`, followed by a summary of
+/// how many diagnostics were found.
+pub struct TextEmitter {
+    /// Whether the output should be coloured.
+    colours: bool,
+
+    /// The number of diagnostics that were fixed, which is included in the
+    /// summary.
+    fixed: usize,
+
+    /// Whether unsafe fixes are applied, which determines which diagnostics
+    /// are fixable.
+    unsafe_fixes: UnsafeFixes,
+
+    /// Whether the summary line should be written after the diagnostics.
+    summary: bool,
+}
+
+impl Default for TextEmitter {
+    fn default() -> Self {
+        Self {
+            colours: colours_enabled(),
+            fixed: 0,
+            unsafe_fixes: UnsafeFixes::default(),
+            summary: true,
+        }
+    }
+}
+
+impl TextEmitter {
+    /// Create a new [TextEmitter], colours are enabled based on the global
+    /// colour choice.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specify whether the output should be coloured.
+    pub fn with_colours(mut self, colours: bool) -> Self {
+        self.colours = colours;
+        self
+    }
+
+    /// Specify the number of diagnostics that were fixed.
+    pub fn with_fixed(mut self, fixed: usize) -> Self {
+        self.fixed = fixed;
+        self
+    }
+
+    /// Specify whether unsafe fixes are applied.
+    pub fn with_unsafe_fixes(mut self, unsafe_fixes: UnsafeFixes) -> Self {
+        self.unsafe_fixes = unsafe_fixes;
+        self
+    }
+
+    /// Specify whether the summary line should be written.
+    pub fn with_summary(mut self, summary: bool) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    fn emit_diagnostics(
+        &self,
+        writer: &mut dyn WriteColor,
+        files: &SourceFiles,
+        diagnostics: &Diagnostics,
+    ) -> Result<()> {
+        let config = term::Config::default();
+
+        for diagnostic in diagnostics {
+            term::emit(writer, &config, files, &to_codespan(diagnostic, files))?;
+
+            for child in &diagnostic.children {
+                term::emit(writer, &config, files, &to_codespan(child, files))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Emitter for TextEmitter {
+    fn emit(&mut self, writer: &mut dyn Write, diagnostics: &Diagnostics) -> Result<()> {
+        let files = SourceFiles::collect(diagnostics);
+
+        if self.colours {
+            self.emit_diagnostics(&mut Ansi::new(&mut *writer), &files, diagnostics)?;
+        } else {
+            self.emit_diagnostics(&mut NoColor::new(&mut *writer), &files, diagnostics)?;
+        }
+
+        if self.summary {
+            write_summary(writer, diagnostics, self.fixed, self.unsafe_fixes, self.colours);
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a [Diagnostic] into the representation that `codespan-reporting`
+/// uses. If the source of the diagnostic isn't available, then the diagnostic
+/// is rendered without any labels.
+fn to_codespan(
+    diagnostic: &Diagnostic,
+    files: &SourceFiles,
+) -> CodespanDiagnostic<bl_ast::SourceId> {
+    let severity = match diagnostic.kind {
+        DiagnosticKind::Error => Severity::Error,
+        DiagnosticKind::Warning => Severity::Warning,
+        DiagnosticKind::Note => Severity::Note,
+    };
+
+    let mut output = CodespanDiagnostic::new(severity).with_message(&diagnostic.message);
+
+    if let Some(code) = diagnostic.code {
+        output = output.with_code(code);
+    }
+
+    // ##Note: spans are inclusive, whereas `codespan-reporting` expects
+    // exclusive ranges. Ranges are also clamped to the source, since errors at
+    // the end of a source might point past it.
+    let range = |span: bl_ast::Span| {
+        let len = files.source(span.id).map_or(0, |source| source.len());
+        span.range.start().min(len)..(span.range.end() + 1).min(len)
+    };
+
+    let mut labels = vec![];
+    if files.contains(diagnostic.span.id) {
+        labels.push(Label::primary(diagnostic.span.id, range(diagnostic.span)));
+    }
+
+    for label in &diagnostic.labels {
+        if files.contains(label.span.id) {
+            labels.push(
+                Label::secondary(label.span.id, range(label.span)).with_message(&label.message),
+            );
+        }
+    }
+
+    let mut notes = diagnostic.notes.clone();
+    if let Some(help) = &diagnostic.help {
+        notes.push(format!("help: {help}"));
+    }
+
+    output.with_labels(labels).with_notes(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{ByteRange, SourceMap, Span};
+    use bl_diagnostics::{Applicability, Diagnostic, DiagnosticKind, Diagnostics, Edit, Fix};
+    use bl_utils::stream::CompilerOutputStream;
+
+    use super::TextEmitter;
+    use crate::message::Emitter;
+
+    fn render(diagnostics: &Diagnostics) -> String {
+        let mut stream = CompilerOutputStream::owned();
+        TextEmitter::new().with_colours(false).emit(&mut stream, diagnostics).unwrap();
+
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = output.lock().unwrap();
+        String::from_utf8(output.clone()).unwrap()
+    }
+
+    #[test]
+    fn render_diagnostic() {
+        let id = SourceMap::add(
+            "index.html",
+            "{% block a %}{% endblock %}\n{% block a %}{% endblock %}\n",
+        );
+        let first = Span::new(ByteRange::new(0, 12), id);
+        let second = Span::new(ByteRange::new(28, 40), id);
+
+        let diagnostics: Diagnostics =
+            [Diagnostic::new(DiagnosticKind::Error, "block `a` is defined more than once", second)
+                .with_code("BL001")
+                .with_label(first, "block `a` is first defined here")
+                .with_help("rename or remove one of the blocks")
+                .with_fix(Fix::safe_edits([Edit::delete(ByteRange::new(28, 54))]))]
+            .into_iter()
+            .collect();
+
+        let expected = "\
+error[BL001]: block `a` is defined more than once
+  ┌─ index.html:2:1
+  │
+1 │ {% block a %}{% endblock %}
+  │ ------------- block `a` is first defined here
+2 │ {% block a %}{% endblock %}
+  │ ^^^^^^^^^^^^^
+  │
+  = help: rename or remove one of the blocks
+
+Found 1 error (1 fixable).
+";
+        assert_eq!(render(&diagnostics), expected);
+        assert_eq!(render(&Diagnostics::new()), "All checks passed!\n");
+
+        // Unsafe fixes aren't fixable by default, but a hint is shown.
+        let diagnostics: Diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                let fix = diagnostic.fix.clone().unwrap();
+                diagnostic.with_fix(fix.with_applicability(Applicability::Unsafe))
+            })
+            .collect();
+        assert!(render(&diagnostics).ends_with(
+            "Found 1 error.\n1 hidden fix can be enabled with the `--unsafe-fixes` option.\n"
+        ));
+    }
+
+    #[test]
+    fn summary_counts_each_kind() {
+        let id = SourceMap::add("summary.html", "{% break %}");
+        let diagnostic = |kind| {
+            Diagnostic::new(kind, "message", Span::new(ByteRange::new(0, 10), id))
+                .with_code("BL002")
+        };
+        let diagnostics: Diagnostics = [
+            diagnostic(DiagnosticKind::Error),
+            diagnostic(DiagnosticKind::Warning),
+            diagnostic(DiagnosticKind::Warning),
+            diagnostic(DiagnosticKind::Note),
+        ]
+        .into_iter()
+        .collect();
+
+        assert!(render(&diagnostics).ends_with("Found 1 error, 2 warnings and 1 note.\n"));
+
+        let warnings: Diagnostics = diagnostics.iter().skip(1).take(2).cloned().collect();
+        assert!(render(&warnings).ends_with("Found 2 warnings.\n"));
+
+        let mut stream = CompilerOutputStream::owned();
+        let mut emitter = TextEmitter::new().with_colours(false).with_fixed(3);
+        emitter.emit(&mut stream, &warnings).unwrap();
+        let CompilerOutputStream::Owned(output) = stream else { unreachable!() };
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert!(output.ends_with("Found 5 violations (3 fixed, 2 warnings remaining).\n"));
+    }
+}
@@ -1 +1,179 @@
+//! The registry of all of the rules that are available.
 
+use std::{fmt, str::FromStr};
+
+use anyhow::bail;
+use bl_utils::suggest::did_you_mean;
+
+use crate::{
+    rule::{Rule, RuleCategory},
+    rules::{DuplicateBlock, LoopControlOutsideLoop, UnusedSuppression},
+};
+
+/// All of the rules that are available, ordered by their code.
+pub static RULES: &[&dyn Rule] = &[&DuplicateBlock, &LoopControlOutsideLoop, &UnusedSuppression];
+
+/// Iterate over all of the rules that are available.
+pub fn all_rules() -> impl Iterator<Item = &'static dyn Rule> {
+    RULES.iter().copied()
+}
+
+/// Find a rule by its code, i.e. `BL001`.
+pub fn rule_by_code(code: &str) -> Option<&'static dyn Rule> {
+    all_rules().find(|rule| rule.code() == code)
+}
+
+/// Find a rule by its name, i.e. `duplicate-block`.
+pub fn rule_by_name(name: &str) -> Option<&'static dyn Rule> {
+    all_rules().find(|rule| rule.name() == name)
+}
+
+/// The number of digits after the `BL` of each rule code.
+const CODE_DIGITS: usize = 3;
+
+/// All of the rule categories, in the order that they are defined.
+const CATEGORIES: &[RuleCategory] =
+    &[RuleCategory::Correctness, RuleCategory::Security, RuleCategory::Style, RuleCategory::A11y];
+
+/// Selects a group of rules, i.e. when enabling or ignoring rules. A selector
+/// is either `ALL`, a category, a prefix of a rule code or the name of a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RuleSelector {
+    /// Every rule.
+    All,
+
+    /// All of the rules within a category, i.e. `correctness`.
+    Category(RuleCategory),
+
+    /// All of the rules whose code starts with the prefix, i.e. `BL0`.
+    Prefix(String),
+
+    /// A single rule, selected by its name.
+    Rule(&'static str),
+}
+
+impl RuleSelector {
+    /// Check whether the selector matches the given rule.
+    pub fn matches(&self, rule: &dyn Rule) -> bool {
+        match self {
+            RuleSelector::All => true,
+            RuleSelector::Category(category) => rule.category() == *category,
+            RuleSelector::Prefix(prefix) => rule.code().starts_with(prefix.as_str()),
+            RuleSelector::Rule(name) => rule.name() == *name,
+        }
+    }
+
+    /// How specific the selector is, when a rule is matched by selectors that
+    /// both enable and disable it, the most specific selector wins. A full
+    /// code or a rule name is the most specific, followed by longer prefixes,
+    /// categories and then `ALL`.
+    pub fn specificity(&self) -> usize {
+        match self {
+            RuleSelector::All => 0,
+            RuleSelector::Category(_) => 1,
+            RuleSelector::Prefix(prefix) => 1 + prefix.len(),
+            RuleSelector::Rule(name) => 1 + rule_by_name(name).map_or(0, |rule| rule.code().len()),
+        }
+    }
+
+    /// Iterate over all of the rules that the selector matches.
+    pub fn rules(&self) -> impl Iterator<Item = &'static dyn Rule> + '_ {
+        all_rules().filter(move |rule| self.matches(*rule))
+    }
+}
+
+impl FromStr for RuleSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "ALL" {
+            return Ok(RuleSelector::All);
+        }
+
+        if let Some(category) = CATEGORIES.iter().find(|category| category.as_str() == value) {
+            return Ok(RuleSelector::Category(*category));
+        }
+
+        if let Some(rule) = rule_by_name(value) {
+            return Ok(RuleSelector::Rule(rule.name()));
+        }
+
+        // Any prefix of a code is valid, even if no rule has that code yet, so
+        // that configurations keep working as rules are added.
+        if let Some(digits) = value.strip_prefix("BL") {
+            if digits.len() <= CODE_DIGITS && digits.chars().all(|c| c.is_ascii_digit()) {
+                return Ok(RuleSelector::Prefix(value.to_string()));
+            }
+        }
+
+        let candidates = all_rules()
+            .flat_map(|rule| [rule.code(), rule.name()])
+            .chain(CATEGORIES.iter().map(RuleCategory::as_str))
+            .chain(["ALL"]);
+
+        match did_you_mean(value, candidates) {
+            Some(suggestion) => {
+                bail!("unknown rule selector `{value}`, did you mean `{suggestion}`?")
+            }
+            None => bail!("unknown rule selector `{value}`"),
+        }
+    }
+}
+
+impl fmt::Display for RuleSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleSelector::All => write!(f, "ALL"),
+            RuleSelector::Category(category) => write!(f, "{category}"),
+            RuleSelector::Prefix(prefix) => write!(f, "{prefix}"),
+            RuleSelector::Rule(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RuleSelector, CODE_DIGITS, RULES};
+
+    #[test]
+    fn rules_are_ordered_and_unique() {
+        for pair in RULES.windows(2) {
+            assert!(pair[0].code() < pair[1].code(), "{:?} >= {:?}", pair[0], pair[1]);
+        }
+
+        for rule in RULES {
+            let code = rule.code();
+            assert!(
+                code.len() == 2 + CODE_DIGITS && code.starts_with("BL"),
+                "invalid code: {code}"
+            );
+            assert_eq!(RULES.iter().filter(|other| other.name() == rule.name()).count(), 1);
+        }
+    }
+
+    #[test]
+    fn parse_selectors() {
+        let codes = |selector: &str| {
+            let selector: RuleSelector = selector.parse().unwrap();
+            selector.rules().map(|rule| rule.code()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(codes("ALL"), ["BL001", "BL002", "BL003"]);
+        assert_eq!(codes("BL00"), ["BL001", "BL002", "BL003"]);
+        assert_eq!(codes("BL002"), ["BL002"]);
+        assert_eq!(codes("duplicate-block"), ["BL001"]);
+        assert_eq!(codes("correctness"), ["BL001", "BL002"]);
+
+        let error = "duplicate-blocks".parse::<RuleSelector>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown rule selector `duplicate-blocks`, did you mean `duplicate-block`?"
+        );
+
+        // Prefixes of codes that don't exist yet are valid, but match nothing.
+        assert_eq!(codes("BL1"), [] as [&str; 0]);
+        assert_eq!(codes("BL999"), [] as [&str; 0]);
+        assert!("BL0001".parse::<RuleSelector>().is_err());
+        assert!("BLX".parse::<RuleSelector>().is_err());
+    }
+}
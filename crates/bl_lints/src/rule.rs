@@ -0,0 +1,109 @@
+//! Definition of the [Rule] trait, which every lint implements.
+
+use std::fmt;
+
+use bl_ast::{AstNodeRef, Document};
+use bl_diagnostics::DiagnosticKind;
+
+use crate::context::LintContext;
+
+/// The category of a [Rule], this is used to group rules together when
+/// selecting which rules should run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RuleCategory {
+    /// Code that is most likely wrong, or will fail at render time.
+    Correctness,
+
+    /// Code that might be exploitable, i.e. disabling auto-escaping.
+    Security,
+
+    /// Code that is valid, but is not idiomatic or consistent.
+    Style,
+
+    /// Markup that is not accessible.
+    A11y,
+}
+
+impl RuleCategory {
+    /// Get the name of the category.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleCategory::Correctness => "correctness",
+            RuleCategory::Security => "security",
+            RuleCategory::Style => "style",
+            RuleCategory::A11y => "a11y",
+        }
+    }
+}
+
+impl fmt::Display for RuleCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Whether a [Rule] is able to provide a fix for the violations that it
+/// reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FixAvailability {
+    /// A fix is provided for every violation.
+    Always,
+
+    /// A fix is provided for some violations.
+    Sometimes,
+
+    /// The rule never provides a fix.
+    None,
+}
+
+/// The location at which the documentation of each rule is published, the
+/// documentation of a rule is found at `{RULES_URL}/{name}`.
+pub const RULES_URL: &str = "https://bracketlint.dev/rules";
+
+/// A lint rule. Each rule is a unit struct which is registered within the
+/// [`crate::registry::RULES`] registry.
+///
+/// Rules should implement the [`Rule::check`] function with a visitor over
+/// the [Document], reporting any violations through the [LintContext].
+pub trait Rule: Send + Sync {
+    /// The unique code of the rule, i.e. `BL001`.
+    fn code(&self) -> &'static str;
+
+    /// The name of the rule in kebab-case, i.e. `duplicate-block`.
+    fn name(&self) -> &'static str;
+
+    /// A short, single sentence, description of what the rule checks for.
+    fn description(&self) -> &'static str;
+
+    /// The category of the rule.
+    fn category(&self) -> RuleCategory;
+
+    /// The severity that diagnostics from this rule are reported with, unless
+    /// overridden by the user.
+    fn default_severity(&self) -> DiagnosticKind;
+
+    /// Whether the rule is still in preview, preview rules are only run when
+    /// preview mode is enabled.
+    fn preview(&self) -> bool {
+        false
+    }
+
+    /// Whether the rule is able to fix the violations that it reports.
+    fn fix_availability(&self) -> FixAvailability {
+        FixAvailability::None
+    }
+
+    /// The URL of the documentation of the rule.
+    fn url(&self) -> String {
+        format!("{RULES_URL}/{}", self.name())
+    }
+
+    /// Check the given [Document] for violations of this rule.
+    fn check(&self, document: AstNodeRef<Document>, context: &mut LintContext);
+}
+
+impl fmt::Debug for dyn Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.code(), self.name())
+    }
+}
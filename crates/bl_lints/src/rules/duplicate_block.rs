@@ -0,0 +1,111 @@
+//! `BL001`: a block with the same name is defined more than once.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::Infallible,
+};
+
+use bl_ast::{
+    ast_visitor_mut_self_default_impl, visitor::walk_mut_self, AstNodeRef, AstVisitorMutSelf,
+    Block, Document, Identifier, Span,
+};
+use bl_diagnostics::DiagnosticKind;
+
+use crate::{
+    context::LintContext,
+    rule::{Rule, RuleCategory},
+};
+
+/// Jinja will refuse to render a template that defines a block with the same
+/// name more than once, i.e.
+///
+/// ```html
+/// {% block content %}{% endblock %}
+/// {% block content %}{% endblock %}
+/// ```
+pub(crate) struct DuplicateBlock;
+
+impl Rule for DuplicateBlock {
+    fn code(&self) -> &'static str {
+        "BL001"
+    }
+
+    fn name(&self) -> &'static str {
+        "duplicate-block"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks for blocks that are defined more than once within a template."
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+
+    fn default_severity(&self) -> DiagnosticKind {
+        DiagnosticKind::Error
+    }
+
+    fn check(&self, document: AstNodeRef<Document>, context: &mut LintContext) {
+        let mut visitor = DuplicateBlockVisitor { context, blocks: HashMap::new() };
+        visitor.visit_document(document).unwrap();
+    }
+}
+
+struct DuplicateBlockVisitor<'c, 's> {
+    context: &'c mut LintContext<'s>,
+
+    /// The names of all of the blocks that have been seen, and the span of the
+    /// first definition.
+    blocks: HashMap<Identifier, Span>,
+}
+
+impl AstVisitorMutSelf for DuplicateBlockVisitor<'_, '_> {
+    type Error = Infallible;
+
+    ast_visitor_mut_self_default_impl!(hiding: Block);
+
+    type BlockRet = ();
+    fn visit_block(&mut self, node: AstNodeRef<Block>) -> Result<Self::BlockRet, Self::Error> {
+        if let Some(label) = &node.label {
+            let name = label.body.data;
+
+            match self.blocks.entry(name) {
+                Entry::Occupied(entry) => {
+                    let message = format!("block `{name}` is defined multiple times");
+                    let diagnostic = self
+                        .context
+                        .diagnostic(message, label.span())
+                        .with_label(*entry.get(), format!("block `{name}` is first defined here"))
+                        .with_help("rename one of the blocks, or remove the duplicate");
+                    self.context.push(diagnostic);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(label.span());
+                }
+            }
+        }
+
+        let _ = walk_mut_self::walk_block(self, node)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DuplicateBlock;
+    use crate::test_utils::check;
+
+    #[test]
+    fn duplicate_block() {
+        let diagnostics = check(
+            &DuplicateBlock,
+            "{% block a %}{% block b %}{% endblock %}{% endblock %}{% block b %}{% endblock %}",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "block `b` is defined multiple times");
+        assert_eq!(diagnostics[0].span.range.start(), 63);
+        assert_eq!(diagnostics[0].labels[0].span.range.start(), 22);
+    }
+}
@@ -0,0 +1,156 @@
+//! `BL002`: a `continue` or `break` tag is used outside of a `for` loop, or
+//! in a dialect that doesn't support them.
+
+use std::convert::Infallible;
+
+use bl_ast::{
+    ast_visitor_mut_self_default_impl, visitor::walk_mut_self, AstNodeId, AstNodeRef,
+    AstVisitorMutSelf, Break, Continue, Document, For, MacroDef,
+};
+use bl_diagnostics::DiagnosticKind;
+
+use crate::{
+    context::LintContext,
+    rule::{Rule, RuleCategory},
+};
+
+/// The `continue` and `break` tags are only valid within the body of a `for`
+/// loop, i.e.
+///
+/// ```html
+/// {% if done %}{% break %}{% endif %}
+/// ```
+///
+/// Only the Jinja and Liquid dialects support loop controls, so they are
+/// reported wherever they appear in the other dialects.
+pub(crate) struct LoopControlOutsideLoop;
+
+impl Rule for LoopControlOutsideLoop {
+    fn code(&self) -> &'static str {
+        "BL002"
+    }
+
+    fn name(&self) -> &'static str {
+        "loop-control-outside-loop"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks for `continue` and `break` tags outside of a `for` loop."
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Correctness
+    }
+
+    fn default_severity(&self) -> DiagnosticKind {
+        DiagnosticKind::Error
+    }
+
+    fn check(&self, document: AstNodeRef<Document>, context: &mut LintContext) {
+        let mut visitor = LoopControlVisitor { context, depth: 0 };
+        visitor.visit_document(document).unwrap();
+    }
+}
+
+struct LoopControlVisitor<'c, 's> {
+    context: &'c mut LintContext<'s>,
+
+    /// The number of loop bodies that the visitor is currently within.
+    depth: usize,
+}
+
+impl LoopControlVisitor<'_, '_> {
+    /// Report the given tag if it is not within a loop, or the dialect doesn't
+    /// support it.
+    fn check_tag(&mut self, tag: &str, id: AstNodeId) {
+        let dialect = self.context.dialect();
+        if !dialect.has_loop_controls() {
+            self.context
+                .report(format!("`{tag}` is not supported by the {dialect} dialect"), id.span());
+        } else if self.depth == 0 {
+            self.context.report(format!("`{tag}` used outside of a `for` loop"), id.span());
+        }
+    }
+}
+
+impl AstVisitorMutSelf for LoopControlVisitor<'_, '_> {
+    type Error = Infallible;
+
+    ast_visitor_mut_self_default_impl!(hiding: For, MacroDef, Continue, Break);
+
+    type ForRet = ();
+    fn visit_for(&mut self, node: AstNodeRef<For>) -> Result<Self::ForRet, Self::Error> {
+        self.visit_expr(node.iterable.ast_ref())?;
+
+        self.depth += 1;
+        self.visit_body(node.loop_body.ast_ref())?;
+        self.depth -= 1;
+
+        // The `else` branch of a loop is run when there are no items, so it is not
+        // part of the loop.
+        if let Some(loop_empty) = &node.loop_empty {
+            self.visit_body(loop_empty.ast_ref())?;
+        }
+
+        Ok(())
+    }
+
+    type MacroDefRet = ();
+    fn visit_macro_def(
+        &mut self,
+        node: AstNodeRef<MacroDef>,
+    ) -> Result<Self::MacroDefRet, Self::Error> {
+        // Macros are separate scopes, so a loop outside of the macro does not apply.
+        let depth = std::mem::take(&mut self.depth);
+        let _ = walk_mut_self::walk_macro_def(self, node)?;
+        self.depth = depth;
+        Ok(())
+    }
+
+    type ContinueRet = ();
+    fn visit_continue(
+        &mut self,
+        node: AstNodeRef<Continue>,
+    ) -> Result<Self::ContinueRet, Self::Error> {
+        self.check_tag("continue", node.id());
+        Ok(())
+    }
+
+    type BreakRet = ();
+    fn visit_break(&mut self, node: AstNodeRef<Break>) -> Result<Self::BreakRet, Self::Error> {
+        self.check_tag("break", node.id());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LoopControlOutsideLoop;
+    use crate::{
+        settings::Dialect,
+        test_utils::{check, check_dialect},
+    };
+
+    #[test]
+    fn loop_control_outside_loop() {
+        let source = "{% for x in xs %}{% if x %}{% break %}{% endif %}{% else %}{% continue %}\
+                      {% endfor %}{% macro m() %}{% for y in ys %}{% endfor %}{% break %}{% endmacro %}";
+        let diagnostics = check(&LoopControlOutsideLoop, source);
+        let messages = diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            ["`continue` used outside of a `for` loop", "`break` used outside of a `for` loop"]
+        );
+    }
+
+    #[test]
+    fn loop_control_unsupported_dialect() {
+        let source = "{% for x in xs %}{% break %}{% endfor %}";
+        assert!(check_dialect(&LoopControlOutsideLoop, source, Dialect::Liquid).is_empty());
+
+        let diagnostics = check_dialect(&LoopControlOutsideLoop, source, Dialect::Twig);
+        let messages = diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, ["`break` is not supported by the twig dialect"]);
+    }
+}
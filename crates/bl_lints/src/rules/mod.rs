@@ -0,0 +1,10 @@
+//! All of the rules that are implemented, each rule lives within its own
+//! module and is registered in [`crate::registry::RULES`].
+
+mod duplicate_block;
+mod loop_control_outside_loop;
+mod unused_suppression;
+
+pub(crate) use duplicate_block::DuplicateBlock;
+pub(crate) use loop_control_outside_loop::LoopControlOutsideLoop;
+pub(crate) use unused_suppression::UnusedSuppression;
@@ -0,0 +1,50 @@
+//! `BL003`: a suppression directive doesn't suppress any violations.
+
+use bl_ast::{AstNodeRef, Document};
+use bl_diagnostics::DiagnosticKind;
+
+use crate::{
+    context::LintContext,
+    rule::{FixAvailability, Rule, RuleCategory},
+};
+
+/// A suppression directive that doesn't suppress any violations is either
+/// stale, or refers to the wrong rule or line, i.e.
+///
+/// ```html
+/// {# bl: ignore[BL002] #}
+/// {% for item in items %}{% break %}{% endfor %}
+/// ```
+///
+/// ##Note: the violations of this rule are reported by
+/// [`crate::suppression`] once all of the other rules have been run, since it
+/// depends on what they reported.
+pub(crate) struct UnusedSuppression;
+
+impl Rule for UnusedSuppression {
+    fn code(&self) -> &'static str {
+        "BL003"
+    }
+
+    fn name(&self) -> &'static str {
+        "unused-suppression"
+    }
+
+    fn description(&self) -> &'static str {
+        "Checks for suppression directives that don't suppress any violations."
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Style
+    }
+
+    fn default_severity(&self) -> DiagnosticKind {
+        DiagnosticKind::Warning
+    }
+
+    fn fix_availability(&self) -> FixAvailability {
+        FixAvailability::Sometimes
+    }
+
+    fn check(&self, _: AstNodeRef<Document>, _: &mut LintContext) {}
+}
@@ -1,9 +1,12 @@
 //! Various structures and options for the linting system.
 
+use bl_diagnostics::{Applicability, DiagnosticKind};
+pub use bl_parse::dialect::Dialect;
+
 /// When a lint option is detected, this is the method of communicating the fix,
 /// whether it is simply displaying the fix, applying it to the file, or
 /// generating a new file with the fix.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum FixMode {
     #[default]
     Diff,
@@ -12,3 +15,67 @@ pub enum FixMode {
 
     Apply,
 }
+
+/// Whether unsafe fixes are applied, by default they are not applied but a
+/// hint is shown when there are unsafe fixes available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UnsafeFixes {
+    /// Unsafe fixes are not applied, but the number of them is shown.
+    #[default]
+    Hint,
+
+    /// Unsafe fixes are applied alongside safe fixes.
+    Enabled,
+
+    /// Unsafe fixes are not applied, and no hint is shown.
+    Disabled,
+}
+
+impl UnsafeFixes {
+    /// Get the applicability that a fix must have to be applied.
+    pub fn required_applicability(self) -> Applicability {
+        match self {
+            UnsafeFixes::Enabled => Applicability::Unsafe,
+            UnsafeFixes::Hint | UnsafeFixes::Disabled => Applicability::Safe,
+        }
+    }
+}
+
+impl From<bool> for UnsafeFixes {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            UnsafeFixes::Enabled
+        } else {
+            UnsafeFixes::Disabled
+        }
+    }
+}
+
+/// The severity of the violations of a rule, which overrides the default
+/// severity of the rule. Rules that are `off` are not run at all.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Error,
+
+    Warning,
+
+    Info,
+
+    Off,
+}
+
+impl Severity {
+    /// Get the [DiagnosticKind] that violations are reported with, or `None`
+    /// if the rule is turned off.
+    pub fn kind(self) -> Option<DiagnosticKind> {
+        match self {
+            Severity::Error => Some(DiagnosticKind::Error),
+            Severity::Warning => Some(DiagnosticKind::Warning),
+            Severity::Info => Some(DiagnosticKind::Note),
+            Severity::Off => None,
+        }
+    }
+}
@@ -0,0 +1,559 @@
+//! Suppression directives, which silence the violations of rules from within
+//! template comments, i.e.
+//!
+//! ```html
+//! {# bl: ignore[BL002] #}
+//! {% break %}
+//! {% break %} {# bl: ignore[loop-control-outside-loop] #}
+//!
+//! {# bl: disable[BL001, BL002] #}
+//! ...
+//! {# bl: enable[BL001, BL002] #}
+//!
+//! {# bl: disable-file[BL001] #}
+//! {# bl: disable-file=BL001, BL002 #}
+//! ```
+//!
+//! An `ignore` directive that is on its own line applies to the next line,
+//! otherwise it applies to the line that it is on. A `disable` directive
+//! applies until the next `enable` directive of the same rule, or until the
+//! end of the file. Rules are referred to by their code or name, and are listed
+//! either in brackets or after an `=`. Directives without any rules apply to
+//! every rule.
+
+use std::{collections::HashMap, convert::Infallible, iter};
+
+use bl_ast::{
+    ast_visitor_mut_self_default_impl, AstNodeRef, AstVisitorMutSelf, ByteRange, Comment, Document,
+    Span,
+};
+use bl_diagnostics::{Diagnostic, Edit, Fix};
+
+use crate::{
+    registry::{rule_by_code, rule_by_name},
+    rule::Rule,
+    rules::UnusedSuppression,
+};
+
+/// The prefix of the contents of a comment that marks it as a directive.
+pub const PREFIX: &str = "bl:";
+
+/// The kinds of suppression directive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    /// `ignore[...]`, which suppresses violations on a single line.
+    Ignore,
+
+    /// `disable[...]`, which suppresses violations until the matching
+    /// `enable[...]`.
+    Disable,
+
+    /// `enable[...]`, which ends a `disable[...]` region.
+    Enable,
+
+    /// `disable-file[...]`, which suppresses violations in the whole file.
+    DisableFile,
+}
+
+impl DirectiveKind {
+    /// Get the name of the directive, as it is written in a comment.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DirectiveKind::Ignore => "ignore",
+            DirectiveKind::Disable => "disable",
+            DirectiveKind::Enable => "enable",
+            DirectiveKind::DisableFile => "disable-file",
+        }
+    }
+}
+
+/// A rule that is referred to by a [Directive].
+#[derive(Debug, Clone)]
+pub struct DirectiveCode {
+    /// The code or name of the rule, as it is written.
+    pub text: String,
+
+    /// The code of the rule, or `None` if there is no such rule.
+    pub rule: Option<&'static str>,
+}
+
+/// A suppression directive within a comment.
+#[derive(Debug, Clone)]
+pub struct Directive {
+    /// The kind of the directive.
+    pub kind: DirectiveKind,
+
+    /// The range of the comment that contains the directive.
+    pub range: ByteRange,
+
+    /// The rules that the directive refers to, an empty list refers to every
+    /// rule.
+    pub codes: Vec<DirectiveCode>,
+
+    /// The offsets of the list of rules within the source, the end is
+    /// exclusive. This is `None` if the directive has no list.
+    pub list: Option<(usize, usize)>,
+
+    /// The line that an `ignore` directive applies to, starting from zero.
+    pub line: usize,
+
+    /// Whether the directive is the only thing on its line.
+    pub own_line: bool,
+}
+
+impl Directive {
+    /// Check whether the directive refers to the rule with the given code.
+    pub fn applies_to(&self, code: &str) -> bool {
+        self.codes.is_empty() || self.codes.iter().any(|directive| directive.rule == Some(code))
+    }
+}
+
+/// A region of the template in which the violations of a rule are suppressed
+/// by a `disable[...]` directive.
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    /// The index of the `disable` directive.
+    index: usize,
+
+    /// The code of the rule, or `None` for every rule.
+    code: Option<&'static str>,
+
+    /// The offsets of the region, both exclusive. The region continues until
+    /// the end of the file if there is no matching `enable` directive.
+    start: usize,
+    end: Option<usize>,
+}
+
+/// All of the suppression directives within a template.
+#[derive(Debug, Default)]
+pub struct Suppressions {
+    /// The directives, in the order that they appear.
+    pub directives: Vec<Directive>,
+
+    /// Comments which begin with [PREFIX] but aren't valid directives, along
+    /// with a description of the problem.
+    pub invalid: Vec<(ByteRange, String)>,
+
+    /// The offset at which each line of the template begins.
+    line_starts: Vec<usize>,
+
+    /// The indices of the `ignore` directives that apply to each line.
+    ignores: HashMap<usize, Vec<usize>>,
+
+    /// The indices of the `disable-file` directives.
+    files: Vec<usize>,
+
+    /// The regions of the `disable` directives.
+    regions: Vec<Region>,
+}
+
+impl Suppressions {
+    /// Find all of the directives within the comments of the given document.
+    pub fn parse(document: AstNodeRef<Document>, source: &str) -> Self {
+        let mut collector = CommentCollector { comments: vec![] };
+        collector.visit_document(document).unwrap();
+        collector.comments.sort_by_key(|range| range.start());
+
+        let line_starts =
+            iter::once(0).chain(source.match_indices('\n').map(|(offset, _)| offset + 1)).collect();
+        let mut suppressions = Suppressions { line_starts, ..Suppressions::default() };
+
+        for range in collector.comments {
+            suppressions.parse_comment(range, source);
+        }
+
+        suppressions.index();
+        suppressions
+    }
+
+    /// Index the directives by the lines and regions that they apply to, so
+    /// that each violation only needs to be checked against the directives
+    /// that could suppress it.
+    fn index(&mut self) {
+        for (index, directive) in self.directives.iter().enumerate() {
+            match directive.kind {
+                DirectiveKind::Ignore => {
+                    self.ignores.entry(directive.line).or_default().push(index);
+                }
+                DirectiveKind::DisableFile => self.files.push(index),
+                DirectiveKind::Enable => {}
+                DirectiveKind::Disable => {
+                    let codes: Vec<_> = match directive.codes.as_slice() {
+                        [] => vec![None],
+                        codes => codes.iter().filter_map(|code| code.rule).map(Some).collect(),
+                    };
+
+                    // A region of every rule is only ended by enabling every rule.
+                    let following = &self.directives[index + 1..];
+                    for code in codes {
+                        let enable = following.iter().find(|enable| {
+                            enable.kind == DirectiveKind::Enable
+                                && (enable.codes.is_empty()
+                                    || code.is_some_and(|code| enable.applies_to(code)))
+                        });
+
+                        self.regions.push(Region {
+                            index,
+                            code,
+                            start: directive.range.end(),
+                            end: enable.map(|enable| enable.range.start()),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the line that the given offset is on, starting from zero.
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset).saturating_sub(1)
+    }
+
+    /// Parse the comment at the given range, if it is a directive.
+    ///
+    /// ##Note: the delimiters of comments are configurable, so rather than
+    /// stripping the delimiters, any leading and trailing punctuation is
+    /// ignored, i.e. `{#- bl: ignore #}` or `<# bl: ignore #>`.
+    fn parse_comment(&mut self, range: ByteRange, source: &str) {
+        let text = &source[range.start()..=range.end()];
+        let contents = text.trim_start_matches(|c: char| !c.is_alphanumeric());
+        let Some(body) = contents.strip_prefix(PREFIX) else {
+            return;
+        };
+
+        let body = body.trim_end_matches(|c: char| !c.is_alphanumeric() && !matches!(c, ']' | '='));
+        let trimmed = body.trim_start();
+        let offset = range.start()
+            + (text.len() - contents.len())
+            + PREFIX.len()
+            + (body.len() - trimmed.len());
+
+        let (kind, codes, list) = match parse_directive(trimmed, offset) {
+            Ok(directive) => directive,
+            Err(message) => {
+                self.invalid.push((range, message));
+                return;
+            }
+        };
+
+        // An `ignore` on its own line applies to the following line.
+        let line = self.line_of(range.start());
+        let before = &source[self.line_starts[line]..range.start()];
+        let after = source[range.end() + 1..].split('\n').next().unwrap_or("");
+        let own_line = before.trim().is_empty() && after.trim().is_empty();
+        let line = if own_line { self.line_of(range.end()) + 1 } else { line };
+
+        self.directives.push(Directive { kind, range, codes, list, line, own_line });
+    }
+
+    /// Get the range that is removed when deleting the given [Directive]. A
+    /// directive on its own line is removed along with the whole line.
+    fn removal_range(&self, directive: &Directive, source: &str) -> ByteRange {
+        if !directive.own_line {
+            return directive.range;
+        }
+
+        let start = self.line_starts[self.line_of(directive.range.start())];
+        let end = source[directive.range.end()..]
+            .find('\n')
+            .map_or(source.len() - 1, |newline| directive.range.end() + newline);
+        ByteRange::new(start, end)
+    }
+
+    /// Get the indices of the directives that suppress a violation of the
+    /// rule with the given code that begins at `offset`.
+    fn suppressing<'a>(&'a self, code: &'a str, offset: usize) -> impl Iterator<Item = usize> + 'a {
+        let ignores = self.ignores.get(&self.line_of(offset)).into_iter().flatten();
+        let lines = ignores.chain(&self.files).copied();
+
+        let regions = self
+            .regions
+            .iter()
+            .filter(move |region| {
+                region.code.map_or(true, |rule| rule == code)
+                    && offset > region.start
+                    && region.end.map_or(true, |end| offset < end)
+            })
+            .map(|region| region.index);
+
+        lines.filter(move |index| self.directives[*index].applies_to(code)).chain(regions)
+    }
+}
+
+/// Collects the ranges of all of the comments within a document.
+struct CommentCollector {
+    comments: Vec<ByteRange>,
+}
+
+impl AstVisitorMutSelf for CommentCollector {
+    type Error = Infallible;
+
+    ast_visitor_mut_self_default_impl!(hiding: Comment);
+
+    type CommentRet = ();
+    fn visit_comment(
+        &mut self,
+        node: AstNodeRef<Comment>,
+    ) -> Result<Self::CommentRet, Self::Error> {
+        self.comments.push(node.span().range);
+        Ok(())
+    }
+}
+
+/// The kind, rules and list offsets of a parsed [Directive].
+type ParsedDirective = (DirectiveKind, Vec<DirectiveCode>, Option<(usize, usize)>);
+
+/// Parse the body of a directive, i.e. `ignore[BL001, BL002]` or
+/// `ignore=BL001, BL002`, that begins at the given offset. Returns the kind of
+/// the directive, the rules that it refers to and the offsets of the list of
+/// rules.
+fn parse_directive(body: &str, offset: usize) -> Result<ParsedDirective, String> {
+    let name_end = body.find(|c: char| c == '[' || c == '=' || c.is_whitespace());
+    let (name, rest) = body.split_at(name_end.unwrap_or(body.len()));
+
+    let kind = match name {
+        "ignore" => DirectiveKind::Ignore,
+        "disable" => DirectiveKind::Disable,
+        "enable" => DirectiveKind::Enable,
+        "disable-file" => DirectiveKind::DisableFile,
+        "" => return Err("expected a suppression directive".to_string()),
+        _ => return Err(format!("unknown suppression directive `{name}`")),
+    };
+
+    let trimmed = rest.trim_start();
+    if trimmed.is_empty() {
+        return Ok((kind, vec![], None));
+    }
+
+    let list = match trimmed.strip_prefix('[') {
+        Some(list) => list.strip_suffix(']'),
+        None => trimmed.strip_prefix('='),
+    };
+    let Some(list) = list else {
+        return Err(format!("expected a list of rules in brackets or after `=` after `{name}`"));
+    };
+
+    let start = offset + name.len() + (rest.len() - trimmed.len()) + 1;
+    let codes: Vec<_> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(|code| DirectiveCode {
+            text: code.to_string(),
+            rule: rule_by_code(code).or_else(|| rule_by_name(code)).map(|rule| rule.code()),
+        })
+        .collect();
+
+    if codes.is_empty() {
+        return Err(format!("expected at least one rule after `{name}`"));
+    }
+
+    Ok((kind, codes, Some((start, start + list.len()))))
+}
+
+/// Remove all of the diagnostics that are suppressed by a directive within the
+/// document. If the [UnusedSuppression] rule is enabled, then any directives
+/// that don't suppress anything are reported.
+pub(crate) fn suppress(
+    diagnostics: Vec<Diagnostic>,
+    document: AstNodeRef<Document>,
+    source: &str,
+    rules: &[&'static dyn Rule],
+) -> Vec<Diagnostic> {
+    let suppressions = Suppressions::parse(document, source);
+    if suppressions.directives.is_empty() && suppressions.invalid.is_empty() {
+        return diagnostics;
+    }
+
+    // Whether each rule of each directive has suppressed anything, directives
+    // of every rule have a single entry.
+    let mut used: Vec<Vec<bool>> = suppressions
+        .directives
+        .iter()
+        .map(|directive| vec![false; directive.codes.len().max(1)])
+        .collect();
+
+    let mut remaining = vec![];
+    for diagnostic in diagnostics {
+        let Some(code) = diagnostic.code else {
+            remaining.push(diagnostic);
+            continue;
+        };
+
+        let mut suppressed = false;
+        for index in suppressions.suppressing(code, diagnostic.span.range.start()) {
+            let directive = &suppressions.directives[index];
+            suppressed = true;
+            if directive.codes.is_empty() {
+                used[index][0] = true;
+            }
+            for (position, directive_code) in directive.codes.iter().enumerate() {
+                if directive_code.rule == Some(code) {
+                    used[index][position] = true;
+                }
+            }
+        }
+
+        if !suppressed {
+            remaining.push(diagnostic);
+        }
+    }
+
+    let rule = &UnusedSuppression;
+    if !rules.iter().any(|enabled| enabled.code() == rule.code()) {
+        return remaining;
+    }
+
+    let enabled =
+        |code: Option<&str>| code.map_or(true, |code| rules.iter().any(|rule| rule.code() == code));
+    let span = |range| Span::new(range, document.span().id);
+    let diagnostic = |message: String, range| {
+        Diagnostic::new(rule.default_severity(), message, span(range)).with_code(rule.code())
+    };
+    let removal = |directive| suppressions.removal_range(directive, source);
+
+    let mut unused = vec![];
+    for (directive, used) in suppressions.directives.iter().zip(&used) {
+        if directive.kind == DirectiveKind::Enable {
+            continue;
+        }
+
+        // Suppressions of this rule are used to silence the diagnostics that
+        // are reported here, so they are never unused.
+        if directive.codes.is_empty() {
+            if !used[0] {
+                let message = format!("unused `{}` suppression", directive.kind.as_str());
+                let fix = Fix::safe_edits([Edit::delete(removal(directive))]);
+                unused.push(diagnostic(message, directive.range).with_fix(fix));
+            }
+            continue;
+        }
+
+        let (kept, removed): (Vec<_>, Vec<_>) =
+            directive.codes.iter().zip(used).partition(|(code, used)| {
+                **used || code.rule == Some(rule.code()) || !enabled(code.rule)
+            });
+        if removed.is_empty() {
+            continue;
+        }
+
+        let names = removed.iter().map(|(code, _)| format!("`{}`", code.text)).collect::<Vec<_>>();
+        let message = format!("unused suppression of {}", names.join(", "));
+
+        let fix = match (kept.is_empty(), directive.list) {
+            (false, Some((start, end))) => {
+                let kept = kept.iter().map(|(code, _)| code.text.as_str()).collect::<Vec<_>>();
+                Fix::safe_edits([Edit::replace(ByteRange::new(start, end - 1), kept.join(", "))])
+            }
+            _ => Fix::safe_edits([Edit::delete(removal(directive))]),
+        };
+
+        let mut reported = diagnostic(message, directive.range).with_fix(fix);
+        for (code, _) in removed.iter().filter(|(code, _)| code.rule.is_none()) {
+            reported = reported.with_note(format!("`{}` is not a known rule", code.text));
+        }
+        unused.push(reported);
+    }
+
+    for (range, message) in &suppressions.invalid {
+        unused.push(diagnostic(format!("invalid suppression: {message}"), *range));
+    }
+
+    // Only directives that explicitly refer to this rule can suppress it, since
+    // an unused directive of every rule would otherwise suppress itself.
+    remaining.extend(unused.into_iter().filter(|diagnostic| {
+        !suppressions
+            .suppressing(rule.code(), diagnostic.span.range.start())
+            .any(|index| !suppressions.directives[index].codes.is_empty())
+    }));
+
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::SourceId;
+    use bl_diagnostics::Applicability;
+
+    use crate::{registry::RULES, settings::Dialect};
+
+    /// Lint the given source with every rule, returning the code and line of
+    /// each diagnostic.
+    fn check(source: &str) -> Vec<(&'static str, usize)> {
+        let result = bl_parse::parse(source, SourceId::default());
+        crate::lint(result.document.ast_ref(), source, RULES, Dialect::default())
+            .iter()
+            .map(|diagnostic| {
+                let line = source[..diagnostic.span.range.start()].matches('\n').count();
+                (diagnostic.code.unwrap(), line)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn suppress_lines_regions_and_files() {
+        let source = "\
+{# bl: ignore[BL002] #}
+{% break %}
+{% break %} {# bl: ignore[loop-control-outside-loop] #}
+{% break %}
+{# bl: disable[BL002] #}
+{% continue %}
+{# bl: enable[BL002] #}
+{% continue %}
+";
+        assert_eq!(check(source), [("BL002", 3), ("BL002", 7)]);
+
+        let source = "{# bl: disable-file[BL002] #}\n{% break %}\n{% break %}\n";
+        assert_eq!(check(source), []);
+
+        // Every directive can also list its rules after an `=`.
+        let source = "{# bl: disable-file=BL002 #}\n{% break %}\n{% break %}\n";
+        assert_eq!(check(source), []);
+
+        let source = "\
+{# bl: disable=BL001, loop-control-outside-loop #}
+{% break %}
+{# bl: enable=BL002 #}
+{% break %} {# bl: ignore=BL002 #}
+{% break %}
+{# bl: disable-file= #}
+{# bl: ignore=[BL002] #}
+";
+        assert_eq!(check(source), [("BL002", 4), ("BL003", 0), ("BL003", 6), ("BL003", 5)]);
+    }
+
+    #[test]
+    fn report_unused_suppressions() {
+        let source = "\
+{# bl: ignore[BL001, BL002] #}
+{% break %}
+{# bl: ignore #}
+{# bl: disable[BL999] #}
+{# bl: frobnicate #}
+";
+        assert_eq!(check(source), [("BL003", 0), ("BL003", 2), ("BL003", 3), ("BL003", 4)]);
+
+        let result = bl_parse::parse(source, SourceId::default());
+        let diagnostics = crate::lint(result.document.ast_ref(), source, RULES, Dialect::default());
+        let fixed = crate::fix::apply_fixes(&diagnostics, source, Applicability::Safe).unwrap();
+        assert_eq!(fixed.source, "{# bl: ignore[BL002] #}\n{% break %}\n{# bl: frobnicate #}\n");
+
+        let source = "{# bl: ignore=BL001, BL002 #}\n{% break %}\n";
+        let result = bl_parse::parse(source, SourceId::default());
+        let diagnostics = crate::lint(result.document.ast_ref(), source, RULES, Dialect::default());
+        let fixed = crate::fix::apply_fixes(&diagnostics, source, Applicability::Safe).unwrap();
+        assert_eq!(fixed.source, "{# bl: ignore=BL002 #}\n{% break %}\n");
+
+        // Suppressions of the unused suppression rule are never unused.
+        assert_eq!(check("{# bl: disable-file[BL003] #}{# bl: ignore[BL001] #}"), []);
+
+        // Directives on their own line are removed along with the line, even
+        // when it is the last line of the file.
+        let source = "{% break %}\n{# bl: ignore #}\n{# bl: ignore #}";
+        let result = bl_parse::parse(source, SourceId::default());
+        let diagnostics = crate::lint(result.document.ast_ref(), source, RULES, Dialect::default());
+        let fixed = crate::fix::apply_fixes(&diagnostics, source, Applicability::Safe).unwrap();
+        assert_eq!(fixed.source, "{% break %}\n");
+    }
+}
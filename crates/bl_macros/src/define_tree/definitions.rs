@@ -15,6 +15,15 @@ pub(crate) const NODES_TYPE_NAME: &str = "Children";
 /// The name of the macro to declare an optional node in type position
 pub(crate) const OPTIONAL_NODE_TYPE_NAME: &str = "OptionalChild";
 
+/// The name of the trait that is implemented for each node in order to
+/// serialize nodes as `{kind, span, ...fields}`, this trait must be in scope
+/// when the `serde` feature is enabled for the invoking crate.
+pub(crate) const SERDE_NODE_TRAIT_NAME: &str = "SerdeNode";
+
+/// The name of the trait that is implemented for each node in order to compare
+/// nodes structurally, this trait must be in scope at the invocation site.
+pub(crate) const STRUCTURAL_EQ_TRAIT_NAME: &str = "StructuralEq";
+
 /// An enum node variant, which has to point to another struct.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct EnumNodeVariant {
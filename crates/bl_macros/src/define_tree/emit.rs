@@ -7,7 +7,7 @@ use quote::{format_ident, quote};
 
 use super::definitions::{
     EnumNodeDef, EnumNodeVariant, NodeFieldData, StructNodeDef, StructNodeField, TreeDef,
-    TreeNodeDef, NODE_DEF_ATTR_NAME,
+    TreeNodeDef, NODE_DEF_ATTR_NAME, SERDE_NODE_TRAIT_NAME, STRUCTURAL_EQ_TRAIT_NAME,
 };
 
 /// Suffix the given identifier with "Mut"/"_mut" etc as appropriate depending
@@ -108,6 +108,7 @@ fn emit_struct_def(struct_def: &StructNodeDef, tree_def: &TreeDef) -> TokenStrea
 
     quote! {
         #(#filtered_attrs)*
+        #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
         #visibility struct #name {
             #(#rendered_fields),*
         }
@@ -140,6 +141,7 @@ fn emit_enum_def(enum_def: &EnumNodeDef, tree_def: &TreeDef) -> TokenStream {
 
     quote! {
         #(#filtered_attrs)*
+        #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
         #visibility enum #name {
             #(#rendered_variants),*
         }
@@ -158,6 +160,221 @@ fn emit_node_defs(tree_def: &TreeDef) -> TokenStream {
         .collect()
 }
 
+/// Emit the implementation of the serde node trait for a struct node. Each
+/// field of the struct is written as an entry in the map of the node.
+fn emit_struct_serde_impl(struct_def: &StructNodeDef) -> TokenStream {
+    let trait_name = format_ident!("{}", SERDE_NODE_TRAIT_NAME);
+    let name = &struct_def.name;
+    let kind = name.to_string();
+
+    let field_names = struct_def.fields.iter().map(|field| &field.name).collect::<Vec<_>>();
+    let field_keys = field_names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+    quote! {
+        #[cfg(feature = "serde")]
+        impl #trait_name for #name {
+            fn kind(&self) -> &'static str {
+                #kind
+            }
+
+            fn serialize_fields<M: ::serde::ser::SerializeMap>(
+                &self,
+                _map: &mut M,
+            ) -> Result<(), M::Error> {
+                #( _map.serialize_entry(#field_keys, &self.#field_names)?; )*
+                Ok(())
+            }
+
+            fn deserialize_fields(
+                kind: &str,
+                _fields: &mut ::serde_json::Map<String, ::serde_json::Value>,
+            ) -> Result<Self, ::serde_json::Error> {
+                Self::expect_kind(kind, #kind)?;
+                Ok(Self { #( #field_names: Self::take_field(_fields, #field_keys)? ),* })
+            }
+        }
+    }
+}
+
+/// Emit the implementation of the serde node trait for an enum node. If all of
+/// the variants of the enum are other nodes, then the enum is transparent and
+/// delegates to the inner node. Otherwise, the enum is written as a `value`
+/// entry in the map of the node.
+fn emit_enum_serde_impl(enum_def: &EnumNodeDef, tree_def: &TreeDef) -> TokenStream {
+    let trait_name = format_ident!("{}", SERDE_NODE_TRAIT_NAME);
+    let name = &enum_def.name;
+    let kind = name.to_string();
+
+    // Only "phantom" children can be delegated to, since wrapped children
+    // would need their own span.
+    let transparent = enum_def
+        .variants
+        .iter()
+        .map(|variant| match variant.variant_data.as_deref() {
+            Some([NodeFieldData::Other { ty }]) => {
+                is_node_ty(ty, tree_def).map(|node| (&variant.name, node))
+            }
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .filter(|variants| !variants.is_empty());
+
+    let Some(variants) = transparent else {
+        return quote! {
+            #[cfg(feature = "serde")]
+            impl #trait_name for #name {
+                fn kind(&self) -> &'static str {
+                    #kind
+                }
+
+                fn serialize_fields<M: ::serde::ser::SerializeMap>(
+                    &self,
+                    map: &mut M,
+                ) -> Result<(), M::Error> {
+                    map.serialize_entry("value", self)
+                }
+
+                fn deserialize_fields(
+                    kind: &str,
+                    fields: &mut ::serde_json::Map<String, ::serde_json::Value>,
+                ) -> Result<Self, ::serde_json::Error> {
+                    Self::expect_kind(kind, #kind)?;
+                    Self::take_field(fields, "value")
+                }
+            }
+        };
+    };
+
+    let (variant_names, node_names): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
+    let node_kinds = node_names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+    quote! {
+        #[cfg(feature = "serde")]
+        impl #trait_name for #name {
+            fn kind(&self) -> &'static str {
+                match self {
+                    #( Self::#variant_names(inner) => inner.kind(), )*
+                }
+            }
+
+            fn serialize_fields<M: ::serde::ser::SerializeMap>(
+                &self,
+                map: &mut M,
+            ) -> Result<(), M::Error> {
+                match self {
+                    #( Self::#variant_names(inner) => inner.serialize_fields(map), )*
+                }
+            }
+
+            fn deserialize_fields(
+                kind: &str,
+                fields: &mut ::serde_json::Map<String, ::serde_json::Value>,
+            ) -> Result<Self, ::serde_json::Error> {
+                match kind {
+                    #( #node_kinds => Ok(Self::#variant_names(#node_names::deserialize_fields(kind, fields)?)), )*
+                    _ => Err(Self::unknown_kind(kind, #kind)),
+                }
+            }
+        }
+    }
+}
+
+/// Emit the serde node trait implementations for all nodes.
+fn emit_serde_impls(tree_def: &TreeDef) -> TokenStream {
+    tree_def
+        .nodes
+        .values()
+        .map(|node| match node {
+            TreeNodeDef::EnumNodeDef(enum_def) => emit_enum_serde_impl(enum_def, tree_def),
+            TreeNodeDef::StructNodeDef(struct_def) => emit_struct_serde_impl(struct_def),
+        })
+        .collect()
+}
+
+/// Emit the structural comparison of two values of a field with the given
+/// `data`. Nodes are compared structurally, and any other data is compared
+/// using [PartialEq].
+fn emit_structural_eq_field(
+    data: &NodeFieldData,
+    lhs: &TokenStream,
+    rhs: &TokenStream,
+    tree_def: &TreeDef,
+) -> TokenStream {
+    let trait_name = format_ident!("{}", STRUCTURAL_EQ_TRAIT_NAME);
+    match data {
+        NodeFieldData::Other { ty } if is_node_ty(ty, tree_def).is_none() => {
+            quote! { #lhs == #rhs }
+        }
+        _ => quote! { #trait_name::structural_eq(#lhs, #rhs) },
+    }
+}
+
+/// Emit the implementation of the structural equality trait for the given
+/// node, which compares the kinds and fields of nodes whilst ignoring their
+/// ids (and hence their spans).
+fn emit_structural_eq_impl(node: &TreeNodeDef, tree_def: &TreeDef) -> TokenStream {
+    let trait_name = format_ident!("{}", STRUCTURAL_EQ_TRAIT_NAME);
+
+    let body = match node {
+        TreeNodeDef::StructNodeDef(struct_def) => {
+            let comparisons = struct_def.fields.iter().map(|field| {
+                let name = &field.name;
+                emit_structural_eq_field(
+                    &field.data,
+                    &quote!(&self.#name),
+                    &quote!(&other.#name),
+                    tree_def,
+                )
+            });
+
+            quote! { true #( && #comparisons )* }
+        }
+        TreeNodeDef::EnumNodeDef(enum_def) => {
+            let arms = enum_def.variants.iter().map(|variant| {
+                let name = &variant.name;
+                match &variant.variant_data {
+                    Some(data) => {
+                        let lhs = (0..data.len()).map(|i| format_ident!("lhs_{i}")).collect::<Vec<_>>();
+                        let rhs = (0..data.len()).map(|i| format_ident!("rhs_{i}")).collect::<Vec<_>>();
+                        let comparisons = data.iter().zip(lhs.iter().zip(rhs.iter())).map(
+                            |(data, (lhs, rhs))| {
+                                emit_structural_eq_field(data, &quote!(#lhs), &quote!(#rhs), tree_def)
+                            },
+                        );
+
+                        quote! {
+                            (Self::#name(#(#lhs),*), Self::#name(#(#rhs),*)) => true #( && #comparisons )*
+                        }
+                    }
+                    None => quote! { (Self::#name, Self::#name) => true },
+                }
+            });
+
+            quote! {
+                #[allow(unreachable_patterns)]
+                match (self, other) {
+                    #(#arms,)*
+                    _ => false,
+                }
+            }
+        }
+    };
+
+    let name = node.name();
+    quote! {
+        impl #trait_name for #name {
+            fn structural_eq(&self, other: &Self) -> bool {
+                #body
+            }
+        }
+    }
+}
+
+/// Emit the structural equality trait implementations for all nodes.
+fn emit_structural_eq_impls(tree_def: &TreeDef) -> TokenStream {
+    tree_def.nodes.values().map(|node| emit_structural_eq_impl(node, tree_def)).collect()
+}
+
 /// Emit the visitor trait, depending on options and the `nodes_mut` flag.
 fn emit_visitor(tree_def: &TreeDef, nodes_mut: bool, self_mut: bool) -> TokenStream {
     let visitor_name = suffix_visitor_like_mut(
@@ -829,22 +1046,25 @@ fn emit_default_impl_macros(
         #[macro_export]
         macro_rules! #default_impl_name {
             (@expr [$e:expr]) => {
-                #default_impl_name!($e);
+                $crate::#default_impl_name!($e);
             };
             ($($node:ident),* $(,)?) => {
-                #default_impl_name!([$($node),*]);
+                $crate::#default_impl_name!([$($node),*]);
             };
             ([$($node:ident),* $(,)?]) => {
                 $(
-                    #default_impl_name!(@node $node);
+                    $crate::#default_impl_name!(@node $node);
                 )*
             };
             (hiding: $($node:ident),* $(,)?) => {
-                #default_impl_name!(hiding: [$($node),*]);
+                $crate::#default_impl_name!(hiding: [$($node),*]);
             };
             (hiding: [$($node:ident),* $(,)?]) => {
                 // Here we call the difference! macro to implement all the nodes that are not given
-                hash_tree_def::difference!(#(#all_nodes),*; $($node),*; #default_impl_name, node);
+                //
+                // ##Note: the callback must be a plain identifier, so this macro must be
+                // imported at the use site when using `hiding`.
+                $crate::difference!(#(#all_nodes),*; $($node),*; #default_impl_name, node);
             };
             #(#default_impl_macro_cases)*
             // Last case is error
@@ -865,9 +1085,12 @@ fn emit_default_impl_macros(
 pub(crate) fn emit_tree(tree_def: &TreeDef) -> Result<TokenStream, syn::Error> {
     let mutability_combinations = [(true, true), (false, true), (false, false)];
 
-    let mut streams = vec![];
-    streams.push(emit_other_items(tree_def));
-    streams.push(emit_node_defs(tree_def));
+    let mut streams = vec![
+        emit_other_items(tree_def),
+        emit_node_defs(tree_def),
+        emit_serde_impls(tree_def),
+        emit_structural_eq_impls(tree_def),
+    ];
     streams.extend(
         mutability_combinations
             .iter()
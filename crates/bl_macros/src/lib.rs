@@ -36,7 +36,7 @@ macro_rules! try_syn_err {
 ///         visitor_node_ref_base_type_name: AstNodeRef,
 ///         get_ref_from_node_function_base_name: ast_ref,
 ///         ref_change_body_function_base_name: with_body,
-///         root_module: hash_ast::ast,
+///         root_module: bl_ast,
 ///     }}
 ///     #[node]
 ///     pub struct Foo {
@@ -69,6 +69,12 @@ macro_rules! try_syn_err {
 ///   walker will also generate a `walk_*_same_children` function that will
 ///   visit the enum member after matching on it, and return the result
 ///   directly.
+/// - Implementations of the `StructuralEq` trait for each node, which compare
+///   nodes by their kinds and fields whilst ignoring the ids of nodes. The
+///   trait must be in scope at the invocation site.
+/// - Implementations of the `SerdeNode` trait for each node, which are only
+///   compiled when the invoking crate enables its `serde` feature. The trait
+///   must be in scope at the invocation site in that case.
 #[proc_macro]
 pub fn define_tree(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let def = parse_macro_input!(input as TreeDef);
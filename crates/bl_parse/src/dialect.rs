@@ -0,0 +1,58 @@
+//! The template dialects that are understood by the lexer and parser.
+
+use std::fmt;
+
+/// The template language that a file is written in. All of the dialects share
+/// the same delimiters by default, but differ in the tags and filters that
+/// are available.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dialect {
+    #[default]
+    Jinja,
+
+    Django,
+
+    Twig,
+
+    Nunjucks,
+
+    Liquid,
+}
+
+impl Dialect {
+    /// Get the name of the dialect.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dialect::Jinja => "jinja",
+            Dialect::Django => "django",
+            Dialect::Twig => "twig",
+            Dialect::Nunjucks => "nunjucks",
+            Dialect::Liquid => "liquid",
+        }
+    }
+
+    /// The tags, and their closing tags, whose contents are not lexed and are
+    /// instead treated as text.
+    pub fn raw_tags(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Dialect::Jinja | Dialect::Liquid => &[("raw", "endraw")],
+            Dialect::Django | Dialect::Twig => &[("verbatim", "endverbatim")],
+            Dialect::Nunjucks => &[("raw", "endraw"), ("verbatim", "endverbatim")],
+        }
+    }
+
+    /// Whether the dialect supports `{% break %}` and `{% continue %}` within
+    /// loops.
+    pub fn has_loop_controls(&self) -> bool {
+        matches!(self, Dialect::Jinja | Dialect::Liquid)
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
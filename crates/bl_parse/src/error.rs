@@ -0,0 +1,30 @@
+//! Errors that can occur when lexing or parsing a template.
+
+use std::fmt;
+
+use bl_ast::ByteRange;
+
+/// An error that occurred whilst lexing or parsing a template. The parser
+/// will always attempt to recover from errors, so a template might produce
+/// several errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// A description of the error.
+    pub message: String,
+
+    /// The location of the error within the source.
+    pub range: ByteRange,
+}
+
+impl ParseError {
+    /// Create a new [ParseError].
+    pub fn new(message: impl Into<String>, range: ByteRange) -> Self {
+        Self { message: message.into(), range }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
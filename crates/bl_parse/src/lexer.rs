@@ -0,0 +1,532 @@
+//! The lexer for templates. The lexer operates in two modes, when outside of
+//! any delimiters, it emits [TokenKind::Text] tokens for all of the content up
+//! until the next opening delimiter. Within delimiters, it emits the tokens
+//! that make up expressions and tags.
+
+use bl_ast::ByteRange;
+
+use crate::{
+    dialect::Dialect,
+    error::ParseError,
+    token::{Token, TokenKind},
+};
+
+/// The delimiters that are used to denote variables, blocks and comments
+/// within a template.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Delimiters {
+    /// The start of a block tag, i.e. `{%`.
+    pub block_start: String,
+
+    /// The end of a block tag, i.e. `%}`.
+    pub block_end: String,
+
+    /// The start of a variable, i.e. `{{`.
+    pub variable_start: String,
+
+    /// The end of a variable, i.e. `}}`.
+    pub variable_end: String,
+
+    /// The start of a comment, i.e. `{#`.
+    pub comment_start: String,
+
+    /// The end of a comment, i.e. `#}`.
+    pub comment_end: String,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Self {
+            block_start: "{%".to_string(),
+            block_end: "%}".to_string(),
+            variable_start: "{{".to_string(),
+            variable_end: "}}".to_string(),
+            comment_start: "{#".to_string(),
+            comment_end: "#}".to_string(),
+        }
+    }
+}
+
+/// The result of lexing a template.
+#[derive(Debug, Clone)]
+pub struct LexResult {
+    /// The produced tokens.
+    pub tokens: Vec<Token>,
+
+    /// Any errors that occurred whilst lexing.
+    pub errors: Vec<ParseError>,
+}
+
+pub struct Lexer<'s> {
+    /// The source that is being lexed.
+    source: &'s str,
+
+    /// The delimiters that are being used within the source.
+    delimiters: Delimiters,
+
+    /// The dialect of the source, which determines the raw tags.
+    dialect: Dialect,
+
+    /// Whether to emit trivia tokens.
+    trivia: bool,
+
+    /// The current byte offset within the source.
+    offset: usize,
+
+    /// The tokens that have been produced so far.
+    tokens: Vec<Token>,
+
+    /// Errors that have been encountered so far.
+    errors: Vec<ParseError>,
+}
+
+impl<'s> Lexer<'s> {
+    /// Create a new [Lexer] with the default [Delimiters].
+    pub fn new(source: &'s str) -> Self {
+        Self {
+            source,
+            delimiters: Delimiters::default(),
+            dialect: Dialect::default(),
+            trivia: false,
+            offset: 0,
+            tokens: vec![],
+            errors: vec![],
+        }
+    }
+
+    /// Use the given [Delimiters] when lexing.
+    pub fn with_delimiters(mut self, delimiters: Delimiters) -> Self {
+        self.delimiters = delimiters;
+        self
+    }
+
+    /// Use the raw tags of the given [Dialect] when lexing.
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Whether the lexer should emit trivia tokens.
+    pub fn with_trivia(mut self, trivia: bool) -> Self {
+        self.trivia = trivia;
+        self
+    }
+
+    /// Lex the entire source.
+    pub fn tokenise(mut self) -> LexResult {
+        while self.offset < self.source.len() {
+            self.lex_text();
+        }
+
+        LexResult { tokens: self.tokens, errors: self.errors }
+    }
+
+    /// Get the remaining source from the current offset.
+    fn rest(&self) -> &'s str {
+        &self.source[self.offset..]
+    }
+
+    /// Peek the character at the current offset.
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Peek the character after the current one.
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    /// Emit a token that spans from `start` up to the current offset
+    /// (exclusive).
+    fn emit(&mut self, kind: TokenKind, start: usize) {
+        debug_assert!(self.offset > start, "empty token");
+
+        if kind.is_trivia() && !self.trivia {
+            return;
+        }
+
+        self.tokens.push(Token::new(kind, ByteRange::new(start, self.offset - 1)));
+    }
+
+    /// Emit an error that spans from `start` up to the current offset
+    /// (exclusive).
+    fn error(&mut self, message: impl Into<String>, start: usize) {
+        let end = self.offset.max(start + 1) - 1;
+        self.errors.push(ParseError::new(message, ByteRange::new(start, end)));
+    }
+
+    /// Lex all of the text up until the next opening delimiter, and then lex
+    /// the contents of the delimiter.
+    fn lex_text(&mut self) {
+        let start = self.offset;
+        let rest = self.rest();
+
+        let candidates = [
+            (&self.delimiters.comment_start, TokenKind::Comment),
+            (&self.delimiters.variable_start, TokenKind::VariableStart),
+            (&self.delimiters.block_start, TokenKind::BlockStart),
+        ];
+
+        // Find the delimiter that occurs first, preferring the longest delimiter
+        // if two delimiters begin at the same position.
+        let next = candidates
+            .iter()
+            .filter_map(|(delimiter, kind)| {
+                rest.find(delimiter.as_str()).map(|pos| (pos, delimiter.len(), *kind))
+            })
+            .min_by(|(a, a_len, _), (b, b_len, _)| a.cmp(b).then(b_len.cmp(a_len)));
+
+        let Some((pos, _, kind)) = next else {
+            self.offset = self.source.len();
+            self.emit(TokenKind::Text, start);
+            return;
+        };
+
+        if pos > 0 {
+            self.offset += pos;
+            self.emit(TokenKind::Text, start);
+        }
+
+        match kind {
+            TokenKind::Comment => self.lex_comment(),
+            TokenKind::VariableStart => {
+                self.lex_open_delimiter(TokenKind::VariableStart);
+                self.lex_delimited(TokenKind::VariableEnd);
+            }
+            _ => {
+                let first_token = self.tokens.len();
+                self.lex_open_delimiter(TokenKind::BlockStart);
+
+                if self.lex_delimited(TokenKind::BlockEnd) {
+                    self.lex_raw_contents(first_token);
+                }
+            }
+        }
+    }
+
+    /// Lex an opening delimiter, including any whitespace control modifiers.
+    fn lex_open_delimiter(&mut self, kind: TokenKind) {
+        let start = self.offset;
+        self.offset += match kind {
+            TokenKind::VariableStart => self.delimiters.variable_start.len(),
+            _ => self.delimiters.block_start.len(),
+        };
+
+        if matches!(self.peek(), Some('-' | '+')) {
+            self.offset += 1;
+        }
+
+        self.emit(kind, start);
+    }
+
+    /// Lex a comment, the entire comment including the delimiters is emitted
+    /// as a single token.
+    fn lex_comment(&mut self) {
+        let start = self.offset;
+        self.offset += self.delimiters.comment_start.len();
+
+        match self.rest().find(self.delimiters.comment_end.as_str()) {
+            Some(pos) => {
+                self.offset += pos + self.delimiters.comment_end.len();
+                self.emit(TokenKind::Comment, start);
+            }
+            None => {
+                self.offset = self.source.len();
+                self.error("unterminated comment", start);
+                self.emit(TokenKind::Comment, start);
+            }
+        }
+    }
+
+    /// Lex the contents of a variable or a block, up until the closing
+    /// delimiter. Returns whether the closing delimiter was found.
+    fn lex_delimited(&mut self, end_kind: TokenKind) -> bool {
+        let opening = *self.tokens.last().unwrap();
+        let end_delimiter = match end_kind {
+            TokenKind::VariableEnd => self.delimiters.variable_end.clone(),
+            _ => self.delimiters.block_end.clone(),
+        };
+
+        loop {
+            let start = self.offset;
+            let rest = self.rest();
+
+            let Some(ch) = self.peek() else {
+                let (open, close) = match end_kind {
+                    TokenKind::VariableEnd => {
+                        (&self.delimiters.variable_start, &self.delimiters.variable_end)
+                    }
+                    _ => (&self.delimiters.block_start, &self.delimiters.block_end),
+                };
+                let message = format!("unterminated `{open}`, expected a closing `{close}`");
+                self.errors.push(ParseError::new(message, opening.range));
+                return false;
+            };
+
+            // Check for the closing delimiter, possibly with a whitespace
+            // control modifier.
+            if rest.starts_with(end_delimiter.as_str())
+                || (matches!(ch, '-' | '+') && rest[1..].starts_with(end_delimiter.as_str()))
+            {
+                let modifier = if rest.starts_with(end_delimiter.as_str()) { 0 } else { 1 };
+                self.offset += modifier + end_delimiter.len();
+                self.emit(end_kind, start);
+                return true;
+            }
+
+            match ch {
+                _ if ch.is_whitespace() => {
+                    self.offset +=
+                        self.rest().find(|c: char| !c.is_whitespace()).unwrap_or(self.rest().len());
+                    self.emit(TokenKind::Whitespace, start);
+                }
+                _ if ch.is_alphabetic() || ch == '_' => self.lex_ident(),
+                '0'..='9' => self.lex_number(),
+                '"' | '\'' => self.lex_string(ch),
+                _ => self.lex_punctuation(ch),
+            }
+        }
+    }
+
+    /// Lex an identifier.
+    fn lex_ident(&mut self) {
+        let start = self.offset;
+        self.offset += self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest().len());
+        self.emit(TokenKind::Ident, start);
+    }
+
+    /// Lex an integer or a float literal.
+    fn lex_number(&mut self) {
+        let start = self.offset;
+        let digits = |rest: &str| {
+            rest.find(|c: char| !(c.is_ascii_digit() || c == '_')).unwrap_or(rest.len())
+        };
+
+        self.offset += digits(self.rest());
+
+        // A float needs to have digits after the dot, otherwise it might be an
+        // attribute access, i.e. `foo.0.bar`.
+        if self.peek() == Some('.') && self.peek_second().is_some_and(|c| c.is_ascii_digit()) {
+            self.offset += 1;
+            self.offset += digits(self.rest());
+
+            if matches!(self.peek(), Some('e' | 'E')) {
+                let exponent = &self.rest()[1..];
+                let sign = usize::from(exponent.starts_with(['+', '-']));
+
+                if exponent[sign..].starts_with(|c: char| c.is_ascii_digit()) {
+                    self.offset += 1 + sign;
+                    self.offset += digits(self.rest());
+                }
+            }
+
+            self.emit(TokenKind::Float, start);
+        } else {
+            self.emit(TokenKind::Int, start);
+        }
+    }
+
+    /// Lex a string literal that is delimited by the given `quote`.
+    fn lex_string(&mut self, quote: char) {
+        let start = self.offset;
+        self.offset += 1;
+
+        let mut chars = self.rest().char_indices();
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                '\\' => {
+                    chars.next();
+                }
+                _ if ch == quote => {
+                    self.offset += index + 1;
+                    self.emit(TokenKind::Str, start);
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        self.offset = self.source.len();
+        self.error("unterminated string literal", start);
+        self.emit(TokenKind::Str, start);
+    }
+
+    /// Lex an operator or a punctuation character.
+    fn lex_punctuation(&mut self, ch: char) {
+        let start = self.offset;
+        let second = self.peek_second();
+
+        let (kind, len) = match (ch, second) {
+            ('*', Some('*')) => (TokenKind::StarStar, 2),
+            ('/', Some('/')) => (TokenKind::SlashSlash, 2),
+            ('=', Some('=')) => (TokenKind::EqEq, 2),
+            ('!', Some('=')) => (TokenKind::NotEq, 2),
+            ('<', Some('=')) => (TokenKind::LtEq, 2),
+            ('>', Some('=')) => (TokenKind::GtEq, 2),
+            ('.', _) => (TokenKind::Dot, 1),
+            (',', _) => (TokenKind::Comma, 1),
+            (':', _) => (TokenKind::Colon, 1),
+            ('|', _) => (TokenKind::Pipe, 1),
+            ('~', _) => (TokenKind::Tilde, 1),
+            ('(', _) => (TokenKind::LeftParen, 1),
+            (')', _) => (TokenKind::RightParen, 1),
+            ('[', _) => (TokenKind::LeftBracket, 1),
+            (']', _) => (TokenKind::RightBracket, 1),
+            ('{', _) => (TokenKind::LeftBrace, 1),
+            ('}', _) => (TokenKind::RightBrace, 1),
+            ('=', _) => (TokenKind::Assign, 1),
+            ('<', _) => (TokenKind::Lt, 1),
+            ('>', _) => (TokenKind::Gt, 1),
+            ('+', _) => (TokenKind::Plus, 1),
+            ('-', _) => (TokenKind::Minus, 1),
+            ('*', _) => (TokenKind::Star, 1),
+            ('/', _) => (TokenKind::Slash, 1),
+            ('%', _) => (TokenKind::Percent, 1),
+            _ => (TokenKind::Unknown, ch.len_utf8()),
+        };
+
+        self.offset += len;
+
+        if kind == TokenKind::Unknown {
+            self.error(format!("unexpected character `{ch}`"), start);
+        }
+
+        self.emit(kind, start);
+    }
+
+    /// If the block tag that begins at the token `first_token` is a raw tag,
+    /// i.e. `{% raw %}` in the current dialect, then lex everything up until
+    /// the closing tag as text.
+    fn lex_raw_contents(&mut self, first_token: usize) {
+        let mut tag = self.tokens[first_token..].iter().filter(|token| !token.kind.is_trivia());
+
+        let (Some(_), Some(name), Some(end), None) =
+            (tag.next(), tag.next(), tag.next(), tag.next())
+        else {
+            return;
+        };
+
+        let name = &self.source[name.range.start()..=name.range.end()];
+        let Some((_, closing)) =
+            self.dialect.raw_tags().iter().find(|(opening, _)| *opening == name)
+        else {
+            return;
+        };
+
+        if !end.is(TokenKind::BlockEnd) {
+            return;
+        }
+
+        // Look for the closing tag, which might contain whitespace and whitespace
+        // control modifiers.
+        let start = self.offset;
+        let mut search = start;
+
+        while let Some(pos) = self.source[search..].find(self.delimiters.block_start.as_str()) {
+            let tag_start = search + pos;
+            let inner = self.source[tag_start + self.delimiters.block_start.len()..]
+                .trim_start_matches(['-', '+'])
+                .trim_start();
+
+            if let Some(after) = inner.strip_prefix(closing) {
+                let after = after.trim_start().trim_start_matches(['-', '+']);
+
+                if after.starts_with(self.delimiters.block_end.as_str()) {
+                    if tag_start > start {
+                        self.offset = tag_start;
+                        self.emit(TokenKind::Text, start);
+                    }
+
+                    return;
+                }
+            }
+
+            search = tag_start + self.delimiters.block_start.len();
+        }
+
+        // The closing tag is missing, the parser will report the missing tag.
+        if self.source.len() > start {
+            self.offset = self.source.len();
+            self.emit(TokenKind::Text, start);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str, trivia: bool) -> Vec<TokenKind> {
+        let result = Lexer::new(source).with_trivia(trivia).tokenise();
+        assert!(result.errors.is_empty(), "unexpected errors: {:?}", result.errors);
+        result.tokens.into_iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn lex_text_and_delimiters() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds("<p>{{ user.name|title }}</p>{# note #}", false),
+            vec![Text, VariableStart, Ident, Dot, Ident, Pipe, Ident, VariableEnd, Text, Comment]
+        );
+
+        assert_eq!(
+            kinds("{%- if x >= 1.5 -%}", true),
+            vec![
+                BlockStart, Whitespace, Ident, Whitespace, Ident, Whitespace, GtEq, Whitespace,
+                Float, Whitespace, BlockEnd
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_raw_blocks() {
+        use TokenKind::*;
+
+        let source = "{% raw %}{{ not lexed }}{% endraw %}";
+        assert_eq!(
+            kinds(source, false),
+            vec![BlockStart, Ident, BlockEnd, Text, BlockStart, Ident, BlockEnd]
+        );
+
+        // Only the raw tags of the dialect are treated as raw.
+        let source = "{% verbatim %}{{ x }}{% endverbatim %}";
+        let kinds = |dialect| {
+            let result = Lexer::new(source).with_dialect(dialect).tokenise();
+            result.tokens.into_iter().map(|token| token.kind).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds(Dialect::Twig),
+            vec![BlockStart, Ident, BlockEnd, Text, BlockStart, Ident, BlockEnd]
+        );
+        assert_eq!(
+            kinds(Dialect::Jinja),
+            vec![
+                BlockStart,
+                Ident,
+                BlockEnd,
+                VariableStart,
+                Ident,
+                VariableEnd,
+                BlockStart,
+                Ident,
+                BlockEnd
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_errors() {
+        let result = Lexer::new("{{ 'abc }}").tokenise();
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0].message, "unterminated string literal");
+
+        let result = Lexer::new("{{ a ? b }}").tokenise();
+        assert_eq!(result.errors[0].message, "unexpected character `?`");
+        assert_eq!(result.errors[0].range, ByteRange::new(5, 5));
+    }
+}
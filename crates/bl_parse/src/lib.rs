@@ -1 +1,130 @@
 //! Contains all of the parsing logic for the `bl` project.
+
+pub mod dialect;
+mod error;
+pub mod lexer;
+mod parser;
+pub mod token;
+
+use bl_ast::{AstNode, Document, SourceId, SpanMap};
+use dialect::Dialect;
+pub use error::ParseError;
+use lexer::{Delimiters, LexResult, Lexer};
+use parser::Parser;
+
+/// The result of parsing a template.
+#[derive(Debug)]
+pub struct ParseResult {
+    /// The parsed document, the parser always produces a document even if
+    /// there were errors.
+    pub document: AstNode<Document>,
+
+    /// Any errors that were encountered whilst lexing and parsing, ordered by
+    /// their position in the source.
+    pub errors: Vec<ParseError>,
+}
+
+/// Parse the given source into a [Document]. The spans of all of the created
+/// nodes are registered in the global [SpanMap] under the given [SourceId].
+pub fn parse(source: &str, source_id: SourceId) -> ParseResult {
+    parse_with_delimiters(source, source_id, &Delimiters::default(), Dialect::default())
+}
+
+/// Parse the given source into a [Document] like [parse], using the given
+/// [Delimiters] and the raw tags of the given [Dialect] rather than the
+/// default ones.
+pub fn parse_with_delimiters(
+    source: &str,
+    source_id: SourceId,
+    delimiters: &Delimiters,
+    dialect: Dialect,
+) -> ParseResult {
+    let LexResult { tokens, mut errors } =
+        Lexer::new(source).with_delimiters(delimiters.clone()).with_dialect(dialect).tokenise();
+
+    let mut parser = Parser::new(source, source_id, tokens, dialect);
+    let document = parser.parse_document();
+    let (spans, parse_errors) = parser.finish();
+    SpanMap::add_local_map(spans);
+
+    errors.extend(parse_errors);
+    errors.sort_by_key(|error| error.range.start());
+
+    ParseResult { document, errors }
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{tree::AstTreeGenerator, SourceId, StructuralEq};
+    use bl_utils::tree_writing::SExpWriter;
+
+    use super::parse;
+
+    fn parse_to_sexp(source: &str) -> String {
+        let result = parse(source, SourceId::default());
+        assert!(result.errors.is_empty(), "unexpected errors: {:?}", result.errors);
+
+        let tree = AstTreeGenerator::generate(result.document.ast_ref());
+        SExpWriter::new(&tree).to_string()
+    }
+
+    #[test]
+    fn parse_variable() {
+        assert_eq!(
+            parse_to_sexp("{{ a.b + 1 }}"),
+            "(Document @0:12
+  (Var @0:12
+    (BinExpr @3:9
+      (BinOp `+` @7:7)
+      (lhs
+        (AccessExpr @3:5
+          (subject
+            (VarExpr @3:3
+              (Name \"a\" @3:3)))
+          (property
+            (Name \"b\" @5:5))))
+      (rhs
+        (Lit 1 @9:9)))))
+"
+        );
+    }
+
+    #[test]
+    fn parse_nested_tags() {
+        let result = parse(
+            "{% for x in xs if x %}{% if x > 1 %}{{ x }}{% else %}-{% endif %}{% endfor %}",
+            SourceId::default(),
+        );
+        assert!(result.errors.is_empty(), "unexpected errors: {:?}", result.errors);
+    }
+
+    #[test]
+    fn parse_structurally_equal() {
+        let source = "{{x|f(1)}}{% if a %}{% endif %}";
+        let lhs = parse(source, SourceId::default());
+        let rhs = parse("{{ x | f( 1 ) }}{% if a %}{%endif%}", SourceId::default());
+        let other = parse("{{ x | f(2) }}{% if a %}{% endif %}", SourceId::default());
+
+        // Spans differ, but the structure is the same.
+        assert!(lhs.document.structural_eq(&rhs.document));
+        assert!(!lhs.document.structural_eq(&other.document));
+
+        // Two parses of the same source are never equal since ids differ.
+        assert_ne!(lhs.document, parse(source, SourceId::default()).document);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let result = parse("{% if x %}{{ 1 + }}{% endfor %}", SourceId::default());
+        let messages = result.errors.iter().map(|error| error.message.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            [
+                "unclosed `if` tag, expected a closing `endif` tag",
+                "expected an expression, found `}}`",
+                "unexpected `endfor` tag"
+            ]
+        );
+    }
+}
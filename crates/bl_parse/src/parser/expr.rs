@@ -0,0 +1,561 @@
+//! Parsing of expressions. Operator precedence follows Jinja, from the lowest
+//! to the highest precedence:
+//!
+//! 1. conditional expressions, `x if y else z`
+//! 2. `or`
+//! 3. `and`
+//! 4. `not`
+//! 5. comparisons, `in`, `not in`, `is` and `is not`
+//! 6. `~`
+//! 7. `+`, `-`
+//! 8. `*`, `/`, `//`, `%`
+//! 9. unary `-`
+//! 10. `**`
+//! 11. filters, `x | filter`
+//! 12. attribute access, subscripts and calls
+
+use bl_ast::{
+    AccessExpr, Arg, ArrayExpr, AstNode, AstNodes, BinExpr, BinOp, ByteRange, CallExpr, CondExpr,
+    DictEntry, DictExpr, Expr, FilteredExpr, Identifier, IndexExpr, Lit, LitExpr, Name, UnaryExpr,
+    UnaryOp, VarExpr,
+};
+use thin_vec::{thin_vec, ThinVec};
+
+use super::Parser;
+use crate::token::{Token, TokenKind};
+
+/// Keywords that cannot begin an argument of an `is` test without
+/// parentheses, i.e. `x is divisibleby 3`.
+const EXPR_KEYWORDS: &[&str] = &["and", "or", "not", "in", "is", "if", "else"];
+
+impl<'s> Parser<'s> {
+    /// Parse an expression.
+    pub(crate) fn parse_expr(&mut self) -> Option<AstNode<Expr>> {
+        self.parse_cond_expr()
+    }
+
+    /// Create a [Name] node from the given identifier token.
+    pub(crate) fn name(&mut self, token: Token) -> AstNode<Name> {
+        let data = Identifier::intern(self.text(token));
+        self.node(Name { data }, token.range)
+    }
+
+    /// Expect the current token to be an identifier and create a [Name] node
+    /// from it.
+    pub(crate) fn expect_name(&mut self) -> Option<AstNode<Name>> {
+        let token = self.expect(TokenKind::Ident)?;
+        Some(self.name(token))
+    }
+
+    /// Create an expression node that spans from `start` to the end of the
+    /// previously consumed token.
+    fn expr(&mut self, expr: Expr, start: usize) -> AstNode<Expr> {
+        let range = ByteRange::new(start, self.prev_end());
+        self.node(expr, range)
+    }
+
+    /// Create a binary expression.
+    fn bin_expr(
+        &mut self,
+        lhs: AstNode<Expr>,
+        rhs: AstNode<Expr>,
+        op: (BinOp, ByteRange),
+        start: usize,
+    ) -> AstNode<Expr> {
+        let op = self.node(op.0, op.1);
+        self.expr(Expr::Bin(BinExpr { lhs, rhs, op }), start)
+    }
+
+    /// Create a unary expression.
+    fn unary_expr(
+        &mut self,
+        expr: AstNode<Expr>,
+        op: (UnaryOp, ByteRange),
+        start: usize,
+    ) -> AstNode<Expr> {
+        let op = self.node(op.0, op.1);
+        self.expr(Expr::Unary(UnaryExpr { op, expr }), start)
+    }
+
+    fn parse_cond_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let value = self.parse_or_expr()?;
+
+        if self.eat_keyword("if").is_none() {
+            return Some(value);
+        }
+
+        let condition = self.parse_or_expr()?;
+        let otherwise =
+            if self.eat_keyword("else").is_some() { Some(self.parse_cond_expr()?) } else { None };
+
+        Some(self.expr(Expr::Cond(CondExpr { value, condition, otherwise }), start))
+    }
+
+    /// Parse an expression that cannot be a conditional expression, this is
+    /// used in places where an `if` keyword has another meaning, i.e. the
+    /// iterable of a `for` loop.
+    pub(crate) fn parse_or_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut lhs = self.parse_and_expr()?;
+
+        while let Some(op) = self.eat_keyword("or") {
+            let rhs = self.parse_and_expr()?;
+            lhs = self.bin_expr(lhs, rhs, (BinOp::Or, op.range), start);
+        }
+
+        Some(lhs)
+    }
+
+    fn parse_and_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut lhs = self.parse_not_expr()?;
+
+        while let Some(op) = self.eat_keyword("and") {
+            let rhs = self.parse_not_expr()?;
+            lhs = self.bin_expr(lhs, rhs, (BinOp::And, op.range), start);
+        }
+
+        Some(lhs)
+    }
+
+    fn parse_not_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+
+        match self.eat_keyword("not") {
+            Some(op) => {
+                let expr = self.parse_not_expr()?;
+                Some(self.unary_expr(expr, (UnaryOp::Not, op.range), start))
+            }
+            None => self.parse_compare_expr(),
+        }
+    }
+
+    fn parse_compare_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut lhs = self.parse_concat_expr()?;
+
+        loop {
+            let Some(token) = self.peek() else { break };
+
+            let op = match token.kind {
+                TokenKind::EqEq => BinOp::Eq,
+                TokenKind::NotEq => BinOp::NotEq,
+                TokenKind::Lt => BinOp::Lt,
+                TokenKind::LtEq => BinOp::Lte,
+                TokenKind::Gt => BinOp::Gt,
+                TokenKind::GtEq => BinOp::Gte,
+                TokenKind::Ident if self.text(token) == "in" => BinOp::In,
+                TokenKind::Ident if self.text(token) == "is" => {
+                    lhs = self.parse_test(lhs, start)?;
+                    continue;
+                }
+                TokenKind::Ident
+                    if self.text(token) == "not"
+                        && self.peek_second().is_some_and(|next| self.text(next) == "in") =>
+                {
+                    // `x not in y` is represented as `not (x in y)`.
+                    let not = self.bump();
+                    let op = self.bump();
+                    let rhs = self.parse_concat_expr()?;
+                    let expr = self.bin_expr(lhs, rhs, (BinOp::In, op.range), start);
+                    lhs = self.unary_expr(expr, (UnaryOp::Not, not.range), start);
+                    continue;
+                }
+                _ => break,
+            };
+
+            self.bump();
+            let rhs = self.parse_concat_expr()?;
+            lhs = self.bin_expr(lhs, rhs, (op, token.range), start);
+        }
+
+        Some(lhs)
+    }
+
+    /// Parse an `is` test, i.e. `x is defined`, `x is not none` or `x is
+    /// divisibleby 3`.
+    fn parse_test(&mut self, subject: AstNode<Expr>, start: usize) -> Option<AstNode<Expr>> {
+        let op = self.bump();
+        let not = self.eat_keyword("not");
+
+        let test_start = self.current_range().start();
+        let mut test = self.parse_postfix_expr()?;
+
+        // Tests can take a single argument without parentheses.
+        let takes_argument = self.peek().is_some_and(|token| match token.kind {
+            TokenKind::Int | TokenKind::Float | TokenKind::Str => true,
+            TokenKind::Ident => !EXPR_KEYWORDS.contains(&self.text(token)),
+            _ => false,
+        });
+
+        if takes_argument {
+            let arg_start = self.current_range().start();
+            let value = self.parse_postfix_expr()?;
+            let arg = self.node(
+                Arg { name: None, value: Some(value) },
+                ByteRange::new(arg_start, self.prev_end()),
+            );
+            let args = self.nodes(thin_vec![arg], ByteRange::new(arg_start, self.prev_end()));
+            test = self.expr(Expr::Call(CallExpr { subject: test, args }), test_start);
+        }
+
+        let expr = self.bin_expr(subject, test, (BinOp::Is, op.range), start);
+
+        match not {
+            Some(not) => Some(self.unary_expr(expr, (UnaryOp::Not, not.range), start)),
+            None => Some(expr),
+        }
+    }
+
+    fn parse_concat_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut lhs = self.parse_additive_expr()?;
+
+        while let Some(op) = self.eat(TokenKind::Tilde) {
+            let rhs = self.parse_additive_expr()?;
+            lhs = self.bin_expr(lhs, rhs, (BinOp::Concat, op.range), start);
+        }
+
+        Some(lhs)
+    }
+
+    fn parse_additive_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut lhs = self.parse_multiplicative_expr()?;
+
+        loop {
+            let op = match self.peek().map(|token| token.kind) {
+                Some(TokenKind::Plus) => BinOp::Add,
+                Some(TokenKind::Minus) => BinOp::Sub,
+                _ => break,
+            };
+
+            let token = self.bump();
+            let rhs = self.parse_multiplicative_expr()?;
+            lhs = self.bin_expr(lhs, rhs, (op, token.range), start);
+        }
+
+        Some(lhs)
+    }
+
+    fn parse_multiplicative_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut lhs = self.parse_unary_expr()?;
+
+        loop {
+            let op = match self.peek().map(|token| token.kind) {
+                Some(TokenKind::Star) => BinOp::Mul,
+                Some(TokenKind::Slash) => BinOp::Div,
+                Some(TokenKind::SlashSlash) => BinOp::FloorDiv,
+                Some(TokenKind::Percent) => BinOp::Modulo,
+                _ => break,
+            };
+
+            let token = self.bump();
+            let rhs = self.parse_unary_expr()?;
+            lhs = self.bin_expr(lhs, rhs, (op, token.range), start);
+        }
+
+        Some(lhs)
+    }
+
+    fn parse_unary_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+
+        if let Some(op) = self.eat(TokenKind::Minus) {
+            let expr = self.parse_unary_expr()?;
+            return Some(self.unary_expr(expr, (UnaryOp::Neg, op.range), start));
+        }
+
+        // A unary `+` has no effect on the expression.
+        if self.eat(TokenKind::Plus).is_some() {
+            return self.parse_unary_expr();
+        }
+
+        self.parse_pow_expr()
+    }
+
+    fn parse_pow_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let lhs = self.parse_filtered_expr()?;
+
+        match self.eat(TokenKind::StarStar) {
+            Some(op) => {
+                let rhs = self.parse_unary_expr()?;
+                Some(self.bin_expr(lhs, rhs, (BinOp::Pow, op.range), start))
+            }
+            None => Some(lhs),
+        }
+    }
+
+    fn parse_filtered_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let subject = self.parse_postfix_expr()?;
+
+        if !self.at(TokenKind::Pipe) {
+            return Some(subject);
+        }
+
+        let filters_start = self.current_range().start();
+        let mut filters = thin_vec![];
+
+        while self.eat(TokenKind::Pipe).is_some() {
+            filters.push(self.parse_filter()?);
+        }
+
+        let filters = self.nodes(filters, ByteRange::new(filters_start, self.prev_end()));
+        Some(self.expr(Expr::FilteredExpr(FilteredExpr { subject, filters }), start))
+    }
+
+    /// Parse a filter application, filters may either take arguments within
+    /// parentheses, i.e. `default("x")`, or after a colon as in Django and
+    /// Liquid, i.e. `default:"x"`.
+    fn parse_filter(&mut self) -> Option<AstNode<CallExpr>> {
+        let token = self.expect(TokenKind::Ident)?;
+        let var = Name { data: Identifier::intern(self.text(token)) };
+        let subject = self.node(Expr::Var(VarExpr { var }), token.range);
+
+        let args = if self.at(TokenKind::LeftParen) {
+            self.parse_call_args()?
+        } else if let Some(colon) = self.eat(TokenKind::Colon) {
+            let mut args = thin_vec![];
+
+            loop {
+                args.push(self.parse_arg(TokenKind::Colon, true)?);
+
+                if self.eat(TokenKind::Comma).is_none() {
+                    break;
+                }
+            }
+
+            self.nodes(args, ByteRange::new(colon.range.start(), self.prev_end()))
+        } else {
+            self.nodes(thin_vec![], ByteRange::singleton(token.range.end()))
+        };
+
+        let range = ByteRange::new(token.range.start(), self.prev_end());
+        Some(self.node(CallExpr { subject, args }, range))
+    }
+
+    /// Parse the arguments of a call, i.e. `(a, b=1)`.
+    pub(crate) fn parse_call_args(&mut self) -> Option<AstNodes<Arg>> {
+        let open = self.expect(TokenKind::LeftParen)?;
+        let mut args = thin_vec![];
+
+        while !self.at(TokenKind::RightParen) {
+            args.push(self.parse_arg(TokenKind::Assign, false)?);
+
+            if self.eat(TokenKind::Comma).is_none() {
+                break;
+            }
+        }
+
+        let close = self.expect(TokenKind::RightParen)?;
+        Some(self.nodes(args, ByteRange::new(open.range.start(), close.range.end())))
+    }
+
+    /// Parse a single argument, which might be named if the name is followed
+    /// by the `assign` token. If the argument is `simple`, then the value of
+    /// the argument cannot contain any operators or filters.
+    pub(crate) fn parse_arg(&mut self, assign: TokenKind, simple: bool) -> Option<AstNode<Arg>> {
+        let start = self.current_range().start();
+
+        let is_named =
+            self.at(TokenKind::Ident) && self.peek_second().is_some_and(|token| token.is(assign));
+
+        let name = if is_named {
+            let token = self.bump();
+            self.bump();
+            Some(self.name(token))
+        } else {
+            None
+        };
+
+        let value = if simple { self.parse_postfix_expr()? } else { self.parse_expr()? };
+        let range = ByteRange::new(start, self.prev_end());
+        Some(self.node(Arg { name, value: Some(value) }, range))
+    }
+
+    /// Parse a primary expression, followed by any number of attribute
+    /// accesses, subscripts and calls.
+    pub(crate) fn parse_postfix_expr(&mut self) -> Option<AstNode<Expr>> {
+        let start = self.current_range().start();
+        let mut subject = self.parse_primary_expr()?;
+
+        loop {
+            if self.eat(TokenKind::Dot).is_some() {
+                // Allow for numeric properties, i.e. `items.0`.
+                let token = match self.peek() {
+                    Some(token) if matches!(token.kind, TokenKind::Ident | TokenKind::Int) => {
+                        self.bump()
+                    }
+                    _ => {
+                        self.error_expected("an attribute name");
+                        return None;
+                    }
+                };
+
+                let property = self.name(token);
+                subject = self.expr(Expr::Access(AccessExpr { subject, property }), start);
+            } else if self.eat(TokenKind::LeftBracket).is_some() {
+                let index = self.parse_expr()?;
+                self.expect(TokenKind::RightBracket)?;
+                subject = self.expr(Expr::Index(IndexExpr { subject, index }), start);
+            } else if self.at(TokenKind::LeftParen) {
+                let args = self.parse_call_args()?;
+                subject = self.expr(Expr::Call(CallExpr { subject, args }), start);
+            } else {
+                break;
+            }
+        }
+
+        Some(subject)
+    }
+
+    fn parse_primary_expr(&mut self) -> Option<AstNode<Expr>> {
+        let Some(token) = self.peek() else {
+            self.error_expected("an expression");
+            return None;
+        };
+
+        let lit = match token.kind {
+            TokenKind::Int => {
+                self.bump();
+                match self.text(token).replace('_', "").parse() {
+                    Ok(value) => Lit::Int(value),
+                    Err(_) => {
+                        self.error("integer literal is too large", token.range);
+                        return None;
+                    }
+                }
+            }
+            TokenKind::Float => {
+                self.bump();
+                Lit::Float(self.text(token).replace('_', "").parse().unwrap_or(f64::INFINITY))
+            }
+            TokenKind::Str => {
+                self.bump();
+                Lit::Str(unescape(self.text(token)))
+            }
+            TokenKind::Ident => {
+                self.bump();
+                match self.text(token) {
+                    "true" | "True" => Lit::Bool(true),
+                    "false" | "False" => Lit::Bool(false),
+                    name => {
+                        let var = Name { data: Identifier::intern(name) };
+                        return Some(self.node(Expr::Var(VarExpr { var }), token.range));
+                    }
+                }
+            }
+            TokenKind::LeftParen => return self.parse_paren_expr(),
+            TokenKind::LeftBracket => return self.parse_array_expr(),
+            TokenKind::LeftBrace => return self.parse_dict_expr(),
+            _ => {
+                self.error_expected("an expression");
+                return None;
+            }
+        };
+
+        Some(self.node(Expr::Lit(LitExpr { lit }), token.range))
+    }
+
+    /// Parse a parenthesised expression, tuples are represented as arrays.
+    fn parse_paren_expr(&mut self) -> Option<AstNode<Expr>> {
+        let open = self.bump();
+        let expr = self.parse_expr()?;
+
+        if !self.at(TokenKind::Comma) {
+            self.expect(TokenKind::RightParen)?;
+            return Some(expr);
+        }
+
+        let mut children = thin_vec![expr];
+        while self.eat(TokenKind::Comma).is_some() && !self.at(TokenKind::RightParen) {
+            children.push(self.parse_expr()?);
+        }
+
+        let close = self.expect(TokenKind::RightParen)?;
+        let range = ByteRange::new(open.range.start(), close.range.end());
+        let children = self.nodes(children, range);
+        Some(self.node(Expr::Array(ArrayExpr { children }), range))
+    }
+
+    fn parse_array_expr(&mut self) -> Option<AstNode<Expr>> {
+        let open = self.bump();
+        let children = self.parse_sequence(TokenKind::RightBracket, Self::parse_expr)?;
+
+        let range = ByteRange::new(open.range.start(), self.prev_end());
+        let children = self.nodes(children, range);
+        Some(self.node(Expr::Array(ArrayExpr { children }), range))
+    }
+
+    fn parse_dict_expr(&mut self) -> Option<AstNode<Expr>> {
+        let open = self.bump();
+        let entries = self.parse_sequence(TokenKind::RightBrace, |this| {
+            let start = this.current_range().start();
+            let key = this.parse_expr()?;
+            this.expect(TokenKind::Colon)?;
+            let value = this.parse_expr()?;
+
+            let range = ByteRange::new(start, this.prev_end());
+            Some(this.node(DictEntry { key, value }, range))
+        })?;
+
+        let range = ByteRange::new(open.range.start(), self.prev_end());
+        let entries = self.nodes(entries, range);
+        Some(self.node(Expr::Dict(DictExpr { entries }), range))
+    }
+
+    /// Parse a comma separated sequence of items that is terminated by the
+    /// `close` token, allowing for a trailing comma.
+    fn parse_sequence<T>(
+        &mut self,
+        close: TokenKind,
+        mut parse_item: impl FnMut(&mut Self) -> Option<T>,
+    ) -> Option<ThinVec<T>> {
+        let mut items = thin_vec![];
+
+        while !self.at(close) {
+            items.push(parse_item(self)?);
+
+            if self.eat(TokenKind::Comma).is_none() {
+                break;
+            }
+        }
+
+        self.expect(close)?;
+        Some(items)
+    }
+}
+
+/// Remove the quotes from a string literal, and resolve any escape sequences.
+fn unescape(literal: &str) -> String {
+    let quote = literal.chars().next().unwrap();
+    let inner = literal[1..].strip_suffix(quote).unwrap_or(&literal[1..]);
+
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some(ch @ ('\\' | '\'' | '"')) => value.push(ch),
+            Some(ch) => {
+                value.push('\\');
+                value.push(ch);
+            }
+            None => value.push('\\'),
+        }
+    }
+
+    value
+}
@@ -0,0 +1,315 @@
+//! The template parser. The parser is a recursive descent parser that operates
+//! on the tokens produced by the [crate::lexer::Lexer]. The parser always
+//! produces a [Document], and will attempt to recover from any errors that it
+//! encounters by skipping to the end of the current tag.
+
+mod expr;
+mod statement;
+
+use bl_ast::{AstNode, AstNodeId, AstNodes, ByteRange, LocalSpanMap, SourceId};
+use thin_vec::ThinVec;
+
+use crate::{
+    dialect::Dialect,
+    error::ParseError,
+    token::{Token, TokenKind},
+};
+
+pub(crate) struct Parser<'s> {
+    /// The source that is being parsed.
+    source: &'s str,
+
+    /// The dialect of the source, which determines the raw tags.
+    dialect: Dialect,
+
+    /// The tokens of the source, excluding any trivia.
+    tokens: Vec<Token>,
+
+    /// The index of the current token.
+    pos: usize,
+
+    /// The spans of all of the nodes that have been created.
+    spans: LocalSpanMap,
+
+    /// Any errors that have been encountered whilst parsing.
+    errors: Vec<ParseError>,
+}
+
+impl<'s> Parser<'s> {
+    pub(crate) fn new(
+        source: &'s str,
+        source_id: SourceId,
+        tokens: Vec<Token>,
+        dialect: Dialect,
+    ) -> Self {
+        Self {
+            source,
+            dialect,
+            spans: LocalSpanMap::with_capacity(source_id, tokens.len()),
+            tokens,
+            pos: 0,
+            errors: vec![],
+        }
+    }
+
+    /// Consume the parser, returning the [LocalSpanMap] of all of the created
+    /// nodes and any errors.
+    pub(crate) fn finish(self) -> (LocalSpanMap, Vec<ParseError>) {
+        (self.spans, self.errors)
+    }
+
+    /// Create a new [AstNode] with the given body and range.
+    fn node<T>(&mut self, body: T, range: ByteRange) -> AstNode<T> {
+        AstNode::with_id(body, self.id(range))
+    }
+
+    /// Create a new [AstNodes] with the given nodes and range.
+    fn nodes<T>(&mut self, nodes: ThinVec<AstNode<T>>, range: ByteRange) -> AstNodes<T> {
+        AstNodes::with_id(nodes, self.id(range))
+    }
+
+    /// Reserve an [AstNodeId] for the given range.
+    fn id(&mut self, range: ByteRange) -> AstNodeId {
+        self.spans.add(range)
+    }
+
+    /// Peek at the current token.
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    /// Peek at the token after the current one.
+    fn peek_second(&self) -> Option<Token> {
+        self.tokens.get(self.pos + 1).copied()
+    }
+
+    /// Check whether the current token is of the given kind.
+    fn at(&self, kind: TokenKind) -> bool {
+        self.peek().is_some_and(|token| token.is(kind))
+    }
+
+    /// Check whether the current token is the given keyword.
+    fn at_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is(TokenKind::Ident) && self.text(token) == keyword)
+    }
+
+    /// Advance to the next token, returning the current token.
+    fn bump(&mut self) -> Token {
+        let token = self.tokens[self.pos];
+        self.pos += 1;
+        token
+    }
+
+    /// Consume the current token if it is of the given kind.
+    fn eat(&mut self, kind: TokenKind) -> Option<Token> {
+        self.at(kind).then(|| self.bump())
+    }
+
+    /// Consume the current token if it is the given keyword.
+    fn eat_keyword(&mut self, keyword: &str) -> Option<Token> {
+        self.at_keyword(keyword).then(|| self.bump())
+    }
+
+    /// Get the text of the given token.
+    fn text(&self, token: Token) -> &'s str {
+        &self.source[token.range.start()..=token.range.end()]
+    }
+
+    /// Get the end offset of the previously consumed token.
+    fn prev_end(&self) -> usize {
+        self.pos.checked_sub(1).map_or(0, |pos| self.tokens[pos].range.end())
+    }
+
+    /// Get the range of the current token, or the end of the source if there
+    /// are no more tokens.
+    fn current_range(&self) -> ByteRange {
+        match self.peek() {
+            Some(token) => token.range,
+            None => ByteRange::singleton(self.source.len().saturating_sub(1)),
+        }
+    }
+
+    /// Describe the current token for use in error messages.
+    fn describe_current(&self) -> String {
+        match self.peek() {
+            Some(token) if matches!(token.kind, TokenKind::Text | TokenKind::Comment) => {
+                token.kind.to_string()
+            }
+            Some(token) => format!("`{}`", self.text(token)),
+            None => "end of file".to_string(),
+        }
+    }
+
+    /// Report an error at the given range.
+    fn error(&mut self, message: impl Into<String>, range: ByteRange) {
+        self.errors.push(ParseError::new(message, range));
+    }
+
+    /// Report that the current token was unexpected.
+    fn error_expected(&mut self, expected: &str) {
+        let message = format!("expected {expected}, found {}", self.describe_current());
+        self.error(message, self.current_range());
+    }
+
+    /// Expect the current token to be of the given kind, reporting an error
+    /// if it is not.
+    fn expect(&mut self, kind: TokenKind) -> Option<Token> {
+        let token = self.eat(kind);
+
+        if token.is_none() {
+            self.error_expected(&format!("`{kind}`"));
+        }
+
+        token
+    }
+
+    /// Skip tokens until the end of the current tag or variable. If the end
+    /// delimiter is found, it is consumed and its range is returned.
+    fn recover(&mut self) -> Option<ByteRange> {
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::BlockEnd | TokenKind::VariableEnd => return Some(self.bump().range),
+                TokenKind::Text
+                | TokenKind::Comment
+                | TokenKind::BlockStart
+                | TokenKind::VariableStart => return None,
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Expect the end of the current block tag, if there are any other tokens
+    /// before the closing delimiter, an error is reported and the tokens are
+    /// skipped.
+    ///
+    /// Returns the end offset of the tag.
+    fn expect_block_end(&mut self) -> usize {
+        if let Some(token) = self.eat(TokenKind::BlockEnd) {
+            return token.range.end();
+        }
+
+        self.error_expected("`%}`");
+        self.recover().map_or_else(|| self.prev_end(), |range| range.end())
+    }
+
+    /// Skip any tokens up until the end of the current block tag, this is used
+    /// for modifiers on tags that do not affect the AST, i.e. `ignore missing`
+    /// on `include` tags.
+    ///
+    /// Returns the end offset of the tag.
+    fn skip_to_block_end(&mut self) -> usize {
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::BlockEnd => return self.bump().range.end(),
+                TokenKind::Text
+                | TokenKind::Comment
+                | TokenKind::BlockStart
+                | TokenKind::VariableStart
+                | TokenKind::VariableEnd => break,
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+
+        self.expect_block_end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bl_ast::{AstNode, BinOp, Expr, Lit, LitExpr, SourceId, Statement, UnaryOp};
+
+    use crate::parse;
+
+    /// Parse the given source, which must not contain any errors, returning
+    /// the top-level statements.
+    fn statements(source: &str) -> Vec<AstNode<Statement>> {
+        let result = parse(source, SourceId::default());
+        assert!(result.errors.is_empty(), "unexpected errors: {:?}", result.errors);
+        result.document.body.children.nodes.to_vec()
+    }
+
+    /// Parse the given expression within a `{{ }}` tag.
+    fn expr(source: &str) -> Expr {
+        let statements = statements(&format!("{{{{ {source} }}}}"));
+        let Statement::Var(var) = &*statements[0].body else { panic!("expected a variable") };
+        (*var.value.body).clone()
+    }
+
+    /// Get the name of a variable expression.
+    fn var_name(expr: &Expr) -> &'static str {
+        let Expr::Var(var) = expr else { panic!("expected a variable, found {expr:?}") };
+        var.var.data.as_str()
+    }
+
+    #[test]
+    fn binary_operator_precedence() {
+        // `1 + 2 * 3` is `1 + (2 * 3)`.
+        let Expr::Bin(add) = expr("1 + 2 * 3") else { panic!("expected a binary expression") };
+        assert_eq!(*add.op.body, BinOp::Add);
+        assert!(matches!(*add.lhs.body, Expr::Lit(LitExpr { lit: Lit::Int(1) })));
+        let Expr::Bin(mul) = &*add.rhs.body else { panic!("expected a binary expression") };
+        assert_eq!(*mul.op.body, BinOp::Mul);
+
+        // `not a or b > 1` is `(not a) or (b > 1)`.
+        let Expr::Bin(or) = expr("not a or b > 1") else { panic!("expected a binary expression") };
+        assert_eq!(*or.op.body, BinOp::Or);
+        let Expr::Unary(not) = &*or.lhs.body else { panic!("expected a unary expression") };
+        assert_eq!(*not.op.body, UnaryOp::Not);
+        assert_eq!(var_name(&not.expr.body), "a");
+        let Expr::Bin(gt) = &*or.rhs.body else { panic!("expected a binary expression") };
+        assert_eq!(*gt.op.body, BinOp::Gt);
+    }
+
+    #[test]
+    fn postfix_and_conditional_expressions() {
+        let Expr::Cond(cond) = expr("a.b[0](x) if c else d") else {
+            panic!("expected a conditional expression")
+        };
+        assert_eq!(var_name(&cond.condition.body), "c");
+        assert_eq!(var_name(&cond.otherwise.as_ref().unwrap().body), "d");
+
+        let Expr::Call(call) = &*cond.value.body else { panic!("expected a call") };
+        assert_eq!(call.args.len(), 1);
+        let Expr::Index(index) = &*call.subject.body else { panic!("expected an index") };
+        let Expr::Access(access) = &*index.subject.body else { panic!("expected an access") };
+        assert_eq!(access.property.body.data.as_str(), "b");
+        assert_eq!(var_name(&access.subject.body), "a");
+
+        let Expr::FilteredExpr(filtered) = expr("x | upper | default(1)") else {
+            panic!("expected a filtered expression")
+        };
+        assert_eq!(var_name(&filtered.subject.body), "x");
+        assert_eq!(filtered.filters.len(), 2);
+        assert_eq!(filtered.filters[1].body.args.len(), 1);
+    }
+
+    #[test]
+    fn for_loop_with_condition_and_else() {
+        let statements =
+            statements("{% for k, v in items if v %}{{ v }}{% else %}none{% endfor %}");
+        let Statement::For(for_loop) = &*statements[0].body else { panic!("expected a loop") };
+
+        assert_eq!(for_loop.key.as_ref().unwrap().body.data.as_str(), "k");
+        assert_eq!(for_loop.value.body.data.as_str(), "v");
+        assert_eq!(var_name(&for_loop.iterable.body), "items");
+        assert_eq!(var_name(&for_loop.condition.as_ref().unwrap().body), "v");
+        assert_eq!(for_loop.loop_body.body.contents.len(), 1);
+        assert_eq!(for_loop.loop_empty.as_ref().unwrap().body.contents.len(), 1);
+    }
+
+    #[test]
+    fn recover_from_invalid_tag() {
+        let result = parse("{% if %}a{% endif %}{{ b }}", SourceId::default());
+        assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+
+        // The statement after the invalid tag is still parsed.
+        let children = &result.document.body.children;
+        assert!(matches!(&*children[children.len() - 1].body, Statement::Var(_)));
+    }
+}
@@ -0,0 +1,485 @@
+//! Parsing of statements, i.e. text, comments, variables and tags.
+
+use bl_ast::{
+    Arg, AstNode, AstNodes, Block, Body, Break, ByteRange, Comment, Continue, Document, Extends,
+    For, If, IfClause, Import, Include, MacroDef, Path, Raw, Set, Statement, Super, Tag, Text, Var,
+};
+use thin_vec::{thin_vec, ThinVec};
+
+use super::Parser;
+use crate::token::{Token, TokenKind};
+
+/// Tags that close or continue a block, these are reported as errors when
+/// they appear without the corresponding opening tag.
+const CLOSING_TAGS: &[&str] =
+    &["endif", "elif", "elsif", "endfor", "endblock", "endmacro", "endraw", "endverbatim"];
+
+/// The tag that stopped the parsing of a sequence of statements.
+#[derive(Debug, Clone, Copy)]
+struct Terminator<'s> {
+    /// The name of the tag.
+    name: &'s str,
+
+    /// The offset at which the tag begins.
+    start: usize,
+}
+
+impl<'s> Parser<'s> {
+    /// Parse an entire [Document].
+    pub(crate) fn parse_document(&mut self) -> AstNode<Document> {
+        let (children, _) = self.parse_statements(&[]);
+
+        let range = ByteRange::new(0, self.source.len().saturating_sub(1));
+        let children = self.nodes(children, range);
+        self.node(Document { children }, range)
+    }
+
+    /// Parse statements until one of the `terminators` tags is encountered.
+    /// If a terminator is found, the parser will be positioned after the name
+    /// of the terminating tag.
+    fn parse_statements(
+        &mut self,
+        terminators: &[&str],
+    ) -> (ThinVec<AstNode<Statement>>, Option<Terminator<'s>>) {
+        let mut statements = thin_vec![];
+
+        while let Some(token) = self.peek() {
+            let statement = match token.kind {
+                TokenKind::Text => {
+                    self.bump();
+                    Some(self.node(Statement::Text(Text {}), token.range))
+                }
+                TokenKind::Comment => {
+                    self.bump();
+                    Some(self.node(Statement::Comment(Comment {}), token.range))
+                }
+                TokenKind::VariableStart => self.parse_variable(),
+                TokenKind::BlockStart => {
+                    let name = self
+                        .peek_second()
+                        .filter(|token| token.is(TokenKind::Ident))
+                        .map(|token| self.text(token));
+
+                    if let Some(name) = name.filter(|name| terminators.contains(name)) {
+                        self.bump();
+                        self.bump();
+                        return (statements, Some(Terminator { name, start: token.range.start() }));
+                    }
+
+                    self.parse_tag()
+                }
+                _ => {
+                    let message = format!("unexpected {}", self.describe_current());
+                    self.error(message, token.range);
+                    self.bump();
+                    None
+                }
+            };
+
+            statements.extend(statement);
+        }
+
+        (statements, None)
+    }
+
+    /// Parse the children of a block tag until one of the `terminators`, the
+    /// children are given a range that spans from the end of the opening tag
+    /// up until the terminating tag.
+    fn parse_children(
+        &mut self,
+        terminators: &[&str],
+        header_end: usize,
+    ) -> (AstNodes<Statement>, Option<Terminator<'s>>) {
+        let (children, terminator) = self.parse_statements(terminators);
+        let range = self.children_range(header_end, terminator);
+        (self.nodes(children, range), terminator)
+    }
+
+    /// Parse the children of a block tag as a [Body].
+    fn parse_body(
+        &mut self,
+        terminators: &[&str],
+        header_end: usize,
+    ) -> (AstNode<Body>, Option<Terminator<'s>>) {
+        let (contents, terminator) = self.parse_children(terminators, header_end);
+        let range = self.children_range(header_end, terminator);
+        (self.node(Body { contents }, range), terminator)
+    }
+
+    /// Compute the range of the children of a block tag, which spans from the
+    /// end of the opening tag up until the terminating tag (or the end of the
+    /// source if the block is unclosed). Empty children are given the range of
+    /// the last character of the opening tag.
+    fn children_range(&self, header_end: usize, terminator: Option<Terminator>) -> ByteRange {
+        let end = self.children_end(header_end, terminator);
+
+        if end > header_end {
+            ByteRange::new(header_end + 1, end)
+        } else {
+            ByteRange::singleton(header_end)
+        }
+    }
+
+    /// Compute the (inclusive) end of the children of a block tag.
+    fn children_end(&self, header_end: usize, terminator: Option<Terminator>) -> usize {
+        match terminator {
+            Some(terminator) => terminator.start.saturating_sub(1).max(header_end),
+            None => self.source.len().saturating_sub(1).max(header_end),
+        }
+    }
+
+    /// Parse the header of a tag (everything after the name) with the given
+    /// function, and then expect the end of the tag. If the header fails to
+    /// parse, the parser recovers by skipping to the end of the tag.
+    ///
+    /// Returns the parsed header and the end offset of the tag.
+    fn parse_tag_header<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Option<T>,
+    ) -> (Option<T>, usize) {
+        match parse(self) {
+            Some(header) => {
+                let end = self.expect_block_end();
+                (Some(header), end)
+            }
+            None => {
+                let end = self.recover().map_or_else(|| self.prev_end(), |range| range.end());
+                (None, end)
+            }
+        }
+    }
+
+    /// Finish parsing a block tag given the terminator that was found. If the
+    /// terminator is missing, an error is reported on the opening tag.
+    ///
+    /// Returns the end offset of the block.
+    fn close_block(
+        &mut self,
+        terminator: Option<Terminator<'s>>,
+        tag: &str,
+        closing: &str,
+        opening: ByteRange,
+    ) -> usize {
+        match terminator {
+            Some(_) => {
+                // Closing tags of blocks and macros may repeat the name.
+                if matches!(closing, "endblock" | "endmacro") {
+                    self.eat(TokenKind::Ident);
+                }
+
+                self.expect_block_end()
+            }
+            None => {
+                let message = format!("unclosed `{tag}` tag, expected a closing `{closing}` tag");
+                self.error(message, opening);
+                self.prev_end()
+            }
+        }
+    }
+
+    /// Parse a variable, i.e. `{{ value }}`.
+    fn parse_variable(&mut self) -> Option<AstNode<Statement>> {
+        let open = self.bump();
+
+        // `{{ super() }}` is represented as its own statement.
+        let is_super = self.at_keyword("super")
+            && self.tokens.get(self.pos + 1).is_some_and(|t| t.is(TokenKind::LeftParen))
+            && self.tokens.get(self.pos + 2).is_some_and(|t| t.is(TokenKind::RightParen))
+            && self.tokens.get(self.pos + 3).is_some_and(|t| t.is(TokenKind::VariableEnd));
+
+        if is_super {
+            self.pos += 4;
+            let range = ByteRange::new(open.range.start(), self.prev_end());
+            return Some(self.node(Statement::Super(Super {}), range));
+        }
+
+        let Some(value) = self.parse_expr() else {
+            self.recover();
+            return None;
+        };
+
+        let end = match self.eat(TokenKind::VariableEnd) {
+            Some(token) => token.range.end(),
+            None => {
+                self.error_expected("`}}`");
+                self.recover().map_or_else(|| self.prev_end(), |range| range.end())
+            }
+        };
+
+        let range = ByteRange::new(open.range.start(), end);
+        Some(self.node(Statement::Var(Var { value }), range))
+    }
+
+    /// Parse a block tag, i.e. `{% if x %}`.
+    fn parse_tag(&mut self) -> Option<AstNode<Statement>> {
+        let open = self.bump();
+        let start = open.range.start();
+
+        let Some(name) = self.eat(TokenKind::Ident) else {
+            self.error_expected("a tag name");
+            self.recover();
+            return None;
+        };
+
+        let (statement, end) = match self.text(name) {
+            "if" => self.parse_if(start)?,
+            "for" => self.parse_for(start)?,
+            "block" => self.parse_block(start)?,
+            "macro" => self.parse_macro_def(start)?,
+            "set" if self.peek_second().is_some_and(|token| token.is(TokenKind::Assign)) => {
+                self.parse_set()?
+            }
+            "include" | "extends" | "import" if self.at(TokenKind::Str) => {
+                self.parse_path_tag(name)
+            }
+            "continue" => (Statement::Continue(Continue {}), self.expect_block_end()),
+            "break" => (Statement::Break(Break {}), self.expect_block_end()),
+            tag if self.raw_closing_tag(tag).is_some() => self.parse_raw(start, tag),
+            tag if CLOSING_TAGS.contains(&tag) => {
+                let message = format!("unexpected `{tag}` tag");
+                self.error(message, ByteRange::new(start, name.range.end()));
+                self.skip_to_block_end();
+                return None;
+            }
+            _ => self.parse_generic_tag(name),
+        };
+
+        Some(self.node(statement, ByteRange::new(start, end)))
+    }
+
+    /// Parse an `if` tag, along with any `elif` and `else` clauses.
+    fn parse_if(&mut self, start: usize) -> Option<(Statement, usize)> {
+        let (mut condition, mut header_end) = self.parse_tag_header(Self::parse_expr);
+        let opening = ByteRange::new(start, header_end);
+
+        let mut clause_start = start;
+        let mut clauses = thin_vec![];
+        let mut otherwise = None;
+        let mut is_valid = true;
+
+        let end = loop {
+            let (body, terminator) =
+                self.parse_body(&["elif", "elsif", "else", "endif"], header_end);
+            let clause_range =
+                ByteRange::new(clause_start, self.children_end(header_end, terminator));
+
+            match condition.take() {
+                Some(condition) => {
+                    clauses.push(self.node(IfClause { condition, if_body: body }, clause_range))
+                }
+                None => is_valid = false,
+            }
+
+            match terminator {
+                Some(Terminator { name: "elif" | "elsif", start }) => {
+                    clause_start = start;
+                    (condition, header_end) = self.parse_tag_header(Self::parse_expr);
+                }
+                Some(Terminator { name: "else", .. }) => {
+                    header_end = self.expect_block_end();
+                    let (body, terminator) = self.parse_body(&["endif"], header_end);
+                    otherwise = Some(body);
+                    break self.close_block(terminator, "if", "endif", opening);
+                }
+                terminator => break self.close_block(terminator, "if", "endif", opening),
+            }
+        };
+
+        if !is_valid {
+            return None;
+        }
+
+        let clauses = self.nodes(clauses, ByteRange::new(start, end));
+        Some((Statement::If(If { clauses, otherwise }), end))
+    }
+
+    /// Parse a `for` loop, along with an optional `else` (or `empty`) clause.
+    fn parse_for(&mut self, start: usize) -> Option<(Statement, usize)> {
+        let (header, header_end) = self.parse_tag_header(|this| {
+            let first = this.expect_name()?;
+            let (key, value) = match this.eat(TokenKind::Comma) {
+                Some(_) => (Some(first), this.expect_name()?),
+                None => (None, first),
+            };
+
+            if this.eat_keyword("in").is_none() {
+                this.error_expected("`in`");
+                return None;
+            }
+
+            let iterable = this.parse_or_expr()?;
+            let condition =
+                if this.eat_keyword("if").is_some() { Some(this.parse_expr()?) } else { None };
+
+            // Modifiers which do not affect the structure of the loop.
+            while this.eat_keyword("recursive").or_else(|| this.eat_keyword("reversed")).is_some() {
+            }
+
+            Some((key, value, iterable, condition))
+        });
+
+        let opening = ByteRange::new(start, header_end);
+        let (loop_body, mut terminator) = self.parse_body(&["else", "empty", "endfor"], header_end);
+
+        let mut loop_empty = None;
+        if terminator.is_some_and(|terminator| terminator.name != "endfor") {
+            let header_end = self.expect_block_end();
+            let (body, next) = self.parse_body(&["endfor"], header_end);
+            loop_empty = Some(body);
+            terminator = next;
+        }
+
+        let end = self.close_block(terminator, "for", "endfor", opening);
+        let (key, value, iterable, condition) = header?;
+
+        Some((Statement::For(For { key, value, iterable, condition, loop_body, loop_empty }), end))
+    }
+
+    /// Parse a `block` tag.
+    fn parse_block(&mut self, start: usize) -> Option<(Statement, usize)> {
+        let (label, header_end) = self.parse_tag_header(|this| {
+            let label = this.eat(TokenKind::Ident).map(|token| this.name(token));
+
+            // Modifiers which do not affect the structure of the block.
+            while this.eat_keyword("scoped").or_else(|| this.eat_keyword("required")).is_some() {}
+
+            Some(label)
+        });
+
+        let opening = ByteRange::new(start, header_end);
+        let (children, terminator) = self.parse_children(&["endblock"], header_end);
+        let end = self.close_block(terminator, "block", "endblock", opening);
+
+        Some((Statement::Block(Block { label: label?, children }), end))
+    }
+
+    /// Parse a `macro` definition.
+    fn parse_macro_def(&mut self, start: usize) -> Option<(Statement, usize)> {
+        let (header, header_end) = self.parse_tag_header(|this| {
+            let name = this.expect_name()?;
+            let open = this.expect(TokenKind::LeftParen)?;
+            let mut args = thin_vec![];
+
+            while !this.at(TokenKind::RightParen) {
+                let arg_start = this.current_range().start();
+                let name = this.expect_name()?;
+                let value = if this.eat(TokenKind::Assign).is_some() {
+                    Some(this.parse_expr()?)
+                } else {
+                    None
+                };
+
+                let range = ByteRange::new(arg_start, this.prev_end());
+                args.push(this.node(Arg { name: Some(name), value }, range));
+
+                if this.eat(TokenKind::Comma).is_none() {
+                    break;
+                }
+            }
+
+            let close = this.expect(TokenKind::RightParen)?;
+            let args = this.nodes(args, ByteRange::new(open.range.start(), close.range.end()));
+            Some((name, args))
+        });
+
+        let opening = ByteRange::new(start, header_end);
+        let (inner, terminator) = self.parse_children(&["endmacro"], header_end);
+        let end = self.close_block(terminator, "macro", "endmacro", opening);
+        let (name, args) = header?;
+
+        Some((Statement::MacroDef(MacroDef { name, args, inner }), end))
+    }
+
+    /// Parse a `set` tag, i.e. `{% set x = 10 %}`.
+    ///
+    /// ##Note: block assignments, i.e. `{% set x %}...{% endset %}` are parsed as
+    /// generic tags.
+    fn parse_set(&mut self) -> Option<(Statement, usize)> {
+        let (header, end) = self.parse_tag_header(|this| {
+            let name = this.expect_name()?;
+            this.expect(TokenKind::Assign)?;
+            let value = this.parse_expr()?;
+            Some((name, value))
+        });
+
+        let (name, value) = header?;
+        Some((Statement::Set(Set { name, value }), end))
+    }
+
+    /// Parse a tag that references another template by a literal path, i.e.
+    /// `include`, `extends` and `import`.
+    fn parse_path_tag(&mut self, name: Token) -> (Statement, usize) {
+        let token = self.bump();
+        let data = self.text(token);
+        let path = self.node(Path { data: data[1..data.len() - 1].to_string() }, token.range);
+
+        match self.text(name) {
+            "extends" => (Statement::Extends(Extends { path }), self.expect_block_end()),
+            "import" => {
+                let name = match self.eat_keyword("as") {
+                    Some(_) => self.expect_name(),
+                    None => None,
+                };
+
+                // Skip any context modifiers, i.e. `with context`.
+                (Statement::Import(Import { path, name }), self.skip_to_block_end())
+            }
+            // Skip any modifiers, i.e. `ignore missing` or `with context`.
+            _ => (Statement::Include(Include { path }), self.skip_to_block_end()),
+        }
+    }
+
+    /// Get the closing tag of the given tag if it is a raw tag in the dialect
+    /// that is being parsed.
+    fn raw_closing_tag(&self, tag: &str) -> Option<&'static str> {
+        let raw_tags = self.dialect.raw_tags();
+        raw_tags.iter().find(|(opening, _)| *opening == tag).map(|(_, closing)| *closing)
+    }
+
+    /// Parse a raw tag, i.e. `{% raw %}`, the contents of the tag are lexed as
+    /// text.
+    fn parse_raw(&mut self, start: usize, tag: &str) -> (Statement, usize) {
+        let header_end = self.expect_block_end();
+        let closing = self.raw_closing_tag(tag).unwrap_or("endraw");
+
+        let (_, terminator) = self.parse_statements(&[closing]);
+        let end = self.close_block(terminator, tag, closing, ByteRange::new(start, header_end));
+
+        (Statement::Raw(Raw {}), end)
+    }
+
+    /// Parse a generic tag, the arguments of the tag are parsed as a sequence
+    /// of (possibly named) expressions.
+    fn parse_generic_tag(&mut self, name: Token) -> (Statement, usize) {
+        let args_start = self.current_range().start();
+        let (args, end) = self.parse_tag_header(|this| {
+            let mut args = thin_vec![];
+
+            while this.peek().is_some_and(|token| {
+                !matches!(
+                    token.kind,
+                    TokenKind::BlockEnd
+                        | TokenKind::Text
+                        | TokenKind::Comment
+                        | TokenKind::BlockStart
+                        | TokenKind::VariableStart
+                )
+            }) {
+                args.push(this.parse_arg(TokenKind::Assign, false)?);
+                this.eat(TokenKind::Comma);
+            }
+
+            Some(args)
+        });
+
+        let name = self.name(name);
+        let range = if end > args_start {
+            ByteRange::new(args_start, end)
+        } else {
+            ByteRange::singleton(end)
+        };
+        let args = self.nodes(args.unwrap_or_default(), range);
+
+        (Statement::Tag(Tag { name, args }), end)
+    }
+}
@@ -0,0 +1,223 @@
+//! Definitions of the tokens that the [crate::lexer::Lexer] produces.
+
+use std::fmt;
+
+use bl_ast::ByteRange;
+
+/// The kind of a [Token].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A hunk of raw text that is outside of any delimiters.
+    Text,
+
+    /// A comment, including the delimiters, i.e. `{# comment #}`.
+    Comment,
+
+    /// The start of a variable, i.e. `{{` or `{{-`.
+    VariableStart,
+
+    /// The end of a variable, i.e. `}}` or `-}}`.
+    VariableEnd,
+
+    /// The start of a block tag, i.e. `{%` or `{%-`.
+    BlockStart,
+
+    /// The end of a block tag, i.e. `%}` or `-%}`.
+    BlockEnd,
+
+    /// An identifier, keywords such as `if` or `and` are also lexed as
+    /// identifiers since they are context dependant.
+    Ident,
+
+    /// An integer literal.
+    Int,
+
+    /// A float literal.
+    Float,
+
+    /// A string literal, including the quotes.
+    Str,
+
+    /// `.`
+    Dot,
+    /// `,`
+    Comma,
+    /// `:`
+    Colon,
+    /// `|`
+    Pipe,
+    /// `~`
+    Tilde,
+    /// `(`
+    LeftParen,
+    /// `)`
+    RightParen,
+    /// `[`
+    LeftBracket,
+    /// `]`
+    RightBracket,
+    /// `{`
+    LeftBrace,
+    /// `}`
+    RightBrace,
+    /// `=`
+    Assign,
+    /// `==`
+    EqEq,
+    /// `!=`
+    NotEq,
+    /// `<`
+    Lt,
+    /// `<=`
+    LtEq,
+    /// `>`
+    Gt,
+    /// `>=`
+    GtEq,
+    /// `+`
+    Plus,
+    /// `-`
+    Minus,
+    /// `*`
+    Star,
+    /// `**`
+    StarStar,
+    /// `/`
+    Slash,
+    /// `//`
+    SlashSlash,
+    /// `%`
+    Percent,
+
+    /// Whitespace within delimiters, this is only emitted when the lexer is
+    /// configured to emit trivia.
+    Whitespace,
+
+    /// A character that the lexer could not make sense of.
+    Unknown,
+}
+
+impl TokenKind {
+    /// Whether this [TokenKind] is considered as trivia, i.e. it carries no
+    /// meaning for the parser.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, TokenKind::Whitespace)
+    }
+
+    /// Get the name of the [TokenKind] as it would be written in a template,
+    /// or a short description if the token has no fixed representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenKind::Text => "text",
+            TokenKind::Comment => "comment",
+            TokenKind::VariableStart => "{{",
+            TokenKind::VariableEnd => "}}",
+            TokenKind::BlockStart => "{%",
+            TokenKind::BlockEnd => "%}",
+            TokenKind::Ident => "identifier",
+            TokenKind::Int => "integer",
+            TokenKind::Float => "float",
+            TokenKind::Str => "string",
+            TokenKind::Dot => ".",
+            TokenKind::Comma => ",",
+            TokenKind::Colon => ":",
+            TokenKind::Pipe => "|",
+            TokenKind::Tilde => "~",
+            TokenKind::LeftParen => "(",
+            TokenKind::RightParen => ")",
+            TokenKind::LeftBracket => "[",
+            TokenKind::RightBracket => "]",
+            TokenKind::LeftBrace => "{",
+            TokenKind::RightBrace => "}",
+            TokenKind::Assign => "=",
+            TokenKind::EqEq => "==",
+            TokenKind::NotEq => "!=",
+            TokenKind::Lt => "<",
+            TokenKind::LtEq => "<=",
+            TokenKind::Gt => ">",
+            TokenKind::GtEq => ">=",
+            TokenKind::Plus => "+",
+            TokenKind::Minus => "-",
+            TokenKind::Star => "*",
+            TokenKind::StarStar => "**",
+            TokenKind::Slash => "/",
+            TokenKind::SlashSlash => "//",
+            TokenKind::Percent => "%",
+            TokenKind::Whitespace => "whitespace",
+            TokenKind::Unknown => "unknown",
+        }
+    }
+
+    /// Get the name of the [TokenKind] in `PascalCase`, this is used when
+    /// printing the token stream.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenKind::Text => "Text",
+            TokenKind::Comment => "Comment",
+            TokenKind::VariableStart => "VariableStart",
+            TokenKind::VariableEnd => "VariableEnd",
+            TokenKind::BlockStart => "BlockStart",
+            TokenKind::BlockEnd => "BlockEnd",
+            TokenKind::Ident => "Ident",
+            TokenKind::Int => "Int",
+            TokenKind::Float => "Float",
+            TokenKind::Str => "Str",
+            TokenKind::Dot => "Dot",
+            TokenKind::Comma => "Comma",
+            TokenKind::Colon => "Colon",
+            TokenKind::Pipe => "Pipe",
+            TokenKind::Tilde => "Tilde",
+            TokenKind::LeftParen => "LeftParen",
+            TokenKind::RightParen => "RightParen",
+            TokenKind::LeftBracket => "LeftBracket",
+            TokenKind::RightBracket => "RightBracket",
+            TokenKind::LeftBrace => "LeftBrace",
+            TokenKind::RightBrace => "RightBrace",
+            TokenKind::Assign => "Assign",
+            TokenKind::EqEq => "EqEq",
+            TokenKind::NotEq => "NotEq",
+            TokenKind::Lt => "Lt",
+            TokenKind::LtEq => "LtEq",
+            TokenKind::Gt => "Gt",
+            TokenKind::GtEq => "GtEq",
+            TokenKind::Plus => "Plus",
+            TokenKind::Minus => "Minus",
+            TokenKind::Star => "Star",
+            TokenKind::StarStar => "StarStar",
+            TokenKind::Slash => "Slash",
+            TokenKind::SlashSlash => "SlashSlash",
+            TokenKind::Percent => "Percent",
+            TokenKind::Whitespace => "Whitespace",
+            TokenKind::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A [Token] is a single lexeme within the source, the text of the token can
+/// be retrieved by using the [ByteRange] of the token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token {
+    /// The kind of the token.
+    pub kind: TokenKind,
+
+    /// The range of the token within the source.
+    pub range: ByteRange,
+}
+
+impl Token {
+    /// Create a new [Token].
+    pub fn new(kind: TokenKind, range: ByteRange) -> Self {
+        Self { kind, range }
+    }
+
+    /// Check whether the token is of the given kind.
+    pub fn is(&self, kind: TokenKind) -> bool {
+        self.kind == kind
+    }
+}
@@ -11,3 +11,19 @@ pub fn normalize_path<P: AsRef<Path>>(path: P) -> PathBuf {
     }
     path.to_path_buf()
 }
+
+/// Convert a path to be relative to the given `root` if it is within it,
+/// otherwise the path is returned as is.
+pub fn relativize_path_to<P: AsRef<Path>, R: AsRef<Path>>(path: P, root: R) -> String {
+    let path = path.as_ref();
+    path.strip_prefix(root.as_ref()).unwrap_or(path).display().to_string()
+}
+
+/// Convert a path to be relative to the current working directory, if it is
+/// within it.
+pub fn relativize_path<P: AsRef<Path>>(path: P) -> String {
+    match std::env::current_dir() {
+        Ok(root) => relativize_path_to(path, root),
+        Err(_) => path.as_ref().display().to_string(),
+    }
+}
@@ -1,5 +1,61 @@
 //! Hash Compiler error and warning reporting module.
-use std::ops::BitOr;
+use std::{
+    io::IsTerminal,
+    ops::BitOr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use clap::ValueEnum;
+
+/// Whether output should be coloured.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColourChoice {
+    /// Colour the output if `stdout` is a terminal, and the `NO_COLOR`
+    /// environment variable is not set.
+    #[default]
+    Auto,
+
+    /// Always colour the output.
+    Always,
+
+    /// Never colour the output.
+    Never,
+}
+
+impl ColourChoice {
+    /// Resolve the [ColourChoice] into whether colours should be emitted.
+    pub fn should_colour(self) -> bool {
+        match self {
+            ColourChoice::Auto => {
+                let no_colour = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                !no_colour && std::io::stdout().is_terminal()
+            }
+            ColourChoice::Always => true,
+            ColourChoice::Never => false,
+        }
+    }
+}
+
+/// Whether colours are enabled, `0` means that this hasn't been resolved yet.
+static COLOURS: AtomicU8 = AtomicU8::new(0);
+
+/// Set the global [ColourChoice] that is used by [highlight] and by any other
+/// emitters that produce coloured output.
+pub fn set_colour_choice(choice: ColourChoice) {
+    COLOURS.store(if choice.should_colour() { 1 } else { 2 }, Ordering::Relaxed);
+}
+
+/// Check whether coloured output is enabled. If no [ColourChoice] has been set,
+/// then this is resolved using [ColourChoice::Auto].
+pub fn colours_enabled() -> bool {
+    match COLOURS.load(Ordering::Relaxed) {
+        0 => {
+            set_colour_choice(ColourChoice::Auto);
+            colours_enabled()
+        }
+        value => value == 1,
+    }
+}
 
 /// Variants of highlighter colour that can be used.
 pub enum Colour {
@@ -96,9 +152,15 @@ impl Highlighter for Decoration {
 /// General function to apply a highlighter on a string. This will call the
 /// provided [Highlighter] implementation and then apply it to the passed
 /// message, resetting the effect at the end of the message.
+///
+/// If colours are disabled, the message is returned as is.
 pub fn highlight(highlighter: impl Highlighter, message: impl ToString) -> String {
     const RESET: &str = "\u{001b}[0m";
 
+    if !colours_enabled() {
+        return message.to_string();
+    }
+
     highlighter
         .escape_code()
         .chars()
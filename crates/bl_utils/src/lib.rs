@@ -2,10 +2,13 @@
 
 pub mod counter;
 pub mod fs;
+pub mod hash;
 pub mod highlight;
 pub mod logging;
 pub mod printing;
 pub mod stream;
+pub mod suggest;
 mod timers;
+pub mod tree_writing;
 
 pub use timers::timed;
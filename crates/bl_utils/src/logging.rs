@@ -66,6 +66,22 @@ impl ToolLogger {
         self.messaging_format.set(format).unwrap();
     }
 
+    /// Get the [MessagingFormat] of the logger.
+    pub fn messaging_format(&self) -> MessagingFormat {
+        self.messaging_format.get().copied().unwrap_or_default()
+    }
+
+    /// Write the record as a JSON object on a single line, i.e.
+    /// `{"level":"warn","message":"..."}`.
+    fn log_json(&self, out: &mut dyn Write, record: &Record) {
+        let message = serde_json::json!({
+            "level": record.level().as_str().to_lowercase(),
+            "message": record.args().to_string(),
+        });
+
+        stream_writeln!(out, "{message}");
+    }
+
     fn log_default(&self, out: &mut dyn Write, record: &Record, level_prefix: String) {
         stream_writeln!(
             out,
@@ -86,6 +102,19 @@ impl Log for ToolLogger {
             return;
         }
 
+        // ##Note: when emitting JSON, the output stream is reserved for the
+        // structured results, so all log records are written to the error stream.
+        if self.messaging_format() == MessagingFormat::Json {
+            self.log_json(&mut self.error_stream.get().unwrap().clone(), record);
+            return;
+        }
+
+        let mut out = if record.level() == Level::Error {
+            self.error_stream.get().unwrap().clone()
+        } else {
+            self.output_stream.get().unwrap().clone()
+        };
+
         // Custom colour formatting for the log level
         let level_prefix = match record.level() {
             Level::Error => highlight(Colour::Red | Modifier::Bold, "error"),
@@ -95,12 +124,6 @@ impl Log for ToolLogger {
             Level::Trace => highlight(Colour::Magenta | Modifier::Bold, "trace"),
         };
 
-        let mut out = if record.level() == Level::Error {
-            self.error_stream.get().unwrap().clone()
-        } else {
-            self.output_stream.get().unwrap().clone()
-        };
-
         self.log_default(&mut out, record, level_prefix);
     }
 
@@ -0,0 +1,20 @@
+//! Utilities for suggesting alternatives for unknown names, i.e. when a user
+//! misspells a configuration key or a rule code.
+
+/// The minimum similarity that a candidate needs to have with the unknown
+/// value to be suggested.
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Find the candidate that is most similar to the given `value`, if any of
+/// them are similar enough to be a likely misspelling.
+pub fn did_you_mean<'c, I>(value: &str, candidates: I) -> Option<&'c str>
+where
+    I: IntoIterator<Item = &'c str>,
+{
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::jaro_winkler(value, candidate), candidate))
+        .filter(|(similarity, _)| *similarity >= SIMILARITY_THRESHOLD)
+        .max_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs))
+        .map(|(_, candidate)| candidate)
+}
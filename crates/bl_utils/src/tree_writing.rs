@@ -0,0 +1,133 @@
+//! Utilities for writing tree-like structures to the terminal. This is
+//! primarily used for debugging purposes, i.e. printing the AST of a template.
+
+use std::{borrow::Cow, fmt};
+
+/// A generic tree node, which has a label, an optional range of bytes within
+/// some source that it represents, and a list of children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    /// The label of the node.
+    pub label: Cow<'static, str>,
+
+    /// The byte range (inclusive) that this node covers within the source.
+    pub span: Option<(usize, usize)>,
+
+    /// The children of this node.
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    /// Create a new [TreeNode] with the given label and no children.
+    pub fn leaf(label: impl Into<Cow<'static, str>>) -> Self {
+        Self { label: label.into(), span: None, children: vec![] }
+    }
+
+    /// Create a new [TreeNode] with the given label and children.
+    pub fn branch(label: impl Into<Cow<'static, str>>, children: Vec<TreeNode>) -> Self {
+        Self { label: label.into(), span: None, children }
+    }
+
+    /// Attach a byte range to the [TreeNode].
+    pub fn with_span(mut self, start: usize, end: usize) -> Self {
+        self.span = Some((start, end));
+        self
+    }
+
+    /// Write the label of the node, along with the span if it is present.
+    fn fmt_label(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)?;
+
+        if let Some((start, end)) = self.span {
+            write!(f, " @{start}:{end}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a [TreeNode] as an indented tree, using box drawing characters to
+/// denote the structure of the tree:
+///
+/// ```text
+/// Document @0:12
+/// └─children
+///   └─Var @0:12
+/// ```
+pub struct TreeWriter<'t> {
+    tree: &'t TreeNode,
+}
+
+impl<'t> TreeWriter<'t> {
+    /// Create a new [TreeWriter] for the given tree.
+    pub fn new(tree: &'t TreeNode) -> Self {
+        Self { tree }
+    }
+
+    fn fmt_children(
+        f: &mut fmt::Formatter<'_>,
+        node: &TreeNode,
+        prefix: &mut String,
+    ) -> fmt::Result {
+        for (index, child) in node.children.iter().enumerate() {
+            let is_last = index == node.children.len() - 1;
+
+            write!(f, "{prefix}{}", if is_last { "└─" } else { "├─" })?;
+            child.fmt_label(f)?;
+            writeln!(f)?;
+
+            let len = prefix.len();
+            prefix.push_str(if is_last { "  " } else { "│ " });
+            Self::fmt_children(f, child, prefix)?;
+            prefix.truncate(len);
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for TreeWriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tree.fmt_label(f)?;
+        writeln!(f)?;
+        Self::fmt_children(f, self.tree, &mut String::new())
+    }
+}
+
+/// Writes a [TreeNode] as an S-expression, each node is written as a list
+/// containing the label, an optional span and then all of the children:
+///
+/// ```text
+/// (Document @0:12
+///   (children
+///     (Var @0:12)))
+/// ```
+pub struct SExpWriter<'t> {
+    tree: &'t TreeNode,
+}
+
+impl<'t> SExpWriter<'t> {
+    /// Create a new [SExpWriter] for the given tree.
+    pub fn new(tree: &'t TreeNode) -> Self {
+        Self { tree }
+    }
+
+    fn fmt_node(f: &mut fmt::Formatter<'_>, node: &TreeNode, depth: usize) -> fmt::Result {
+        write!(f, "(")?;
+        node.fmt_label(f)?;
+
+        for child in &node.children {
+            write!(f, "\n{:indent$}", "", indent = (depth + 1) * 2)?;
+            Self::fmt_node(f, child, depth + 1)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for SExpWriter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Self::fmt_node(f, self.tree, 0)?;
+        writeln!(f)
+    }
+}
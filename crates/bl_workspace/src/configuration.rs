@@ -0,0 +1,462 @@
+//! The [Configuration] is the resolved form of the [Options] of a single
+//! configuration file, where all of the patterns are made absolute and all of
+//! the rule selectors are parsed. Configurations can be combined with one
+//! another (i.e. overriding a configuration file with command line arguments)
+//! and are then converted into the final [Settings].
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use bl_lints::{
+    registry::{all_rules, rule_by_code, rule_by_name, RuleSelector},
+    rule::Rule,
+    settings::{Dialect, FixMode, Severity, UnsafeFixes},
+};
+use bl_parse::lexer::Delimiters;
+use bl_utils::{fs::normalize_path, suggest::did_you_mean};
+
+use crate::{
+    options::{DelimiterOptions, Options},
+    settings::{
+        FilePattern, FilePatternSet, FileResolverSettings, LinterSettings, PerFileIgnore,
+        RuleOptions, Settings, EXCLUDE, INCLUDE,
+    },
+};
+
+/// The default maximum length of a line.
+pub const DEFAULT_LINE_LENGTH: usize = 120;
+
+#[derive(Debug, Clone, Default)]
+pub struct Configuration {
+    pub include: Option<Vec<FilePattern>>,
+    pub exclude: Option<Vec<FilePattern>>,
+    pub extend_exclude: Vec<FilePattern>,
+    pub extend_include: Vec<FilePattern>,
+    pub force_exclude: Option<bool>,
+    pub respect_gitignore: Option<bool>,
+    pub fix_mode: Option<FixMode>,
+    pub unsafe_fixes: Option<UnsafeFixes>,
+    pub extend_safe_fixes: Vec<RuleSelector>,
+    pub extend_unsafe_fixes: Vec<RuleSelector>,
+    pub rule_selections: Vec<RuleSelection>,
+    pub preview: Option<bool>,
+    pub per_file_ignores: Option<Vec<(FilePattern, Vec<RuleSelector>)>>,
+    pub severity: Vec<Vec<(RuleSelector, Severity)>>,
+    pub dialect: Option<Dialect>,
+    pub extension: BTreeMap<String, Dialect>,
+    pub delimiters: DelimiterOptions,
+    pub line_length: Option<usize>,
+    pub rules: BTreeMap<&'static str, RuleOptions>,
+}
+
+impl Configuration {
+    /// Resolve the given [Options], any relative patterns are resolved against
+    /// the given `root` directory.
+    pub fn from_options(options: Options, root: &Path) -> Result<Self> {
+        let patterns = |patterns: Vec<String>| {
+            patterns.into_iter().map(|pattern| FilePattern::user(pattern, root)).collect::<Vec<_>>()
+        };
+
+        // Files with any of the configured extensions are always included.
+        let extension = options.extension.unwrap_or_default();
+        let extend_include = options
+            .extend_include
+            .into_iter()
+            .flatten()
+            .chain(extension.keys().map(|extension| format!("*.{extension}")))
+            .collect();
+
+        let per_file_ignores = options
+            .per_file_ignores
+            .map(|ignores| {
+                ignores
+                    .into_iter()
+                    .map(|(pattern, selectors)| {
+                        Ok((FilePattern::user(pattern, root), parse_selectors(selectors)?))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let severity = options
+            .severity
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(selector, severity)| Ok((selector.parse()?, severity)))
+            .collect::<Result<_>>()?;
+
+        let rules = options
+            .rules
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, options)| Ok((rule_code(&key)?, RuleOptions(options))))
+            .collect::<Result<_>>()?;
+
+        if options.line_length == Some(0) {
+            bail!("`line-length` must be greater than zero");
+        }
+
+        Ok(Configuration {
+            include: options.include.map(patterns),
+            exclude: options.exclude.map(patterns),
+            extend_exclude: options.extend_exclude.map(patterns).unwrap_or_default(),
+            extend_include: patterns(extend_include),
+            force_exclude: options.force_exclude,
+            respect_gitignore: options.respect_gitignore,
+            fix_mode: None,
+            unsafe_fixes: options.unsafe_fixes.map(UnsafeFixes::from),
+            extend_safe_fixes: parse_selectors(options.extend_safe_fixes.unwrap_or_default())?,
+            extend_unsafe_fixes: parse_selectors(options.extend_unsafe_fixes.unwrap_or_default())?,
+            rule_selections: vec![RuleSelection {
+                select: options.select.map(parse_selectors).transpose()?,
+                extend_select: parse_selectors(options.extend_select.unwrap_or_default())?,
+                ignore: parse_selectors(options.ignore.unwrap_or_default())?,
+            }],
+            preview: options.preview,
+            per_file_ignores,
+            severity: vec![severity],
+            dialect: options.dialect,
+            extension,
+            delimiters: options.delimiters.unwrap_or_default(),
+            line_length: options.line_length,
+            rules,
+        })
+    }
+
+    /// Read and resolve the configuration file at the given path, relative
+    /// patterns are resolved against the directory of the file. If the file
+    /// extends another configuration file, then that file is read too and
+    /// combined with this one.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Configuration::from_file_extending(&normalize_path(path), &mut vec![])
+    }
+
+    /// Read the configuration file at the given path, `visited` holds all of
+    /// the files that extend this one, which is used to detect cycles.
+    fn from_file_extending(path: &Path, visited: &mut Vec<PathBuf>) -> Result<Self> {
+        if visited.iter().any(|visited| visited == path) {
+            bail!("circular `extend` of `{}`", path.display());
+        }
+        visited.push(path.to_path_buf());
+
+        let mut options = Options::from_file(path)?;
+        let root = path.parent().unwrap_or(Path::new("/"));
+        let extend = options.extend.take().map(|extend| normalize_path(root.join(extend)));
+
+        let configuration = Configuration::from_options(options, root)
+            .with_context(|| format!("invalid configuration in `{}`", path.display()))?;
+
+        match extend {
+            Some(extend) => {
+                let base = Configuration::from_file_extending(&extend, visited)
+                    .with_context(|| format!("failed to extend `{}`", extend.display()))?;
+                Ok(configuration.combine(base))
+            }
+            None => Ok(configuration),
+        }
+    }
+
+    /// Combine this configuration with a `base` configuration, values that
+    /// are set in this configuration take precedence.
+    pub fn combine(self, base: Configuration) -> Configuration {
+        let mut rules = base.rules;
+        rules.extend(self.rules);
+
+        let mut extension = base.extension;
+        extension.extend(self.extension);
+
+        Configuration {
+            include: self.include.or(base.include),
+            exclude: self.exclude.or(base.exclude),
+            extend_exclude: base.extend_exclude.into_iter().chain(self.extend_exclude).collect(),
+            extend_include: base.extend_include.into_iter().chain(self.extend_include).collect(),
+            force_exclude: self.force_exclude.or(base.force_exclude),
+            respect_gitignore: self.respect_gitignore.or(base.respect_gitignore),
+            fix_mode: self.fix_mode.or(base.fix_mode),
+            unsafe_fixes: self.unsafe_fixes.or(base.unsafe_fixes),
+            extend_safe_fixes: base
+                .extend_safe_fixes
+                .into_iter()
+                .chain(self.extend_safe_fixes)
+                .collect(),
+            extend_unsafe_fixes: base
+                .extend_unsafe_fixes
+                .into_iter()
+                .chain(self.extend_unsafe_fixes)
+                .collect(),
+            rule_selections: base.rule_selections.into_iter().chain(self.rule_selections).collect(),
+            preview: self.preview.or(base.preview),
+            per_file_ignores: self.per_file_ignores.or(base.per_file_ignores),
+            severity: base.severity.into_iter().chain(self.severity).collect(),
+            dialect: self.dialect.or(base.dialect),
+            extension,
+            delimiters: DelimiterOptions {
+                block_start: self.delimiters.block_start.or(base.delimiters.block_start),
+                block_end: self.delimiters.block_end.or(base.delimiters.block_end),
+                variable_start: self.delimiters.variable_start.or(base.delimiters.variable_start),
+                variable_end: self.delimiters.variable_end.or(base.delimiters.variable_end),
+                comment_start: self.delimiters.comment_start.or(base.delimiters.comment_start),
+                comment_end: self.delimiters.comment_end.or(base.delimiters.comment_end),
+            },
+            line_length: self.line_length.or(base.line_length),
+            rules,
+        }
+    }
+
+    /// Convert the configuration into [Settings], unset values fall back to
+    /// their defaults.
+    pub fn into_settings(self) -> Result<Settings> {
+        // Every rule is enabled by default, and then each selection is applied
+        // in turn, from the furthest base configuration to the command line.
+        // Preview rules only run in preview mode, even when selected.
+        let preview = self.preview.unwrap_or(false);
+
+        // The severity of each rule is set by the latest configuration that
+        // matches it, the most specific selector of that configuration wins.
+        let severity: BTreeMap<_, _> = all_rules()
+            .filter_map(|rule| {
+                self.severity.iter().rev().find_map(|layer| {
+                    layer
+                        .iter()
+                        .filter(|(selector, _)| selector.matches(rule))
+                        .max_by_key(|(selector, _)| selector.specificity())
+                        .map(|(_, severity)| (rule.code(), *severity))
+                })
+            })
+            .collect();
+
+        let rules = all_rules()
+            .filter(|rule| {
+                self.rule_selections
+                    .iter()
+                    .fold(true, |enabled, selection| selection.is_enabled(*rule, enabled))
+            })
+            .filter(|rule| preview || !rule.preview())
+            .filter(|rule| severity.get(rule.code()) != Some(&Severity::Off))
+            .map(|rule| rule.code())
+            .collect();
+
+        let matching = |selectors: &[RuleSelector]| {
+            all_rules()
+                .filter(|rule| selectors.iter().any(|selector| selector.matches(*rule)))
+                .map(|rule| rule.code())
+                .collect::<Vec<_>>()
+        };
+
+        let per_file_ignores = self
+            .per_file_ignores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(pattern, selectors)| {
+                let rules = matching(&selectors);
+                Ok(PerFileIgnore { pattern: FilePatternSet::try_from_iter([pattern])?, rules })
+            })
+            .collect::<Result<_>>()?;
+
+        let defaults = Delimiters::default();
+        let delimiters = Delimiters {
+            block_start: self.delimiters.block_start.unwrap_or(defaults.block_start),
+            block_end: self.delimiters.block_end.unwrap_or(defaults.block_end),
+            variable_start: self.delimiters.variable_start.unwrap_or(defaults.variable_start),
+            variable_end: self.delimiters.variable_end.unwrap_or(defaults.variable_end),
+            comment_start: self.delimiters.comment_start.unwrap_or(defaults.comment_start),
+            comment_end: self.delimiters.comment_end.unwrap_or(defaults.comment_end),
+        };
+
+        if [
+            &delimiters.block_start,
+            &delimiters.block_end,
+            &delimiters.variable_start,
+            &delimiters.variable_end,
+            &delimiters.comment_start,
+            &delimiters.comment_end,
+        ]
+        .iter()
+        .any(|delimiter| delimiter.is_empty())
+        {
+            bail!("delimiters must not be empty");
+        }
+
+        let include = self.include.unwrap_or_else(|| INCLUDE.to_vec());
+        let include = include.into_iter().chain(self.extend_include);
+        let exclude = self.exclude.unwrap_or_else(|| EXCLUDE.to_vec());
+
+        Ok(Settings {
+            respect_gitignore: self.respect_gitignore.unwrap_or(true),
+            file_resolver: FileResolverSettings {
+                include: FilePatternSet::try_from_iter(include)?,
+                exclude: FilePatternSet::try_from_iter(exclude)?,
+                user_exclude: FilePatternSet::try_from_iter(self.extend_exclude)?,
+                force_exclude: self.force_exclude.unwrap_or(false),
+            },
+            linter_settings: LinterSettings {
+                fix_mode: self.fix_mode.unwrap_or_default(),
+                unsafe_fixes: self.unsafe_fixes.unwrap_or_default(),
+                extend_safe_fixes: matching(&self.extend_safe_fixes),
+                extend_unsafe_fixes: matching(&self.extend_unsafe_fixes),
+                preview,
+                rules,
+                per_file_ignores,
+                severity,
+                dialect: self.dialect.unwrap_or_default(),
+                extension: self.extension,
+                delimiters,
+                line_length: self.line_length.unwrap_or(DEFAULT_LINE_LENGTH),
+                rule_options: self.rules,
+            },
+        })
+    }
+}
+
+/// The rules that are selected and ignored by a single configuration, or by
+/// the command line. Selections are applied in order, so that a configuration
+/// can adjust the rules that are enabled by the configuration it extends.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSelection {
+    /// The rules that are enabled, replacing those that were enabled before.
+    pub select: Option<Vec<RuleSelector>>,
+
+    /// Rules that are enabled in addition to the existing rules.
+    pub extend_select: Vec<RuleSelector>,
+
+    /// The rules that are disabled.
+    pub ignore: Vec<RuleSelector>,
+}
+
+impl RuleSelection {
+    /// Check whether the rule is enabled after this selection is applied,
+    /// given whether it was `enabled` beforehand. When a rule is both selected
+    /// and ignored, the most specific selector takes precedence, and ignoring
+    /// wins when they are equally specific.
+    pub fn is_enabled(&self, rule: &dyn Rule, enabled: bool) -> bool {
+        let specificity = |selectors: &mut dyn Iterator<Item = &RuleSelector>| {
+            selectors.filter(|selector| selector.matches(rule)).map(RuleSelector::specificity).max()
+        };
+
+        let selected = specificity(&mut self.select.iter().flatten().chain(&self.extend_select));
+        let ignored = specificity(&mut self.ignore.iter());
+
+        match (selected, ignored) {
+            (Some(selected), Some(ignored)) => selected > ignored,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => enabled && self.select.is_none(),
+        }
+    }
+}
+
+/// Parse all of the given rule selectors.
+fn parse_selectors(selectors: Vec<String>) -> Result<Vec<RuleSelector>> {
+    selectors.iter().map(|selector| selector.parse()).collect()
+}
+
+/// Find the code of the rule with the given name or code.
+fn rule_code(key: &str) -> Result<&'static str> {
+    if let Some(rule) = rule_by_code(key).or_else(|| rule_by_name(key)) {
+        return Ok(rule.code());
+    }
+
+    match did_you_mean(key, all_rules().flat_map(|rule| [rule.code(), rule.name()])) {
+        Some(suggestion) => bail!("unknown rule `{key}` in `rules`, did you mean `{suggestion}`?"),
+        None => bail!("unknown rule `{key}` in `rules`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bl_diagnostics::DiagnosticKind;
+    use bl_lints::settings::Dialect;
+
+    use super::{Configuration, RuleSelection};
+
+    #[test]
+    fn extend_inherits_and_detects_cycles() {
+        let root = std::env::temp_dir().join(format!("bracketlint-extend-{}", std::process::id()));
+        let app = root.join("app");
+        std::fs::create_dir_all(&app).unwrap();
+
+        let write = |path: &Path, contents: &str| std::fs::write(path, contents).unwrap();
+        write(&root.join("bracketlint.toml"), "dialect = \"twig\"\nline-length = 80\n");
+        write(
+            &app.join("bracketlint.toml"),
+            "extend = \"../bracketlint.toml\"\nline-length = 100\n",
+        );
+
+        let settings = Configuration::from_file(&app.join("bracketlint.toml"))
+            .unwrap()
+            .into_settings()
+            .unwrap()
+            .linter_settings;
+        assert_eq!(settings.dialect, Dialect::Twig);
+        assert_eq!(settings.line_length, 100);
+
+        write(&root.join("bracketlint.toml"), "extend = \"app/bracketlint.toml\"\n");
+        let error = Configuration::from_file(&app.join("bracketlint.toml")).unwrap_err();
+        assert!(format!("{error:#}").contains("circular `extend`"), "{error:#}");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rule_selections_apply_in_order() {
+        let selection = |select: Option<&[&str]>, extend_select: &[&str], ignore: &[&str]| {
+            let parse = |selectors: &[&str]| {
+                selectors.iter().map(|selector| selector.parse().unwrap()).collect::<Vec<_>>()
+            };
+            RuleSelection {
+                select: select.map(parse),
+                extend_select: parse(extend_select),
+                ignore: parse(ignore),
+            }
+        };
+        let rules = |rule_selections: Vec<RuleSelection>| {
+            Configuration { rule_selections, ..Configuration::default() }
+                .into_settings()
+                .unwrap()
+                .linter_settings
+                .rules
+        };
+
+        assert_eq!(rules(vec![]), ["BL001", "BL002", "BL003"]);
+
+        // The more specific selector wins, regardless of whether it selects or
+        // ignores the rule.
+        assert_eq!(rules(vec![selection(Some(&["BL002"]), &[], &["correctness"])]), ["BL002"]);
+        assert_eq!(rules(vec![selection(Some(&["correctness"]), &[], &["BL002"])]), ["BL001"]);
+        assert!(rules(vec![selection(Some(&["BL002"]), &[], &["BL002"])]).is_empty());
+
+        // Later selections adjust the rules of earlier selections, unless they
+        // replace them with `select`.
+        let base = selection(Some(&["correctness"]), &[], &["BL001"]);
+        assert_eq!(rules(vec![base.clone(), selection(None, &["BL003"], &[])]), ["BL002", "BL003"]);
+        assert_eq!(rules(vec![base, selection(Some(&["style"]), &[], &[])]), ["BL003"]);
+    }
+
+    #[test]
+    fn severity_overrides() {
+        let options = "[severity]\ncorrectness = \"warning\"\nBL002 = \"info\"\nstyle = \"off\"\n";
+        let configuration =
+            Configuration::from_options(toml::from_str(options).unwrap(), Path::new("/")).unwrap();
+        let settings = configuration.clone().into_settings().unwrap().linter_settings;
+
+        assert_eq!(settings.rules, ["BL001", "BL002"]);
+        assert_eq!(
+            settings.diagnostic_kind("BL001", DiagnosticKind::Error),
+            DiagnosticKind::Warning
+        );
+        assert_eq!(settings.diagnostic_kind("BL002", DiagnosticKind::Error), DiagnosticKind::Note);
+
+        // A later configuration takes precedence, even when it is less specific.
+        let child = "[severity]\ncorrectness = \"error\"\n";
+        let child =
+            Configuration::from_options(toml::from_str(child).unwrap(), Path::new("/")).unwrap();
+        let settings = child.combine(configuration).into_settings().unwrap().linter_settings;
+        assert_eq!(settings.rules, ["BL001", "BL002"]);
+        assert_eq!(settings.diagnostic_kind("BL002", DiagnosticKind::Error), DiagnosticKind::Error);
+    }
+}
@@ -3,16 +3,18 @@
 //! and information about a particular lint run. The [WorkspaceBuilder] is
 //! responsible for creating a [Workspace] instance.
 
+pub mod configuration;
 mod member;
+pub mod options;
 pub mod resolver;
 pub mod settings;
 
 use std::{collections::HashMap, path::PathBuf};
 
-use bl_lints::settings::FixMode;
 use bl_utils::stream::CompilerOutputStream;
 use index_vec::IndexVec;
 pub use member::{Member, MemberId};
+use resolver::Discovery;
 use settings::Settings;
 
 #[derive(Default)]
@@ -48,6 +50,27 @@ impl WorkspaceMembers {
     pub fn get_member_by_id(&self, id: MemberId) -> Option<&Member> {
         self.members.get(id)
     }
+
+    /// Get the number of members in the [Workspace].
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Check whether the [Workspace] has no members.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Iterate over all of the members, in the order that they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &Member> {
+        self.members.iter()
+    }
+
+    /// Get all of the members as a mutable slice, this is used to process the
+    /// members in parallel.
+    pub fn as_mut_slice(&mut self) -> &mut [Member] {
+        self.members.as_raw_slice_mut()
+    }
 }
 
 pub struct Workspace {
@@ -62,6 +85,9 @@ pub struct Workspace {
 
     /// The [Settings] for the [Workspace].
     pub settings: Settings,
+
+    /// How the settings of nested directories are discovered.
+    pub discovery: Discovery,
 }
 
 impl Workspace {}
@@ -76,11 +102,14 @@ pub struct WorkspaceBuilder {
 
     /// Optionally set the [Settings] for the [Workspace].
     settings: Option<Settings>,
+
+    /// Optionally set how the settings of nested directories are discovered.
+    discovery: Option<Discovery>,
 }
 
 impl WorkspaceBuilder {
     pub fn new() -> Self {
-        WorkspaceBuilder { stdout: None, stderr: None, settings: None }
+        WorkspaceBuilder { stdout: None, stderr: None, settings: None, discovery: None }
     }
 
     pub fn with_stdout(mut self, stdout: CompilerOutputStream) -> Self {
@@ -98,14 +127,17 @@ impl WorkspaceBuilder {
         self
     }
 
+    pub fn with_discovery(mut self, discovery: Discovery) -> Self {
+        self.discovery = Some(discovery);
+        self
+    }
+
     pub fn build(self) -> Workspace {
         Workspace {
             stdout: self.stdout.unwrap_or_else(CompilerOutputStream::stdout),
             stderr: self.stderr.unwrap_or_else(CompilerOutputStream::stderr),
-            settings: self.settings.unwrap_or_else(|| Settings::new(true, FixMode::default())), /* @@Todo: actually
-                                                                                                 * create a default
-                                                                                                 * for this or
-                                                                                                 * something? */
+            settings: self.settings.unwrap_or_default(),
+            discovery: self.discovery.unwrap_or_default(),
             members: WorkspaceMembers::new(),
         }
     }
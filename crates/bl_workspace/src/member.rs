@@ -14,14 +14,29 @@ pub struct Member {
     /// The raw file contents of the member.
     pub contents: String,
 
+    /// The id of the source of the member within the [ast::SourceMap].
+    pub source_id: ast::SourceId,
+
     /// The parsed document of the member.
     pub document: Option<ast::AstNode<ast::Document>>,
 }
 
 impl Member {
-    /// Create a new [Member] with the given contents.
+    /// Create a new [Member] with the given contents, the contents are also
+    /// registered within the [ast::SourceMap] so that diagnostics can refer
+    /// to them.
     pub fn new(path: PathBuf, contents: String) -> Self {
-        Member { path, contents, document: None }
+        let source_id = ast::SourceMap::add(path.clone(), contents.clone());
+        Member { path, contents, source_id, document: None }
+    }
+
+    /// Replace the contents of the [Member], i.e. after fixes have been
+    /// applied. The new contents are registered as a new source within the
+    /// [ast::SourceMap], and the previously parsed document is discarded.
+    pub fn set_contents(&mut self, contents: String) {
+        self.source_id = ast::SourceMap::add(self.path.clone(), contents.clone());
+        self.contents = contents;
+        self.document = None;
     }
 }
 
@@ -0,0 +1,259 @@
+//! Definitions of the [Options] that can be set within a configuration file,
+//! and the discovery of configuration files.
+//!
+//! The configuration is read from the first of `bracketlint.toml`,
+//! `.bracketlint.toml` or a `pyproject.toml` with a `[tool.bracketlint]` table
+//! that is found when walking up from the current directory.
+//!
+//! Each directory may contain its own configuration file, which applies to all
+//! of the files beneath it instead of the configuration of the parent
+//! directory. Options can be inherited from another configuration file with
+//! `extend`, i.e.
+//!
+//! ```toml
+//! extend = "../bracketlint.toml"
+//! extend-exclude = ["vendor"]
+//! select = ["correctness", "BL1"]
+//! extend-select = ["BL002"]
+//! ignore = ["duplicate-block"]
+//! preview = true
+//! dialect = "django"
+//! extension = { tpl = "django" }
+//! line-length = 100
+//! unsafe-fixes = false
+//! extend-safe-fixes = ["BL012"]
+//!
+//! [per-file-ignores]
+//! "emails/*.html" = ["BL002"]
+//!
+//! [severity]
+//! style = "warning"
+//! duplicate-block = "off"
+//!
+//! [delimiters]
+//! variable-start = "[["
+//! variable-end = "]]"
+//!
+//! [rules.duplicate-block]
+//! some-option = true
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use bl_lints::settings::{Dialect, Severity};
+use bl_utils::suggest::did_you_mean;
+use serde::Deserialize;
+
+/// The names of the files that configuration is read from, in order of
+/// precedence within a single directory.
+pub const CONFIG_FILES: &[&str] = &["bracketlint.toml", ".bracketlint.toml", "pyproject.toml"];
+
+/// The options that can be specified within a configuration file. All of the
+/// options are optional, unset options fall back to their defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Options {
+    /// Another configuration file that this configuration inherits from,
+    /// relative to the directory of this configuration file.
+    pub extend: Option<PathBuf>,
+
+    /// Glob patterns of the files that are checked, replacing the defaults.
+    pub include: Option<Vec<String>>,
+
+    /// Glob patterns of the files and directories that are excluded, replacing
+    /// the defaults.
+    pub exclude: Option<Vec<String>>,
+
+    /// Glob patterns that are excluded in addition to `exclude`.
+    pub extend_exclude: Option<Vec<String>>,
+
+    /// Glob patterns of the files that are checked in addition to `include`.
+    pub extend_include: Option<Vec<String>>,
+
+    /// Whether to apply the exclusions to paths that are passed directly on
+    /// the command line.
+    pub force_exclude: Option<bool>,
+
+    /// Whether to respect `.gitignore` and other standard ignore files.
+    pub respect_gitignore: Option<bool>,
+
+    /// The rules that are enabled, as rule selectors.
+    pub select: Option<Vec<String>>,
+
+    /// Rules that are enabled in addition to `select`, as rule selectors.
+    pub extend_select: Option<Vec<String>>,
+
+    /// The rules that are disabled, as rule selectors.
+    pub ignore: Option<Vec<String>>,
+
+    /// Whether preview rules are enabled.
+    pub preview: Option<bool>,
+
+    /// Whether unsafe fixes are applied, when unset they aren't applied but a
+    /// hint is shown when any are available.
+    pub unsafe_fixes: Option<bool>,
+
+    /// Rule selectors whose unsafe fixes are promoted to safe fixes.
+    pub extend_safe_fixes: Option<Vec<String>>,
+
+    /// Rule selectors whose safe fixes are demoted to unsafe fixes.
+    pub extend_unsafe_fixes: Option<Vec<String>>,
+
+    /// Rules that are disabled for the files that match each glob pattern.
+    pub per_file_ignores: Option<BTreeMap<String, Vec<String>>>,
+
+    /// The severity of the rules that each rule selector matches, the most
+    /// specific selector takes precedence. The severity of a configuration
+    /// that extends this one takes precedence over any selector here.
+    pub severity: Option<BTreeMap<String, Severity>>,
+
+    /// The template language of files whose extension isn't specific to a
+    /// single template language.
+    pub dialect: Option<Dialect>,
+
+    /// The template language of files with each extension, files with these
+    /// extensions are also checked.
+    pub extension: Option<BTreeMap<String, Dialect>>,
+
+    /// Any custom delimiters that templates use.
+    pub delimiters: Option<DelimiterOptions>,
+
+    /// The maximum length of a line.
+    pub line_length: Option<usize>,
+
+    /// Options of individual rules, keyed by the name or code of the rule.
+    pub rules: Option<BTreeMap<String, toml::Table>>,
+}
+
+/// Overrides of the default delimiters, each delimiter that isn't set keeps
+/// its default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DelimiterOptions {
+    pub block_start: Option<String>,
+    pub block_end: Option<String>,
+    pub variable_start: Option<String>,
+    pub variable_end: Option<String>,
+    pub comment_start: Option<String>,
+    pub comment_end: Option<String>,
+}
+
+/// The parts of a `pyproject.toml` that are relevant, all other tables are
+/// ignored.
+#[derive(Deserialize)]
+struct Pyproject {
+    tool: Option<Tools>,
+}
+
+#[derive(Deserialize)]
+struct Tools {
+    bracketlint: Option<Options>,
+}
+
+impl Options {
+    /// Read the [Options] from the given configuration file, the options of a
+    /// `pyproject.toml` are read from its `[tool.bracketlint]` table.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+
+        let options = if is_pyproject(path) {
+            toml::from_str::<Pyproject>(&contents)
+                .map(|pyproject| pyproject.tool.and_then(|tool| tool.bracketlint))
+                .map(Option::unwrap_or_default)
+        } else {
+            toml::from_str::<Options>(&contents)
+        };
+
+        options
+            .map_err(with_suggestion)
+            .with_context(|| format!("failed to parse `{}`", path.display()))
+    }
+}
+
+/// Check whether the given path refers to a `pyproject.toml`.
+fn is_pyproject(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "pyproject.toml")
+}
+
+/// Add a "did you mean" suggestion to an error about an unknown key.
+///
+/// ##Note: `serde` reports unknown keys as "unknown field `key`, expected one
+/// of `a`, `b`", so the unknown key and all of the expected keys are the
+/// quoted parts of the message.
+fn with_suggestion(error: toml::de::Error) -> anyhow::Error {
+    if !error.message().starts_with("unknown field") {
+        return anyhow!("{error}");
+    }
+
+    let quoted = error.message().split('`').skip(1).step_by(2).collect::<Vec<_>>();
+    match quoted.split_first() {
+        Some((key, expected)) => match did_you_mean(key, expected.iter().copied()) {
+            Some(suggestion) => anyhow!("{error}help: did you mean `{suggestion}`?"),
+            None => anyhow!("{error}"),
+        },
+        None => anyhow!("{error}"),
+    }
+}
+
+/// Find the configuration file that applies to the given directory, checking
+/// the directory itself and then each of its ancestors.
+pub fn find_config_file(directory: &Path) -> Result<Option<PathBuf>> {
+    for ancestor in directory.ancestors() {
+        if let Some(path) = config_file_in(ancestor)? {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Find the configuration file within the given directory, if any. A
+/// `pyproject.toml` is only used if it has a `[tool.bracketlint]` table.
+pub fn config_file_in(directory: &Path) -> Result<Option<PathBuf>> {
+    for name in CONFIG_FILES {
+        let path = directory.join(name);
+        if !path.is_file() {
+            continue;
+        }
+
+        if is_pyproject(&path) {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read `{}`", path.display()))?;
+            let table: toml::Table = toml::from_str(&contents)
+                .with_context(|| format!("failed to parse `{}`", path.display()))?;
+
+            if !table.get("tool").is_some_and(|tool| tool.get("bracketlint").is_some()) {
+                continue;
+            }
+        }
+
+        return Ok(Some(path));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with_suggestion, Options};
+
+    #[test]
+    fn unknown_keys_are_suggested() {
+        let error = toml::from_str::<Options>("selct = [\"BL001\"]\n").unwrap_err();
+        let message = with_suggestion(error).to_string();
+        assert!(message.contains("unknown field `selct`"), "{message}");
+        assert!(message.ends_with("help: did you mean `select`?"), "{message}");
+
+        let error = toml::from_str::<Options>("[delimiters]\nblock-begin = \"<%\"\n").unwrap_err();
+        let message = with_suggestion(error).to_string();
+        assert!(message.ends_with("help: did you mean `block-end`?"), "{message}");
+
+        let error = toml::from_str::<Options>("unrelated = 1\n").unwrap_err();
+        assert!(!with_suggestion(error).to_string().contains("help:"));
+    }
+}
@@ -3,7 +3,9 @@
 
 use std::{
     cmp::Ordering,
+    collections::BTreeMap,
     ffi::OsStr,
+    fmt,
     path::{Path, PathBuf},
     sync::RwLock,
 };
@@ -15,16 +17,37 @@ use ignore::{DirEntry, Error, WalkBuilder, WalkState};
 use itertools::Itertools;
 use log::debug;
 
-use crate::settings::Settings;
+use crate::{configuration::Configuration, options::config_file_in, settings::Settings};
+
+/// How the [Settings] of each file are discovered.
+#[derive(Debug, Default)]
+pub enum Discovery {
+    /// Every file uses the root settings, i.e. when a configuration file is
+    /// given explicitly or configuration files are ignored.
+    #[default]
+    Fixed,
+
+    /// A configuration file within a directory takes precedence over the root
+    /// settings for all of the files beneath that directory. The given
+    /// command line overrides are applied to each configuration file.
+    Hierarchical(Configuration),
+}
 
-// @@Todo: add a way to add exclusions to the search.
 pub struct Resolver<'a> {
+    /// The settings of any file that isn't beneath a directory with its own
+    /// configuration file.
     settings: &'a Settings,
+
+    /// How the settings of nested directories are discovered.
+    discovery: &'a Discovery,
+
+    /// The settings of each directory that has its own configuration file.
+    directories: BTreeMap<PathBuf, Settings>,
 }
 
 impl<'a> Resolver<'a> {
-    pub fn new(settings: &'a Settings) -> Self {
-        Resolver { settings }
+    pub fn new(settings: &'a Settings, discovery: &'a Discovery) -> Self {
+        Resolver { settings, discovery, directories: BTreeMap::new() }
     }
 
     /// Check whether we should respect `.gitignore` files.
@@ -37,23 +60,119 @@ impl<'a> Resolver<'a> {
         self.settings.file_resolver.force_exclude
     }
 
-    pub fn resolve(&self, _: &Path) -> &Settings {
-        self.settings
+    /// Get the [Settings] of the given path, which are the settings of the
+    /// closest ancestor directory that has a configuration file.
+    pub fn resolve(&self, path: &Path) -> &Settings {
+        path.ancestors()
+            .find_map(|ancestor| self.directories.get(ancestor))
+            .unwrap_or(self.settings)
+    }
+
+    /// Check whether the configuration file of the given directory still
+    /// needs to be discovered.
+    fn needs_discovery(&self, directory: &Path) -> bool {
+        matches!(self.discovery, Discovery::Hierarchical(_))
+            && !self.directories.contains_key(directory)
+    }
+
+    /// Discover the configuration files of all of the ancestors of the given
+    /// path, this is needed for paths that are checked without walking the
+    /// directories above them.
+    pub fn discover_ancestors(&mut self, path: &Path) -> Result<()> {
+        for ancestor in path.ancestors().skip(1) {
+            self.discover(ancestor)?;
+        }
+        Ok(())
+    }
+
+    /// Discover the configuration file within the given directory, if there is
+    /// one then it applies to all of the files beneath the directory.
+    fn discover(&mut self, directory: &Path) -> Result<()> {
+        let Discovery::Hierarchical(overrides) = self.discovery else {
+            return Ok(());
+        };
+
+        if self.directories.contains_key(directory) {
+            return Ok(());
+        }
+
+        if let Some(path) = config_file_in(directory)? {
+            debug!("using configuration from `{}`", path.display());
+            let configuration = Configuration::from_file(&path)?;
+            let settings = overrides.clone().combine(configuration).into_settings()?;
+            self.directories.insert(directory.to_path_buf(), settings);
+        }
+
+        Ok(())
     }
 }
 
-pub fn find_files_in_paths(paths: &[PathBuf], settings: &Settings) -> Result<ResolvedFiles> {
+/// The setting that caused a path to be excluded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exclusion {
+    /// The path matched the `exclude` patterns.
+    Exclude,
+
+    /// The path matched the `extend-exclude` patterns.
+    ExtendExclude,
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exclusion::Exclude => write!(f, "`exclude`"),
+            Exclusion::ExtendExclude => write!(f, "`extend-exclude`"),
+        }
+    }
+}
+
+/// The result of searching for files within a set of paths.
+pub struct FileSearch<'a> {
+    /// All of the files that were found, and any errors that occurred whilst
+    /// walking the paths.
+    pub files: ResolvedFiles,
+
+    /// All of the paths that were excluded, along with the setting that
+    /// excluded them. Paths within an excluded directory aren't visited, and
+    /// so aren't included.
+    pub excluded: Vec<(PathBuf, Exclusion)>,
+
+    /// The resolver of the settings of each file.
+    pub resolver: Resolver<'a>,
+}
+
+/// Find all of the files within the given paths, along with the [Resolver] of
+/// their settings.
+pub fn find_files_in_paths<'a>(
+    paths: &[PathBuf],
+    settings: &'a Settings,
+    discovery: &'a Discovery,
+) -> Result<FileSearch<'a>> {
     // Create a resolver, and then use it to aid in the search for files.
-    let resolver = Resolver::new(settings);
+    let mut resolver = Resolver::new(settings, discovery);
 
     // Normalize every path (e.g., convert from relative to absolute).
     let mut paths: Vec<PathBuf> = paths.iter().map(fs::normalize_path).unique().collect();
 
+    // Discover the configuration files of the ancestors of each path, since
+    // they aren't visited whilst walking.
+    for path in &paths {
+        resolver.discover_ancestors(path)?;
+    }
+
     // Check if the paths themselves are excluded.
+    let mut excluded = vec![];
     if resolver.force_exclude() {
-        paths.retain(|path| !is_file_excluded(path, &resolver));
+        paths.retain(|path| match file_exclusion(path, &resolver) {
+            Some(exclusion) => {
+                excluded.push((path.clone(), exclusion));
+                false
+            }
+            None => true,
+        });
+
         if paths.is_empty() {
-            return Ok(vec![]);
+            return Ok(FileSearch { files: vec![], excluded, resolver });
         }
     }
 
@@ -80,29 +199,33 @@ pub fn find_files_in_paths(paths: &[PathBuf], settings: &Settings) -> Result<Res
     let mut visitor = FilesVisitorBuilder::new(&state);
     walker.visit(&mut visitor);
 
-    state.finish()
+    let mut search = state.finish()?;
+    search.excluded.append(&mut excluded);
+    Ok(search)
 }
 
-type ResolvedFiles = Vec<Result<ResolvedFile, ignore::Error>>;
+pub type ResolvedFiles = Vec<Result<ResolvedFile, ignore::Error>>;
+
+type ExcludedPaths = Vec<(PathBuf, Exclusion)>;
 
 pub struct WalkFilesState<'a> {
     resolver: RwLock<Resolver<'a>>,
-    merged: std::sync::Mutex<(ResolvedFiles, Result<()>)>,
+    merged: std::sync::Mutex<(ResolvedFiles, ExcludedPaths, Result<()>)>,
 }
 
 impl<'a> WalkFilesState<'a> {
     pub fn new(resolver: Resolver<'a>) -> Self {
         WalkFilesState {
             resolver: RwLock::new(resolver),
-            merged: std::sync::Mutex::new((Vec::new(), Ok(()))),
+            merged: std::sync::Mutex::new((Vec::new(), Vec::new(), Ok(()))),
         }
     }
 
-    fn finish(self) -> Result<ResolvedFiles> {
-        let (files, error) = self.merged.into_inner().unwrap();
+    fn finish(self) -> Result<FileSearch<'a>> {
+        let (files, excluded, error) = self.merged.into_inner().unwrap();
         error?;
 
-        Ok(files)
+        Ok(FileSearch { files, excluded, resolver: self.resolver.into_inner().unwrap() })
     }
 }
 
@@ -121,12 +244,18 @@ where
     'config: 's,
 {
     fn build(&mut self) -> Box<dyn ignore::ParallelVisitor + 's> {
-        Box::new(FilesVisitor { local_files: vec![], local_error: Ok(()), global: self.state })
+        Box::new(FilesVisitor {
+            local_files: vec![],
+            local_excluded: vec![],
+            local_error: Ok(()),
+            global: self.state,
+        })
     }
 }
 
 pub struct FilesVisitor<'s, 'config> {
     local_files: Vec<Result<ResolvedFile, ignore::Error>>,
+    local_excluded: ExcludedPaths,
     local_error: Result<()>,
     global: &'s WalkFilesState<'config>,
 }
@@ -140,17 +269,13 @@ impl<'s, 'config> ignore::ParallelVisitor for FilesVisitor<'s, 'config> {
                 let resolver = self.global.resolver.read().unwrap();
                 let settings = resolver.resolve(path);
 
-                if let Some(file_name) = path.file_name() {
-                    let file_path = Candidate::new(path);
-                    let file_basename = Candidate::new(file_name);
-                    if match_candidate_exclusion(
-                        &file_path,
-                        &file_basename,
-                        &settings.file_resolver.exclude,
-                    ) {
-                        return WalkState::Skip;
-                    }
-                } else {
+                if path.file_name().is_none() {
+                    return WalkState::Skip;
+                }
+
+                if let Some(exclusion) = path_exclusion(path, settings) {
+                    debug!("Ignored path via {exclusion}: {:?}", path);
+                    self.local_excluded.push((path.to_path_buf(), exclusion));
                     return WalkState::Skip;
                 }
             }
@@ -158,8 +283,19 @@ impl<'s, 'config> ignore::ParallelVisitor for FilesVisitor<'s, 'config> {
 
         match result {
             Ok(entry) => {
-                // Ignore directories
+                // Ignore directories, but discover any configuration file that
+                // they contain before their children are visited.
                 let resolved = if entry.file_type().map_or(true, |ft| ft.is_dir()) {
+                    let path = entry.path();
+                    if self.global.resolver.read().unwrap().needs_discovery(path) {
+                        let result = self.global.resolver.write().unwrap().discover(path);
+                        if let Err(error) = result {
+                            if self.local_error.is_ok() {
+                                self.local_error = Err(error);
+                            }
+                            return WalkState::Quit;
+                        }
+                    }
                     None
                 } else if entry.depth() == 0 {
                     // Accept all files that are passed-in directly.
@@ -193,7 +329,7 @@ impl<'s, 'config> ignore::ParallelVisitor for FilesVisitor<'s, 'config> {
 impl Drop for FilesVisitor<'_, '_> {
     fn drop(&mut self) {
         let mut merged = self.global.merged.lock().unwrap();
-        let (ref mut files, ref mut error) = &mut *merged;
+        let (ref mut files, ref mut excluded, ref mut error) = &mut *merged;
 
         if files.is_empty() {
             *files = std::mem::take(&mut self.local_files);
@@ -201,6 +337,8 @@ impl Drop for FilesVisitor<'_, '_> {
             files.append(&mut self.local_files);
         }
 
+        excluded.append(&mut self.local_excluded);
+
         let local_error = std::mem::replace(&mut self.local_error, Ok(()));
         if error.is_ok() {
             *error = local_error;
@@ -219,31 +357,40 @@ pub fn match_candidate_exclusion(
     exclusion.is_match_candidate(file_path) || exclusion.is_match_candidate(file_basename)
 }
 
-pub fn is_file_excluded(path: &Path, resolver: &Resolver) -> bool {
+/// Check whether the given path itself matches any of the exclusions of the
+/// given [Settings].
+fn path_exclusion(path: &Path, settings: &Settings) -> Option<Exclusion> {
+    let file_path = Candidate::new(path);
+    let file_basename = Candidate::new(path.file_name()?);
+
+    if match_candidate_exclusion(&file_path, &file_basename, &settings.file_resolver.exclude) {
+        Some(Exclusion::Exclude)
+    } else if match_candidate_exclusion(
+        &file_path,
+        &file_basename,
+        &settings.file_resolver.user_exclude,
+    ) {
+        Some(Exclusion::ExtendExclude)
+    } else {
+        None
+    }
+}
+
+/// Find the exclusion of the given path, or any of its ancestors.
+pub fn file_exclusion(path: &Path, resolver: &Resolver) -> Option<Exclusion> {
     for path in path.ancestors() {
-        let settings = resolver.resolve(path);
-        if let Some(file_name) = path.file_name() {
-            let file_path = Candidate::new(path);
-            let file_basename = Candidate::new(file_name);
-            if match_candidate_exclusion(
-                &file_path,
-                &file_basename,
-                &settings.file_resolver.exclude,
-            ) {
-                debug!("Ignored path via `exclude`: {:?}", path);
-                return true;
-            } else if match_candidate_exclusion(
-                &file_path,
-                &file_basename,
-                &settings.file_resolver.user_exclude,
-            ) {
-                return true;
-            }
-        } else {
-            break;
+        path.file_name()?;
+
+        if let Some(exclusion) = path_exclusion(path, resolver.resolve(path)) {
+            debug!("Ignored path via {exclusion}: {:?}", path);
+            return Some(exclusion);
         }
     }
-    false
+    None
+}
+
+pub fn is_file_excluded(path: &Path, resolver: &Resolver) -> bool {
+    file_exclusion(path, resolver).is_some()
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -290,3 +437,96 @@ impl Ord for ResolvedFile {
         self.path().cmp(other.path())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{find_files_in_paths, Discovery, Exclusion};
+    use crate::{configuration::Configuration, settings::FilePattern};
+
+    /// Create a project with a file in each of the `app`, `vendor` and `build`
+    /// directories, returning its root.
+    fn project(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bracketlint-{name}-{}", std::process::id()));
+        for directory in ["app", "vendor", "build"] {
+            std::fs::create_dir_all(root.join(directory)).unwrap();
+            std::fs::write(root.join(directory).join("index.html"), "").unwrap();
+        }
+        root
+    }
+
+    /// Search the given paths with `vendor` excluded by `exclude`, and `build`
+    /// excluded by `extend-exclude`, returning the found files and the
+    /// exclusions relative to the `root`.
+    fn search(
+        root: &Path,
+        paths: &[PathBuf],
+        force_exclude: bool,
+    ) -> (Vec<PathBuf>, Vec<(PathBuf, Exclusion)>) {
+        let settings = Configuration {
+            exclude: Some(vec![FilePattern::user("vendor", root)]),
+            extend_exclude: vec![FilePattern::user("build", root)],
+            force_exclude: Some(force_exclude),
+            ..Configuration::default()
+        }
+        .into_settings()
+        .unwrap();
+
+        let discovery = Discovery::Fixed;
+        let search = find_files_in_paths(paths, &settings, &discovery).unwrap();
+        let relative = |path: PathBuf| path.strip_prefix(root).unwrap().to_path_buf();
+
+        let mut files: Vec<_> =
+            search.files.into_iter().map(|file| relative(file.unwrap().into_path())).collect();
+        files.sort();
+        let mut excluded: Vec<_> = search
+            .excluded
+            .into_iter()
+            .map(|(path, exclusion)| (relative(path), exclusion))
+            .collect();
+        excluded.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+
+        (files, excluded)
+    }
+
+    #[test]
+    fn exclusions_are_attributed_to_their_setting() {
+        let root = project("attribution");
+
+        let (files, excluded) = search(&root, &[root.clone()], false);
+        assert_eq!(files, [Path::new("app/index.html")]);
+        assert_eq!(
+            excluded,
+            [
+                (PathBuf::from("build"), Exclusion::ExtendExclude),
+                (PathBuf::from("vendor"), Exclusion::Exclude)
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn force_exclude_applies_to_explicit_paths() {
+        let root = project("force-exclude");
+        let paths = [root.join("vendor/index.html"), root.join("build/index.html")];
+
+        // Explicitly passed paths are checked, unless exclusions are forced.
+        let (files, excluded) = search(&root, &paths, false);
+        assert_eq!(files, [Path::new("build/index.html"), Path::new("vendor/index.html")]);
+        assert!(excluded.is_empty());
+
+        let (files, excluded) = search(&root, &paths, true);
+        assert!(files.is_empty());
+        assert_eq!(
+            excluded,
+            [
+                (PathBuf::from("build/index.html"), Exclusion::ExtendExclude),
+                (PathBuf::from("vendor/index.html"), Exclusion::Exclude)
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
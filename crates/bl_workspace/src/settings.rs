@@ -1,18 +1,41 @@
 //! Defines all of the settings that a [super::Workspace] can hold.
 
-use std::{ops::Deref, path::PathBuf, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::Result;
-use bl_lints::settings::FixMode;
+use bl_diagnostics::{Applicability, DiagnosticKind};
+use bl_lints::{
+    registry::all_rules,
+    rule::Rule,
+    settings::{Dialect, FixMode, Severity, UnsafeFixes},
+};
+use bl_parse::lexer::Delimiters;
+use bl_utils::hash::StableHasher;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+use crate::configuration::Configuration;
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub enum FilePattern {
     Builtin(&'static str),
     User(String, PathBuf),
 }
 
 impl FilePattern {
+    /// Create a pattern that was specified by the user, relative patterns are
+    /// resolved against the given `root` directory.
+    pub fn user(pattern: impl Into<String>, root: &Path) -> Self {
+        let pattern = pattern.into();
+        let absolute = bl_utils::fs::normalize_path(root.join(&pattern));
+        FilePattern::User(pattern, absolute)
+    }
+
     pub fn add_to(self, builder: &mut GlobSetBuilder) -> Result<()> {
         match self {
             FilePattern::Builtin(pattern) => {
@@ -62,10 +85,26 @@ pub(crate) static EXCLUDE: &[FilePattern] = &[
 
 pub(crate) static INCLUDE: &[FilePattern] = &[
     FilePattern::Builtin("*.html"),
+    FilePattern::Builtin("*.j2"),
     FilePattern::Builtin("*.jinja"),
+    FilePattern::Builtin("*.jinja2"),
+    FilePattern::Builtin("*.liquid"),
+    FilePattern::Builtin("*.njk"),
     FilePattern::Builtin("*.twig"),
 ];
 
+/// The dialects of the file extensions that are specific to a single template
+/// language, the dialect of any other file (i.e. `.html`) is configured with
+/// the `dialect` option.
+pub(crate) static EXTENSIONS: &[(&str, Dialect)] = &[
+    ("j2", Dialect::Jinja),
+    ("jinja", Dialect::Jinja),
+    ("jinja2", Dialect::Jinja),
+    ("liquid", Dialect::Liquid),
+    ("njk", Dialect::Nunjucks),
+    ("twig", Dialect::Twig),
+];
+
 #[derive(Debug, Clone, Default)]
 pub struct FilePatternSet {
     /// The actual set of globs that are used to match files.
@@ -94,6 +133,12 @@ impl FilePatternSet {
     }
 }
 
+impl Hash for FilePatternSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self._set_internals.hash(state);
+    }
+}
+
 impl Deref for FilePatternSet {
     type Target = GlobSet;
 
@@ -129,8 +174,129 @@ impl FileResolverSettings {
     }
 }
 
+/// Rules that are disabled for all of the files that match a pattern.
+#[derive(Debug, Clone, Hash)]
+pub struct PerFileIgnore {
+    /// The files that the rules are disabled for.
+    pub pattern: FilePatternSet,
+
+    /// The codes of the rules that are disabled.
+    pub rules: Vec<&'static str>,
+}
+
+impl PerFileIgnore {
+    /// Check whether the given path matches the pattern.
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.pattern.is_match(path)
+            || path.file_name().is_some_and(|name| self.pattern.is_match(name))
+    }
+}
+
+/// The options of a single rule, as they were written in the configuration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RuleOptions(pub toml::Table);
+
+impl Hash for RuleOptions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_string().hash(state);
+    }
+}
+
+#[derive(Hash)]
 pub struct LinterSettings {
     pub fix_mode: FixMode,
+
+    /// Whether unsafe fixes are applied.
+    pub unsafe_fixes: UnsafeFixes,
+
+    /// The codes of the rules whose unsafe fixes are treated as safe.
+    pub extend_safe_fixes: Vec<&'static str>,
+
+    /// The codes of the rules whose safe fixes are treated as unsafe, these
+    /// take precedence over `extend_safe_fixes`.
+    pub extend_unsafe_fixes: Vec<&'static str>,
+
+    /// Whether preview mode is enabled, which enables preview rules.
+    pub preview: bool,
+
+    /// The codes of the rules that are enabled, in the order of the registry.
+    pub rules: Vec<&'static str>,
+
+    /// Rules that are disabled for particular files.
+    pub per_file_ignores: Vec<PerFileIgnore>,
+
+    /// The severity of the rules whose severity has been overridden, rules
+    /// that are turned off are not within `rules`.
+    pub severity: BTreeMap<&'static str, Severity>,
+
+    /// The template language of files whose extension isn't specific to a
+    /// single template language.
+    pub dialect: Dialect,
+
+    /// The template language of files with each extension, these take
+    /// precedence over the built-in [EXTENSIONS].
+    pub extension: BTreeMap<String, Dialect>,
+
+    /// The delimiters that templates use.
+    pub delimiters: Delimiters,
+
+    /// The maximum length of a line.
+    pub line_length: usize,
+
+    /// Options of individual rules, keyed by the code of the rule.
+    pub rule_options: BTreeMap<&'static str, RuleOptions>,
+}
+
+impl LinterSettings {
+    /// Get the applicability of a fix of the rule with the given code, after
+    /// it has been promoted or demoted by the settings. Display-only fixes are
+    /// never changed.
+    pub fn fix_applicability(&self, code: &str, applicability: Applicability) -> Applicability {
+        if applicability == Applicability::DisplayOnly {
+            applicability
+        } else if self.extend_unsafe_fixes.contains(&code) {
+            Applicability::Unsafe
+        } else if self.extend_safe_fixes.contains(&code) {
+            Applicability::Safe
+        } else {
+            applicability
+        }
+    }
+
+    /// Get the kind of the diagnostics of the rule with the given code, after
+    /// any override of its severity.
+    pub fn diagnostic_kind(&self, code: &str, kind: DiagnosticKind) -> DiagnosticKind {
+        self.severity.get(code).and_then(|severity| severity.kind()).unwrap_or(kind)
+    }
+
+    /// Get the template language of the file at the given path.
+    pub fn dialect_for(&self, path: &Path) -> Dialect {
+        let Some(extension) = path.extension().and_then(|extension| extension.to_str()) else {
+            return self.dialect;
+        };
+
+        self.extension
+            .get(extension)
+            .or_else(|| {
+                EXTENSIONS.iter().find(|(ext, _)| *ext == extension).map(|(_, dialect)| dialect)
+            })
+            .copied()
+            .unwrap_or(self.dialect)
+    }
+
+    /// Get all of the rules that should be run on the file at the given path.
+    pub fn rules_for(&self, path: &Path) -> Vec<&'static dyn Rule> {
+        let ignored = self
+            .per_file_ignores
+            .iter()
+            .filter(|ignore| ignore.is_match(path))
+            .flat_map(|ignore| ignore.rules.iter().copied())
+            .collect::<Vec<_>>();
+
+        all_rules()
+            .filter(|rule| self.rules.contains(&rule.code()) && !ignored.contains(&rule.code()))
+            .collect()
+    }
 }
 
 pub struct Settings {
@@ -142,12 +308,25 @@ pub struct Settings {
     pub linter_settings: LinterSettings,
 }
 
+impl Default for Settings {
+    fn default() -> Self {
+        // The default configuration has no user patterns or selectors, so it
+        // can always be converted.
+        Configuration::default().into_settings().unwrap()
+    }
+}
+
 impl Settings {
-    pub fn new(respect_gitignore: bool, fix_mode: FixMode) -> Self {
-        Settings {
-            respect_gitignore,
-            file_resolver: FileResolverSettings::new(),
-            linter_settings: LinterSettings { fix_mode },
-        }
+    /// Compute a key of all of the settings that affect the diagnostics of a
+    /// file, this is used to invalidate cached diagnostics when the settings
+    /// change.
+    ///
+    /// ##Note: the key is stable between builds, but the settings themselves
+    /// may change between versions of `bracketlint`, so the cache is also
+    /// keyed by version.
+    pub fn cache_key(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        self.linter_settings.hash(&mut hasher);
+        hasher.finish()
     }
 }